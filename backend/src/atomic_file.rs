@@ -0,0 +1,14 @@
+use std::{io::Result, path::Path};
+
+use tokio::{fs::File, io::AsyncWriteExt};
+
+/// Replaces the file at `path` with `data`, through a temporary file next to
+/// it, so a crash leaves either the old contents or the new ones.
+pub async fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut tmp_file = File::create(&tmp_path).await?;
+    tmp_file.write_all(data).await?;
+    tmp_file.flush().await?;
+    tmp_file.sync_all().await?;
+    tokio::fs::rename(tmp_path, path).await
+}
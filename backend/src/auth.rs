@@ -1,6 +1,8 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use axum::{
     Form,
-    extract::{FromRequestParts, State},
+    extract::{FromRequestParts, Path, State},
     http::{StatusCode, request::Parts},
     response::{IntoResponse, Redirect, Response},
 };
@@ -8,7 +10,10 @@ use axum_extra::extract::{CookieJar, cookie::Cookie};
 use serde::Deserialize;
 use tracing::info;
 
-use crate::{ServerState, token::UserData};
+use crate::{
+    ServerState,
+    token::{Key, ShareGrant, UserData},
+};
 
 impl FromRequestParts<ServerState> for UserData {
     type Rejection = Response;
@@ -49,3 +54,34 @@ pub async fn login(
     let cookie = Cookie::new("auth", token);
     (jar.add(cookie), Redirect::to("/")).into_response()
 }
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Share links are kept in a cookie scoped to the room they were opened for,
+/// so the room's websocket can pick them up.
+pub fn share_grant(jar: &CookieJar, room_id: &str, key: &Key) -> Option<ShareGrant> {
+    let token = jar.get("share")?;
+    key.get_share_grant(token.value_trimmed(), now_secs())
+        .ok()
+        .filter(|grant| grant.room == room_id)
+}
+
+pub async fn open_share_link(
+    State(state): State<ServerState>,
+    Path((room_id, token)): Path<(String, String)>,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    let grant = state.auth_key.get_share_grant(&token, now_secs());
+    if !grant.is_ok_and(|grant| grant.room == room_id) {
+        return (StatusCode::FORBIDDEN, "Invalid share link").into_response();
+    }
+    let cookie = Cookie::build(("share", token))
+        .path(format!("/test/{room_id}"))
+        .build();
+    (jar.add(cookie), Redirect::to(&format!("/test/{room_id}"))).into_response()
+}
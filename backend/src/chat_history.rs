@@ -0,0 +1,60 @@
+use std::{
+    collections::VecDeque,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use backend_commons::chat::ChatMessage;
+use tokio::fs::{read, try_exists};
+
+use crate::atomic_file::write_atomically;
+
+/// The most recent chat messages of a room, kept next to its event store.
+/// Older messages are dropped once `limit` is reached.
+pub struct ChatHistory {
+    path: PathBuf,
+    limit: usize,
+    messages: VecDeque<ChatMessage>,
+}
+
+impl ChatHistory {
+    pub async fn open(path: &Path, limit: usize) -> Result<Self> {
+        let path = path.join("chat");
+        let mut messages: VecDeque<ChatMessage> = if try_exists(&path).await? {
+            let data = read(&path).await?;
+            postcard::from_bytes(&data).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Failed to deserialize chat history: {e}"),
+                )
+            })?
+        } else {
+            VecDeque::new()
+        };
+        while messages.len() > limit {
+            messages.pop_front();
+        }
+        Ok(Self {
+            path,
+            limit,
+            messages,
+        })
+    }
+
+    pub fn list(&self) -> Vec<ChatMessage> {
+        self.messages.iter().cloned().collect()
+    }
+
+    pub async fn push(&mut self, message: ChatMessage) -> Result<()> {
+        if self.messages.len() == self.limit {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message);
+        self.save().await
+    }
+
+    async fn save(&self) -> Result<()> {
+        let data = postcard::to_stdvec(&self.messages).unwrap();
+        write_atomically(&self.path, &data).await
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use backend_commons::checkpoint::Checkpoint;
+use tokio::fs::{read, try_exists};
+
+use crate::atomic_file::write_atomically;
+
+/// Catalogue of named positions in a room's event store, kept next to its
+/// `wal` and `snapshot` directories.
+pub struct Checkpoints {
+    path: PathBuf,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl Checkpoints {
+    pub async fn open(path: &Path) -> Result<Self> {
+        let path = path.join("checkpoints");
+        let checkpoints = if try_exists(&path).await? {
+            let data = read(&path).await?;
+            postcard::from_bytes(&data).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Failed to deserialize checkpoints: {e}"),
+                )
+            })?
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, checkpoints })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Checkpoint> {
+        self.checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.name == name)
+    }
+
+    pub fn list(&self) -> Vec<Checkpoint> {
+        self.checkpoints.clone()
+    }
+
+    /// Adds a checkpoint, replacing any previous one with the same name.
+    pub async fn insert(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        self.checkpoints.retain(|c| c.name != checkpoint.name);
+        self.checkpoints.push(checkpoint);
+        self.save().await
+    }
+
+    pub async fn remove(&mut self, name: &str) -> Result<bool> {
+        let len = self.checkpoints.len();
+        self.checkpoints.retain(|c| c.name != name);
+        if self.checkpoints.len() == len {
+            return Ok(false);
+        }
+        self.save().await?;
+        Ok(true)
+    }
+
+    async fn save(&self) -> Result<()> {
+        let data = postcard::to_stdvec(&self.checkpoints).unwrap();
+        write_atomically(&self.path, &data).await
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    io::Result,
+    path::{Component, Path, PathBuf},
+};
+
+use tokio::fs::{create_dir_all, rename, try_exists};
+
+/// Kind of the rooms of the test board, next to those of the games.
+const TEST_KIND: &str = "test";
+
+fn root() -> PathBuf {
+    PathBuf::from("data")
+}
+
+/// Whether `room_id` is a single plain path component, which keeps its
+/// files inside the directory of its kind.
+pub fn is_room_id(room_id: &str) -> bool {
+    let mut components = Path::new(room_id).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !room_id.contains(['/', '\\'])
+}
+
+/// Files of a room of `kind`, such as a [`game_back::game::Game::KIND`].
+/// Every kind has a directory of its own, so room names cannot collide with
+/// each other or with the server-wide stores.
+pub fn room(kind: &str, room_id: &str) -> PathBuf {
+    root().join("rooms").join(kind).join(room_id)
+}
+
+/// Files of a test room, moved there from where they were kept before rooms
+/// had a directory of their own.
+pub async fn test_room(room_id: &str) -> Result<PathBuf> {
+    let path = room(TEST_KIND, room_id);
+    let legacy = root().join(room_id);
+    let is_reserved = matches!(room_id, "rooms" | "meta");
+    if !is_reserved && !try_exists(&path).await? && try_exists(legacy.join("wal")).await? {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).await?;
+        }
+        rename(legacy, &path).await?;
+    }
+    Ok(path)
+}
+
+/// Directory of the server-wide stores, such as the match history.
+pub fn meta() -> PathBuf {
+    root().join("meta")
+}
@@ -1,11 +1,10 @@
 use std::{
-    fmt::write,
-    io::{ErrorKind, Result},
+    io::{Error, ErrorKind, Result},
     path::{Path, PathBuf},
     vec,
 };
 
-use backend_commons::store::{StateBuilder, Store};
+use backend_commons::store::{EventPosition, StateBuilder, Store};
 use tokio::{
     fs::{File, OpenOptions, create_dir_all, read_dir, try_exists},
     io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter},
@@ -21,6 +20,7 @@ pub struct EventStore {
     wal: Wal,
     path: PathBuf,
     log_index: u64,
+    event_count: u64,
     version: u64,
 }
 
@@ -33,6 +33,10 @@ fn parse_snapshot_index(file_name: &str) -> Option<u64> {
 }
 
 async fn last_snapshot_index(path: &Path) -> Result<Option<u64>> {
+    last_snapshot_index_before(path, u64::MAX).await
+}
+
+async fn last_snapshot_index_before(path: &Path, log_index: u64) -> Result<Option<u64>> {
     let snapshot_dir_path = path.join("snapshot");
     create_dir_all(&snapshot_dir_path).await?;
     let mut snapshot_dir = read_dir(&snapshot_dir_path).await?;
@@ -42,11 +46,12 @@ async fn last_snapshot_index(path: &Path) -> Result<Option<u64>> {
             .path()
             .file_name()
             .and_then(|filename| filename.to_str())
-            .and_then(|filename_str| parse_snapshot_index(filename_str));
-        if let Some(idx) = idx {
-            if last_index.is_none() || idx > last_index.unwrap() {
-                last_index = Some(idx);
-            }
+            .and_then(parse_snapshot_index);
+        if let Some(idx) = idx
+            && idx < log_index
+            && last_index.is_none_or(|last| idx > last)
+        {
+            last_index = Some(idx);
         }
     }
     Ok(last_index)
@@ -62,11 +67,11 @@ async fn last_log_index(path: &Path) -> Result<Option<u64>> {
             .path()
             .file_name()
             .and_then(|filename| filename.to_str())
-            .and_then(|filename_str| parse_log_index(filename_str));
-        if let Some(idx) = idx {
-            if last_index.is_none() || idx > last_index.unwrap() {
-                last_index = Some(idx);
-            }
+            .and_then(parse_log_index);
+        if let Some(idx) = idx
+            && last_index.is_none_or(|last| idx > last)
+        {
+            last_index = Some(idx);
         }
     }
     Ok(last_index)
@@ -89,12 +94,20 @@ impl EventStore {
         state.load_state(version, content)
     }
 
-    async fn apply_events(state: &mut impl StateBuilder, path: &Path, log_idx: u64) -> Result<()> {
+    /// Replays at most `limit` events of the given log segment and returns how
+    /// many were applied.
+    async fn apply_events(
+        state: &mut impl StateBuilder,
+        path: &Path,
+        log_idx: u64,
+        limit: u64,
+    ) -> Result<u64> {
         let log_file_path = path.join("wal").join(format!("{log_idx:020}.log"));
         let log_file = File::open(log_file_path).await?;
         let mut reader = BufReader::new(log_file);
         let version = reader.read_u64_le().await?;
-        loop {
+        let mut count = 0;
+        while count < limit {
             let len = match reader.read_u32_le().await {
                 Ok(len) => len,
                 Err(error) => {
@@ -108,8 +121,9 @@ impl EventStore {
             let mut event_buf = vec![0u8; len as usize];
             reader.read_exact(&mut event_buf).await?;
             state.load_event(version, event_buf)?;
+            count += 1;
         }
-        Ok(())
+        Ok(count)
     }
 
     async fn get_log_version(path: &Path, id: u64) -> Result<u64> {
@@ -131,22 +145,25 @@ impl EventStore {
             Self::read_state(path, snapshot_idx, state).await?;
         }
 
+        // A snapshot already contains every event of the log segment it was
+        // taken in, so replaying starts with the segment after it.
+        let mut last_log_events = 0;
         if let Some(log_index) = log_index {
-            for i in snapshot_index.unwrap_or(0)..=log_index {
-                Self::apply_events(state, path, i).await?;
+            for i in snapshot_index.map_or(0, |idx| idx + 1)..=log_index {
+                last_log_events = Self::apply_events(state, path, i, u64::MAX).await?;
             }
         }
 
-        let log_index = match log_index {
+        let (log_index, event_count) = match log_index {
             Some(idx) => {
                 let log_version = Self::get_log_version(path, idx).await?;
                 if log_version == current_version {
-                    idx
+                    (idx, last_log_events)
                 } else {
-                    idx + 1
+                    (idx + 1, 0)
                 }
             }
-            None => 0,
+            None => (0, 0),
         };
 
         let wal = Wal::open(
@@ -160,23 +177,49 @@ impl EventStore {
 
         Ok(Self {
             log_index,
+            event_count,
             wal,
             path: path.to_path_buf(),
             version: current_version,
         })
     }
 
+    /// Replays the store at `path` into `state` without opening it for
+    /// writing, so it can be read while its room is running. Events the
+    /// room has not flushed yet are missing.
+    pub async fn read(path: &Path, state: &mut impl StateBuilder) -> Result<()> {
+        if !try_exists(path.join("wal")).await? {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("No store at {}", path.display()),
+            ));
+        }
+        let snapshot_index = last_snapshot_index(path).await?;
+        if let Some(snapshot_idx) = snapshot_index {
+            Self::read_state(path, snapshot_idx, state).await?;
+        }
+        if let Some(log_index) = last_log_index(path).await? {
+            for i in snapshot_index.map_or(0, |idx| idx + 1)..=log_index {
+                Self::apply_events(state, path, i, u64::MAX).await?;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn flush(&mut self) -> Result<()> {
         self.wal.flush().await
     }
 
     pub async fn append(&mut self, event: &[u8]) -> Result<()> {
-        self.wal.append(event).await
+        self.wal.append(event).await?;
+        self.event_count += 1;
+        Ok(())
     }
 
     pub async fn next_log(&mut self) -> Result<()> {
         self.flush().await?;
         self.log_index += 1;
+        self.event_count = 0;
         self.wal = Wal::open(
             self.path
                 .join("wal")
@@ -192,6 +235,47 @@ impl EventStore {
     pub fn get_current_log_size(&self) -> u64 {
         self.wal.get_size()
     }
+
+    pub fn position(&self) -> EventPosition {
+        EventPosition {
+            log: self.log_index,
+            event: self.event_count,
+        }
+    }
+
+    /// Rebuilds the state as it was at `position`, starting from the last
+    /// snapshot taken before that log segment.
+    pub async fn load_at(
+        &mut self,
+        position: EventPosition,
+        state: &mut impl StateBuilder,
+    ) -> Result<()> {
+        if position > self.position() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Position {position:?} is in the future"),
+            ));
+        }
+        self.flush().await?;
+
+        let snapshot_index = last_snapshot_index_before(&self.path, position.log).await?;
+        if let Some(snapshot_idx) = snapshot_index {
+            Self::read_state(&self.path, snapshot_idx, state).await?;
+        }
+
+        for i in snapshot_index.map_or(0, |idx| idx + 1)..position.log {
+            Self::apply_events(state, &self.path, i, u64::MAX).await?;
+        }
+
+        let applied = Self::apply_events(state, &self.path, position.log, position.event).await?;
+        if applied < position.event {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Position {position:?} is past the end of its log"),
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl Wal {
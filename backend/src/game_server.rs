@@ -0,0 +1,441 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    marker::PhantomData,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use backend_commons::{
+    chat::{ChatListMessage, ChatMessage, MAX_CHAT_MESSAGE_LENGTH},
+    client_info::{ClientData, ClientListMessage, ClientMessage, ClientRole},
+};
+use game_back::{
+    ToClient, ToServer,
+    clock::TimeControl,
+    game::{Game, GameState, Match, Outcome, bot_id, is_bot},
+    history::{MatchSummary, Player},
+    store::{CURRENT_VERSION, MatchEvent, MatchLoader, MatchStore},
+};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::{
+    chat_history::ChatHistory,
+    data_dir,
+    event_store::EventStore,
+    match_history::SharedHistory,
+    socket_endpoint::{Bot, Bots, Client, SocketHandler},
+    token::UserData,
+    user_store::SharedUsers,
+};
+
+const LOG_FILE_SIZE_LIMIT: u64 = 1024;
+const CHAT_HISTORY_LIMIT: usize = 200;
+const BOT_NAME: &str = "Computer";
+
+/// Milliseconds since the Unix epoch, which match clocks run on.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Computer player in one seat, playing [`Game::bot_move`] whenever its
+/// view says it is its turn.
+struct GameBot<G: Game> {
+    seat: usize,
+    game: PhantomData<G>,
+}
+
+impl<G: Game> Bot<ToClient<G::View>, ToServer<G::Move>> for GameBot<G> {
+    fn on_message(&mut self, message: ToClient<G::View>) -> Vec<ToServer<G::Move>> {
+        let ToClient::State(GameState {
+            view,
+            seats,
+            seat,
+            to_move,
+            ..
+        }) = message
+        else {
+            return Vec::new();
+        };
+        // It waits for an opponent.
+        if seats.iter().any(Option::is_none) || seat != Some(self.seat) || to_move != seat {
+            return Vec::new();
+        }
+        G::bot_move(&view, self.seat)
+            .map(ToServer::Move)
+            .into_iter()
+            .collect()
+    }
+}
+
+type GameBots<G> = Bots<ToClient<<G as Game>::View>, ToServer<<G as Game>::Move>, ()>;
+
+/// Room hosting one match of `G`. Seated users play, everyone else watches.
+pub struct GameRoom<G: Game> {
+    clients: HashMap<u64, Client<ToClient<G::View>>>,
+    bots: Option<GameBots<G>>,
+    game: Match<G>,
+    name: String,
+    store: MatchStore<EventStore>,
+    chat: ChatHistory,
+    history: SharedHistory,
+    users: SharedUsers,
+    /// Names of everyone who joined, for the players of the match.
+    names: HashMap<u64, String>,
+}
+
+impl<G: Game> GameRoom<G> {
+    /// Opens the room's match, or starts one with `settings` and `control`
+    /// if there is none.
+    pub async fn new(
+        name: String,
+        settings: G::Settings,
+        control: Option<TimeControl>,
+        history: SharedHistory,
+        users: SharedUsers,
+    ) -> Self {
+        let path = data_dir::room(G::KIND, &name);
+        let mut loader = MatchLoader::<G>::new(settings, control);
+        let store = EventStore::open(&path, CURRENT_VERSION, &mut loader)
+            .await
+            .unwrap();
+        let chat = ChatHistory::open(&path, CHAT_HISTORY_LIMIT).await.unwrap();
+
+        let is_new = loader.is_new();
+        let game = loader.get_match();
+        let mut store = MatchStore::new(store);
+        // The settings are only part of snapshots.
+        if is_new {
+            store.snapshot(&game).await.unwrap();
+        }
+
+        Self {
+            clients: HashMap::new(),
+            bots: None,
+            game,
+            name,
+            store,
+            chat,
+            history,
+            users,
+            names: HashMap::new(),
+        }
+    }
+
+    async fn broadcast(&mut self, message: ToClient<G::View>) {
+        for client in self.clients.values_mut() {
+            client.send(message.clone()).await;
+        }
+    }
+
+    /// Sends everyone the match as seen from their seat.
+    async fn send_states(&mut self) {
+        let now = now();
+        for (&id, client) in self.clients.iter_mut() {
+            client
+                .send(ToClient::State(self.game.state_for(id, now)))
+                .await;
+        }
+    }
+
+    /// Seated users play, everyone else watches.
+    fn seat_and_role(&self, client_id: u64) -> (Option<usize>, ClientRole) {
+        let seat = self.game.seat_of(client_id);
+        let role = match seat {
+            Some(_) => ClientRole::Player,
+            None => ClientRole::Spectator,
+        };
+        (seat, role)
+    }
+
+    fn client_data(&self, client_id: u64, name: String) -> ClientData {
+        let (seat, role) = self.seat_and_role(client_id);
+        ClientData {
+            id: client_id,
+            name,
+            ping: 0,
+            seat,
+            role,
+        }
+    }
+
+    /// Tells everyone the seat and role of every client.
+    async fn send_seats(&mut self) {
+        let ids = self.clients.keys().copied().collect::<Vec<_>>();
+        for id in ids {
+            let (seat, role) = self.seat_and_role(id);
+            self.broadcast(ToClient::ClientListMessage(ClientListMessage::Update(
+                id,
+                ClientMessage::Seat { seat, role },
+            )))
+            .await;
+        }
+    }
+
+    async fn reject(&mut self, client_id: u64, error: impl Display) {
+        if let Some(client) = self.clients.get_mut(&client_id) {
+            client.send(ToClient::Rejected(error.to_string())).await;
+        }
+    }
+
+    /// Stores an event the match has applied, or tells the author why it
+    /// was refused.
+    async fn commit(&mut self, client_id: u64, event: Result<MatchEvent<G::Move>, impl Display>) {
+        match event {
+            Ok(event) => self.store_event(event).await,
+            Err(error) => self.reject(client_id, error).await,
+        }
+    }
+
+    /// Persists an event the match has applied and shows its result to
+    /// everyone.
+    async fn store_event(&mut self, event: MatchEvent<G::Move>) {
+        self.store.apply_event(&event).await.unwrap();
+        let sat = matches!(event, MatchEvent::Sat { .. });
+        if let Some(outcome) = self.game.outcome()
+            && !sat
+        {
+            info!("Room {} {} ended: {:?}", G::KIND, self.name, outcome);
+            self.record(outcome).await;
+        }
+        self.send_states().await;
+        if sat {
+            self.send_seats().await;
+        }
+    }
+
+    /// Adds the match that just ended to its players' histories. The store
+    /// is flushed first, so the match can be exported right away.
+    async fn record(&mut self, outcome: Outcome) {
+        self.store.store.flush().await.unwrap();
+        let players = self
+            .game
+            .seats()
+            .iter()
+            .map(|user| {
+                user.map(|id| Player {
+                    id,
+                    name: self
+                        .names
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("#{id}")),
+                })
+            })
+            .collect();
+        let summary = MatchSummary {
+            kind: G::KIND.to_owned(),
+            room: self.name.clone(),
+            players,
+            outcome,
+            moves: self.game.moves().len(),
+            finished_at: now(),
+        };
+        if let Err(error) = self.history.lock().await.record(&summary).await {
+            warn!("Room {} cannot record its match: {}", self.name, error);
+        }
+        self.rate(&summary).await;
+    }
+
+    /// Updates the players' ratings after a rated match: one between two
+    /// people in which both sides moved.
+    async fn rate(&mut self, summary: &MatchSummary) {
+        let [Some(first), Some(second)] = &summary.players[..] else {
+            return;
+        };
+        if is_bot(first.id) || is_bot(second.id) || summary.moves < 2 {
+            return;
+        }
+        let players = [(first.id, &*first.name), (second.id, &*second.name)];
+        let rated = self
+            .users
+            .lock()
+            .await
+            .rate(G::KIND, players, summary.outcome)
+            .await;
+        if let Err(error) = rated {
+            warn!("Room {} cannot rate its match: {}", self.name, error);
+        }
+    }
+
+    /// Ends the match if the side to move ran out of time.
+    async fn check_flag(&mut self) {
+        if let Some(event) = self.game.flag(now()) {
+            self.store_event(event).await;
+        }
+    }
+
+    fn attach_bot(&self, seat: usize) {
+        if let Some(bots) = &self.bots {
+            let user_data = UserData {
+                id: bot_id(seat),
+                username: BOT_NAME.to_owned(),
+            };
+            bots.attach(
+                user_data,
+                GameBot::<G> {
+                    seat,
+                    game: PhantomData,
+                },
+            );
+        }
+    }
+
+    async fn on_chat(&mut self, client_id: u64, text: String) {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+            return;
+        }
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        let message = ChatMessage {
+            author: client_id,
+            author_name: client.get_user_data().username,
+            text: text.to_owned(),
+            sent_at: now(),
+        };
+        if let Err(error) = self.chat.push(message.clone()).await {
+            warn!("Room {} cannot store chat message: {}", self.name, error);
+        }
+        self.broadcast(ToClient::ChatListMessage(ChatListMessage::Added(message)))
+            .await;
+    }
+}
+
+impl<G: Game> SocketHandler<ToClient<G::View>, ToServer<G::Move>, ()> for GameRoom<G> {
+    async fn on_connect(&mut self, mut client: Client<ToClient<G::View>>) {
+        let id = client.get_id();
+        let user_data = client.get_user_data();
+        self.names.insert(id, user_data.username.clone());
+        client
+            .send(ToClient::State(self.game.state_for(id, now())))
+            .await;
+        client
+            .send(ToClient::ChatListMessage(ChatListMessage::Set(
+                self.chat.list(),
+            )))
+            .await;
+        let present = self
+            .clients
+            .values()
+            .map(|present| self.client_data(present.get_id(), present.get_user_data().username))
+            .collect::<Vec<_>>();
+        for data in present {
+            client
+                .send(ToClient::ClientListMessage(ClientListMessage::Joined(data)))
+                .await;
+        }
+        self.clients.insert(id, client);
+        let data = self.client_data(id, user_data.username);
+        self.broadcast(ToClient::ClientListMessage(ClientListMessage::Joined(data)))
+            .await;
+    }
+
+    async fn on_message(&mut self, client_id: u64, message: ToServer<G::Move>) {
+        // A move in the same instant as the flag falls is too late.
+        self.check_flag().await;
+        match message {
+            ToServer::Chat(text) => self.on_chat(client_id, text).await,
+            ToServer::Sit(seat) => {
+                let event = self.game.sit(client_id, seat);
+                self.commit(client_id, event).await;
+            }
+            ToServer::Stand => {
+                let event = self.game.stand(client_id);
+                self.commit(client_id, event).await;
+            }
+            ToServer::Move(played) => {
+                let event = self.game.play(client_id, played, now());
+                self.commit(client_id, event).await;
+            }
+            ToServer::Resign => {
+                let event = self.game.resign(client_id, now());
+                self.commit(client_id, event).await;
+            }
+            ToServer::AddBot(seat) => {
+                let event = self.game.add_bot(seat);
+                let added = event.is_ok();
+                self.commit(client_id, event).await;
+                if added {
+                    self.attach_bot(seat);
+                }
+            }
+            ToServer::RemoveBot(seat) => {
+                let event = self.game.remove_bot(seat);
+                let removed = event.is_ok();
+                self.commit(client_id, event).await;
+                // Its task ends with the client, which disconnects it.
+                if removed {
+                    self.clients.remove(&bot_id(seat));
+                }
+            }
+        }
+    }
+
+    /// Players keep their seat while away, so they can come back to the match.
+    async fn on_disconnect(&mut self, client_id: u64) {
+        self.clients.remove(&client_id);
+        self.broadcast(ToClient::ClientListMessage(ClientListMessage::Quit(
+            client_id,
+        )))
+        .await;
+    }
+
+    async fn tick(&mut self) {
+        if self.store.store.get_current_log_size() > LOG_FILE_SIZE_LIMIT {
+            self.store.snapshot(&self.game).await.unwrap();
+        } else {
+            self.store.store.flush().await.unwrap();
+        }
+        for client in self.clients.values_mut() {
+            client.ping().await;
+        }
+    }
+
+    async fn on_ping(&mut self, client_id: u64, data: axum::body::Bytes) {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        client.pong(data).await
+    }
+
+    async fn on_pong(&mut self, client_id: u64, timestamp: u128) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let ping = now.saturating_sub(timestamp) as u32;
+        self.broadcast(ToClient::ClientListMessage(ClientListMessage::Update(
+            client_id,
+            ClientMessage::Ping(ping),
+        )))
+        .await;
+    }
+
+    async fn on_internal_message(&mut self, _: ()) {}
+
+    /// Flag-fall, which cannot wait for the next tick.
+    fn next_wakeup(&self) -> Option<Instant> {
+        let flag_at = self.game.flag_at()?;
+        Some(Instant::now() + Duration::from_millis(flag_at.saturating_sub(now())))
+    }
+
+    async fn on_wakeup(&mut self) {
+        self.check_flag().await;
+    }
+
+    /// Brings back the computer players of a reopened match.
+    fn set_bots(&mut self, bots: GameBots<G>) {
+        self.bots = Some(bots);
+        let seats = self.game.seats().to_vec();
+        for (seat, user) in seats.into_iter().enumerate() {
+            if user.is_some_and(is_bot) {
+                self.attach_bot(seat);
+            }
+        }
+    }
+}
@@ -1,38 +1,79 @@
+mod atomic_file;
 mod auth;
+mod chat_history;
+mod checkpoints;
+mod data_dir;
 mod event_store;
+mod game_server;
+mod match_history;
 mod menu_server;
+mod replays;
 mod socket_endpoint;
 mod test_server;
 mod token;
+mod user_store;
 
 use std::{collections::HashMap, sync::Arc};
 
 use axum::{
     Router,
-    extract::{Path, Request, State, WebSocketUpgrade},
+    extract::{Path, Query, Request, State, WebSocketUpgrade},
+    http::StatusCode,
     middleware::{Next, from_fn_with_state},
-    response::{Redirect, Response},
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
 };
+use axum_extra::extract::CookieJar;
+use game_back::{
+    checkers::{Checkers, CheckersSettings},
+    chess::{Chess, ChessSettings},
+    clock::ClockSettings,
+    connect_four::{ConnectFour, ConnectFourSettings},
+    game::Game,
+    go::{Go, GoSettings},
+};
 use menu_back::{ToClient, ToServer};
 use tokio::sync::Mutex;
 use tower_http::services::{ServeDir, ServeFile};
 use tracing::info;
 
 use crate::{
-    auth::login,
+    auth::{login, open_share_link, share_grant},
+    game_server::GameRoom,
+    match_history::{MatchHistory, SharedHistory},
     menu_server::{Menu, MenuMessage},
+    replays::{export, own_history, replay, user_history},
     socket_endpoint::SocketEndpoint,
     test_server::Test,
     token::{Key, UserData},
+    user_store::{SharedUsers, UserStore},
 };
 
+type GameRooms<G> = Arc<
+    Mutex<
+        HashMap<
+            String,
+            SocketEndpoint<
+                game_back::ToClient<<G as Game>::View>,
+                game_back::ToServer<<G as Game>::Move>,
+                (),
+            >,
+        >,
+    >,
+>;
+
 #[derive(Clone)]
 struct ServerState {
     auth_key: Key,
     menu: Arc<Mutex<SocketEndpoint<ToClient, ToServer, MenuMessage>>>,
     test_rooms:
         Arc<Mutex<HashMap<String, SocketEndpoint<test_back::ToClient, test_back::ToServer, ()>>>>,
+    chess_rooms: GameRooms<Chess>,
+    go_rooms: GameRooms<Go>,
+    connect_four_rooms: GameRooms<ConnectFour>,
+    checkers_rooms: GameRooms<Checkers>,
+    history: SharedHistory,
+    users: SharedUsers,
 }
 
 async fn ws(
@@ -52,23 +93,151 @@ async fn test_ws(
     ws: WebSocketUpgrade,
     Path(room_id): Path<String>,
     user_data: UserData,
+    jar: CookieJar,
     State(state): State<ServerState>,
 ) -> Response {
+    if !data_dir::is_room_id(&room_id) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let grant = share_grant(&jar, &room_id, &state.auth_key);
     let mut rooms = state.test_rooms.lock().await;
     let menu = state.menu.lock().await;
     if rooms.contains_key(&room_id) {
         let room = rooms.get(&room_id).unwrap();
-        room.handler(ws, user_data)
+        room.handler_with_grant(ws, user_data, grant)
     } else {
-        let test = Test::new(room_id.clone()).await;
+        let test = Test::new(room_id.clone(), state.auth_key.clone()).await;
         let room = SocketEndpoint::new(test);
-        let handler = room.handler(ws, user_data);
+        let handler = room.handler_with_grant(ws, user_data, grant);
         rooms.insert(room_id.clone(), room);
         menu.send_internal_message(MenuMessage::ServerCreated(room_id.clone()));
         handler
     }
 }
 
+/// Connects to a game room, opening it with `settings` and the time control
+/// in `clock` if nobody is in it.
+async fn join_game<G: Game>(
+    state: &ServerState,
+    rooms: &GameRooms<G>,
+    ws: WebSocketUpgrade,
+    room_id: String,
+    user_data: UserData,
+    settings: G::Settings,
+    clock: ClockSettings,
+) -> Response {
+    if !clock.is_valid() || !data_dir::is_room_id(&room_id) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let mut rooms = rooms.lock().await;
+    if let Some(room) = rooms.get(&room_id) {
+        return room.handler(ws, user_data);
+    }
+    let room = SocketEndpoint::new(
+        GameRoom::<G>::new(
+            room_id.clone(),
+            settings,
+            clock.time_control(),
+            state.history.clone(),
+            state.users.clone(),
+        )
+        .await,
+    );
+    let handler = room.handler(ws, user_data);
+    rooms.insert(room_id.clone(), room);
+    let menu = state.menu.lock().await;
+    menu.send_internal_message(MenuMessage::ServerCreated(format!(
+        "{}/{}",
+        G::KIND,
+        room_id
+    )));
+    handler
+}
+
+async fn chess_ws(
+    ws: WebSocketUpgrade,
+    Path(room_id): Path<String>,
+    Query(clock): Query<ClockSettings>,
+    user_data: UserData,
+    State(state): State<ServerState>,
+) -> Response {
+    join_game::<Chess>(
+        &state,
+        &state.chess_rooms,
+        ws,
+        room_id,
+        user_data,
+        ChessSettings::default(),
+        clock,
+    )
+    .await
+}
+
+/// Settings such as the board size come from the query, like `?size=9`, and
+/// only matter for a new room.
+async fn go_ws(
+    ws: WebSocketUpgrade,
+    Path(room_id): Path<String>,
+    Query(settings): Query<GoSettings>,
+    Query(clock): Query<ClockSettings>,
+    user_data: UserData,
+    State(state): State<ServerState>,
+) -> Response {
+    if !settings.is_valid() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    join_game::<Go>(
+        &state,
+        &state.go_rooms,
+        ws,
+        room_id,
+        user_data,
+        settings,
+        clock,
+    )
+    .await
+}
+
+async fn connect_four_ws(
+    ws: WebSocketUpgrade,
+    Path(room_id): Path<String>,
+    Query(clock): Query<ClockSettings>,
+    user_data: UserData,
+    State(state): State<ServerState>,
+) -> Response {
+    join_game::<ConnectFour>(
+        &state,
+        &state.connect_four_rooms,
+        ws,
+        room_id,
+        user_data,
+        ConnectFourSettings::default(),
+        clock,
+    )
+    .await
+}
+
+/// The variant comes from the query, like `?variant=international`.
+async fn checkers_ws(
+    ws: WebSocketUpgrade,
+    Path(room_id): Path<String>,
+    Query(settings): Query<CheckersSettings>,
+    Query(clock): Query<ClockSettings>,
+    user_data: UserData,
+    State(state): State<ServerState>,
+) -> Response {
+    join_game::<Checkers>(
+        &state,
+        &state.checkers_rooms,
+        ws,
+        room_id,
+        user_data,
+        settings,
+        clock,
+    )
+    .await
+}
+
 async fn auth_middleware(_user_dat: UserData, request: Request, next: Next) -> Response {
     next.run(request).await
 }
@@ -83,11 +252,29 @@ async fn main() {
     info!("Starting server");
 
     let test_rooms = Arc::new(Mutex::new(HashMap::new()));
-    let menu = Arc::new(Mutex::new(SocketEndpoint::new(Menu::new())));
+    let chess_rooms = Arc::new(Mutex::new(HashMap::new()));
+    let go_rooms = Arc::new(Mutex::new(HashMap::new()));
+    let connect_four_rooms = Arc::new(Mutex::new(HashMap::new()));
+    let checkers_rooms = Arc::new(Mutex::new(HashMap::new()));
+    let users = Arc::new(Mutex::new(
+        UserStore::open(&data_dir::meta()).await.unwrap(),
+    ));
+    let menu = Arc::new(Mutex::new(SocketEndpoint::new(Menu::new(users.clone()))));
+    let history = Arc::new(Mutex::new(
+        MatchHistory::open(&data_dir::meta().join("history"))
+            .await
+            .unwrap(),
+    ));
 
     let state = ServerState {
         menu,
         test_rooms,
+        chess_rooms,
+        go_rooms,
+        connect_four_rooms,
+        checkers_rooms,
+        history,
+        users,
         auth_key: Key::new("test-key".to_owned()).unwrap(),
     };
 
@@ -101,6 +288,42 @@ async fn main() {
             ServeFile::new("../test_front/dist/index.html"),
         )
         .route("/test/{room_id}/socket", get(test_ws))
+        .route("/test/{room_id}/share/{token}", get(open_share_link))
+        .nest_service("/chess/static", ServeDir::new("../chess_front/dist"))
+        .nest_service(
+            "/chess/{room_id}",
+            ServeFile::new("../chess_front/dist/index.html"),
+        )
+        .route("/chess/{room_id}/socket", get(chess_ws))
+        .route("/chess/{room_id}/replay", get(replay::<Chess>))
+        .route("/chess/{room_id}/export", get(export::<Chess>))
+        .nest_service("/go/static", ServeDir::new("../go_front/dist"))
+        .nest_service(
+            "/go/{room_id}",
+            ServeFile::new("../go_front/dist/index.html"),
+        )
+        .route("/go/{room_id}/socket", get(go_ws))
+        .route("/go/{room_id}/replay", get(replay::<Go>))
+        .route("/go/{room_id}/export", get(export::<Go>))
+        .nest_service(
+            "/connect_four/static",
+            ServeDir::new("../connect_four_front/dist"),
+        )
+        .nest_service(
+            "/connect_four/{room_id}",
+            ServeFile::new("../connect_four_front/dist/index.html"),
+        )
+        .route("/connect_four/{room_id}/socket", get(connect_four_ws))
+        .route("/connect_four/{room_id}/replay", get(replay::<ConnectFour>))
+        .nest_service("/checkers/static", ServeDir::new("../checkers_front/dist"))
+        .nest_service(
+            "/checkers/{room_id}",
+            ServeFile::new("../checkers_front/dist/index.html"),
+        )
+        .route("/checkers/{room_id}/socket", get(checkers_ws))
+        .route("/checkers/{room_id}/replay", get(replay::<Checkers>))
+        .route("/history", get(own_history))
+        .route("/history/{user_id}", get(user_history))
         .route_layer(from_fn_with_state(state.clone(), auth_middleware))
         .nest_service("/login", ServeDir::new("../login/dist"))
         .route("/login_handler", post(login))
@@ -0,0 +1,67 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use game_back::{game::is_bot, history::MatchSummary};
+use tokio::{
+    fs::{create_dir_all, read, try_exists},
+    sync::Mutex,
+};
+
+use crate::atomic_file::write_atomically;
+
+/// Shared by every game room, which record their matches as they end.
+pub type SharedHistory = Arc<Mutex<MatchHistory>>;
+
+/// Finished matches of every user, oldest first, in one file per user.
+/// The matches themselves stay in the event stores of their rooms.
+pub struct MatchHistory {
+    path: PathBuf,
+}
+
+impl MatchHistory {
+    pub async fn open(path: &Path) -> Result<Self> {
+        create_dir_all(path).await?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub async fn list(&self, user: u64) -> Result<Vec<MatchSummary>> {
+        let path = self.path.join(user.to_string());
+        if !try_exists(&path).await? {
+            return Ok(Vec::new());
+        }
+        let data = read(&path).await?;
+        postcard::from_bytes(&data).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Failed to deserialize match history: {e}"),
+            )
+        })
+    }
+
+    /// Adds the match to the history of each of its players but computer
+    /// ones.
+    pub async fn record(&self, summary: &MatchSummary) -> Result<()> {
+        let users = summary
+            .players
+            .iter()
+            .flatten()
+            .map(|player| player.id)
+            .filter(|&id| !is_bot(id));
+        for user in users {
+            let mut matches = self.list(user).await?;
+            matches.push(summary.clone());
+            self.save(user, &matches).await?;
+        }
+        Ok(())
+    }
+
+    async fn save(&self, user: u64, matches: &[MatchSummary]) -> Result<()> {
+        let data = postcard::to_stdvec(matches).unwrap();
+        write_atomically(&self.path.join(user.to_string()), &data).await
+    }
+}
@@ -9,7 +9,10 @@ use menu_back::{
 };
 use tracing::info;
 
-use crate::socket_endpoint::{Client, SocketHandler};
+use crate::{
+    socket_endpoint::{Client, SocketHandler},
+    user_store::SharedUsers,
+};
 
 pub enum MenuMessage {
     ServerCreated(String),
@@ -18,13 +21,15 @@ pub enum MenuMessage {
 pub struct Menu {
     clients: HashMap<u64, Client<ToClient>>,
     servers: ServerList,
+    users: SharedUsers,
 }
 
 impl Menu {
-    pub fn new() -> Self {
+    pub fn new(users: SharedUsers) -> Self {
         Self {
             clients: HashMap::new(),
             servers: ServerList::new(),
+            users,
         }
     }
 
@@ -50,6 +55,12 @@ impl SocketHandler<ToClient, ToServer, MenuMessage> for Menu {
             ToServer::Pong => {
                 info!("Pong from {}", client_id);
             }
+            ToServer::GetLeaderboard(kind) => {
+                let leaderboard = self.users.lock().await.leaderboard(&kind);
+                if let Some(client) = self.clients.get_mut(&client_id) {
+                    client.send(ToClient::Leaderboard(leaderboard)).await;
+                }
+            }
         };
     }
 
@@ -0,0 +1,112 @@
+use std::io::ErrorKind;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use game_back::{
+    game::{Game, Match, is_bot},
+    history::ExportInfo,
+    store::MatchLoader,
+};
+use tracing::warn;
+
+use crate::{ServerState, data_dir, event_store::EventStore, token::UserData};
+
+/// Loads the match of a game room from its event log, whether the room is
+/// open or not.
+async fn load_match<G: Game>(room_id: &str) -> Result<Match<G>, StatusCode> {
+    if !data_dir::is_room_id(room_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let path = data_dir::room(G::KIND, room_id);
+    let mut loader = MatchLoader::<G>::new(G::Settings::default(), None);
+    match EventStore::read(&path, &mut loader).await {
+        Ok(()) if !loader.is_new() => Ok(loader.get_match()),
+        Ok(()) => Err(StatusCode::NOT_FOUND),
+        Err(error) if error.kind() == ErrorKind::NotFound => Err(StatusCode::NOT_FOUND),
+        Err(error) => {
+            warn!("Cannot load {} {}: {}", G::KIND, room_id, error);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn list(state: &ServerState, user: u64) -> Response {
+    match state.history.lock().await.list(user).await {
+        Ok(matches) => Json(matches).into_response(),
+        Err(error) => {
+            warn!("Cannot read the match history of {}: {}", user, error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// The finished matches of the user asking, oldest first.
+pub async fn own_history(user_data: UserData, State(state): State<ServerState>) -> Response {
+    list(&state, user_data.id).await
+}
+
+pub async fn user_history(Path(user): Path<u64>, State(state): State<ServerState>) -> Response {
+    list(&state, user).await
+}
+
+/// Every position of a room's match, from the start to the last move, for
+/// anyone to step through.
+pub async fn replay<G: Game>(Path(room_id): Path<String>) -> Response {
+    match load_match::<G>(&room_id).await {
+        Ok(game) => Json(game.replay()).into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
+/// A room's match in its game's standard notation, named after the players
+/// as recorded in their history.
+pub async fn export<G: Game>(
+    Path(room_id): Path<String>,
+    State(state): State<ServerState>,
+) -> Response {
+    let game = match load_match::<G>(&room_id).await {
+        Ok(game) => game,
+        Err(status) => return status.into_response(),
+    };
+    let mut info = ExportInfo {
+        room: room_id.clone(),
+        ..Default::default()
+    };
+    if let Some(&user) = game.seats().iter().flatten().find(|&&user| !is_bot(user)) {
+        let history = state.history.lock().await.list(user).await;
+        let summary = history
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .find(|summary| summary.kind == G::KIND && summary.room == room_id);
+        if let Some(summary) = summary {
+            info.players = summary
+                .players
+                .into_iter()
+                .map(|player| player.map_or_else(|| "?".to_owned(), |player| player.name))
+                .collect();
+            info.finished_at = Some(summary.finished_at);
+        }
+    }
+    let Some(notation) = G::export(&game, &info) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let disposition = format!(
+        "attachment; filename=\"{}-{}.{}\"",
+        G::KIND,
+        room_id.replace(|c: char| !c.is_ascii_alphanumeric(), "_"),
+        notation.extension
+    );
+    (
+        [
+            (header::CONTENT_TYPE, notation.content_type.to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        notation.text,
+    )
+        .into_response()
+}
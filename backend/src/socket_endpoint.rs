@@ -1,8 +1,11 @@
 use std::{
+    collections::HashMap,
     marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use crate::token::{ShareGrant, UserData};
 use axum::{
     body::Bytes,
     extract::{
@@ -22,19 +25,50 @@ use tokio::{
         broadcast,
         mpsc::{self, UnboundedReceiver, unbounded_channel},
     },
+    task,
     time::{self, Instant},
 };
-use tracing::debug;
 
-use crate::token::UserData;
+/// Source of [`Client::connection_id`]s, unique for the whole server.
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(0);
 
+/// A participant in a room, either behind a websocket or a [`Bot`] running
+/// in-process.
 pub struct Client<ToClient> {
     user_data: UserData,
-    socket: SplitSink<WebSocket, Message>,
-    to_client: PhantomData<ToClient>,
+    share_grant: Option<ShareGrant>,
+    /// Tells apart the connections of a user, as a reconnect or a new bot in
+    /// the same seat replaces one while it is still winding down.
+    connection_id: u64,
+    connection: Connection<ToClient>,
+}
+
+enum Connection<ToClient> {
+    Socket(SplitSink<WebSocket, Message>),
+    Bot(mpsc::UnboundedSender<ToClient>),
+}
+
+/// An in-process participant. It gets every message a client would and
+/// answers with messages a client could send, which the room handles like
+/// any other.
+pub trait Bot<ToClient, ToServer>: Send + 'static {
+    fn on_message(&mut self, message: ToClient) -> Vec<ToServer>;
 }
 
 impl<ToClient> Client<ToClient> {
+    fn new(
+        user_data: UserData,
+        share_grant: Option<ShareGrant>,
+        connection: Connection<ToClient>,
+    ) -> Self {
+        Self {
+            user_data,
+            share_grant,
+            connection_id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            connection,
+        }
+    }
+
     pub fn get_id(&self) -> u64 {
         self.user_data.id
     }
@@ -42,38 +76,55 @@ impl<ToClient> Client<ToClient> {
     pub fn get_user_data(&self) -> UserData {
         self.user_data.clone()
     }
+
+    pub fn get_share_grant(&self) -> Option<ShareGrant> {
+        self.share_grant.clone()
+    }
 }
 
 impl<ToClient> Client<ToClient>
 where
     ToClient: Serialize,
 {
+    /// Bots have no latency to measure.
     pub async fn ping(&mut self) {
+        let Connection::Socket(socket) = &mut self.connection else {
+            return;
+        };
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis()
             .to_le_bytes();
-        self.socket
+        socket
             .send(Message::Ping(Bytes::from_owner(timestamp)))
             .await
             .unwrap()
     }
 
     pub async fn pong(&mut self, data: Bytes) {
-        self.socket
+        let Connection::Socket(socket) = &mut self.connection else {
+            return;
+        };
+        socket
             .send(Message::Pong(Bytes::from_owner(data)))
             .await
             .unwrap()
     }
 
     pub async fn send(&mut self, message: ToClient) {
-        let _ = self
-            .socket
-            .send(Message::Binary(Bytes::from(
-                serde_cbor::to_vec(&message).unwrap(),
-            )))
-            .await;
+        match &mut self.connection {
+            Connection::Socket(socket) => {
+                let _ = socket
+                    .send(Message::Binary(Bytes::from(
+                        serde_cbor::to_vec(&message).unwrap(),
+                    )))
+                    .await;
+            }
+            Connection::Bot(sender) => {
+                let _ = sender.send(message);
+            }
+        }
     }
 }
 
@@ -106,6 +157,45 @@ pub trait SocketHandler<ToClient, ToServer, Internal> {
         timestamp: u128,
     ) -> impl std::future::Future<Output = ()> + std::marker::Send;
     fn tick(&mut self) -> impl std::future::Future<Output = ()> + std::marker::Send;
+    /// When to call [`SocketHandler::on_wakeup`], for deadlines the
+    /// [`SocketHandler::tick`] is too coarse for. Asked again after every
+    /// call into the handler.
+    fn next_wakeup(&self) -> Option<Instant> {
+        None
+    }
+    fn on_wakeup(&mut self) -> impl std::future::Future<Output = ()> + std::marker::Send {
+        async {}
+    }
+    /// Called once before anything else, for handlers that add bots to
+    /// their own room.
+    fn set_bots(&mut self, _bots: Bots<ToClient, ToServer, Internal>) {}
+}
+
+/// Attaches [`Bot`]s to a room. They join through
+/// [`SocketHandler::on_connect`] like any client, and leave once the handler
+/// drops their [`Client`] or the room closes.
+pub struct Bots<ToClient, ToServer, Internal> {
+    message_sender: mpsc::UnboundedSender<ServerMessage<ToClient, ToServer, Internal>>,
+    kill_sender: broadcast::Sender<()>,
+}
+
+impl<ToClient, ToServer, Internal> Bots<ToClient, ToServer, Internal>
+where
+    ToClient: Send + 'static,
+    ToServer: Send + 'static,
+    Internal: Send + 'static,
+{
+    pub fn attach(&self, user_data: UserData, bot: impl Bot<ToClient, ToServer>) {
+        let (sender, receiver) = unbounded_channel();
+        let client = Client::new(user_data, None, Connection::Bot(sender));
+        tokio::spawn(bot_loop(
+            self.message_sender.clone(),
+            receiver,
+            self.kill_sender.subscribe(),
+            client,
+            bot,
+        ));
+    }
 }
 
 pub struct SocketEndpoint<ToClient, ToServer, Internal> {
@@ -122,24 +212,32 @@ where
     Internal: Send + 'static,
 {
     pub fn new(
-        socket_handler: impl SocketHandler<ToClient, ToServer, Internal> + Send + 'static,
+        mut socket_handler: impl SocketHandler<ToClient, ToServer, Internal> + Send + 'static,
     ) -> Self {
         let (message_sender, message_receiver) = unbounded_channel();
         let (kill_sender, _) = broadcast::channel(1);
-        tokio::spawn(pass_messages(
-            message_receiver,
-            socket_handler,
-            kill_sender.subscribe(),
-        ));
         let state = SocketEndpoint {
             message_sender,
             kill_sender,
             to_client: PhantomData,
             to_server: PhantomData,
         };
+        socket_handler.set_bots(state.bots());
+        tokio::spawn(pass_messages(
+            message_receiver,
+            socket_handler,
+            state.kill_sender.subscribe(),
+        ));
         state
     }
 
+    pub fn bots(&self) -> Bots<ToClient, ToServer, Internal> {
+        Bots {
+            message_sender: self.message_sender.clone(),
+            kill_sender: self.kill_sender.clone(),
+        }
+    }
+
     pub fn send_internal_message(&self, message: Internal) {
         self.message_sender
             .send(ServerMessage::InternalMessage(message))
@@ -147,12 +245,24 @@ where
     }
 
     pub fn handler(&self, ws: WebSocketUpgrade, user_data: UserData) -> Response {
+        self.handler_with_grant(ws, user_data, None)
+    }
+
+    /// Like [`SocketEndpoint::handler`], for a client that came through a
+    /// share link.
+    pub fn handler_with_grant(
+        &self,
+        ws: WebSocketUpgrade,
+        user_data: UserData,
+        share_grant: Option<ShareGrant>,
+    ) -> Response {
         let message_sender = self.message_sender.clone();
         let kill_receiver = self.kill_sender.subscribe();
         ws.on_upgrade(move |socket| {
             on_upgrade::<ToClient, ToServer, Internal>(
                 socket,
                 user_data,
+                share_grant,
                 message_sender,
                 kill_receiver,
             )
@@ -169,15 +279,31 @@ impl<ToClient, ToServer, Internal> Drop for SocketEndpoint<ToClient, ToServer, I
 enum ServerMessage<ToClient, ToServer, Internal> {
     NewClient(Client<ToClient>),
     InternalMessage(Internal),
-    Message { client_id: u64, message: ToServer },
-    Disconnect { client_id: u64 },
-    Ping { client_id: u64, data: Bytes },
-    Pong { client_id: u64, timestamp: u128 },
+    Message {
+        client_id: u64,
+        connection_id: u64,
+        message: ToServer,
+    },
+    Disconnect {
+        client_id: u64,
+        connection_id: u64,
+    },
+    Ping {
+        client_id: u64,
+        connection_id: u64,
+        data: Bytes,
+    },
+    Pong {
+        client_id: u64,
+        connection_id: u64,
+        timestamp: u128,
+    },
 }
 
 async fn on_upgrade<ToClient, ToServer, Internal>(
     socket: WebSocket,
     user_data: UserData,
+    share_grant: Option<ShareGrant>,
     message_sender: mpsc::UnboundedSender<ServerMessage<ToClient, ToServer, Internal>>,
     kill_receiver: broadcast::Receiver<()>,
 ) where
@@ -185,11 +311,7 @@ async fn on_upgrade<ToClient, ToServer, Internal>(
     ToServer: DeserializeOwned,
 {
     let (to_client, from_client) = socket.split();
-    let client = Client {
-        user_data,
-        socket: to_client,
-        to_client: PhantomData,
-    };
+    let client = Client::new(user_data, share_grant, Connection::Socket(to_client));
     socket_loop(message_sender, from_client, kill_receiver, client).await;
 }
 
@@ -204,6 +326,7 @@ where
     ToServer: DeserializeOwned,
 {
     let client_id = client.get_id().to_owned();
+    let connection_id = client.connection_id;
     message_sender.send(ServerMessage::NewClient(client)).ok()?;
     loop {
         select! {
@@ -211,17 +334,17 @@ where
             match message {
               Message::Binary(message) => {
                 let Ok(message) = serde_cbor::from_slice(&message) else { break; };
-                message_sender.send(ServerMessage::Message { client_id, message }).ok()?;
+                message_sender.send(ServerMessage::Message { client_id, connection_id, message }).ok()?;
               },
               Message::Ping(data) => {
-                message_sender.send(ServerMessage::Ping { client_id, data}).ok()?;
+                message_sender.send(ServerMessage::Ping { client_id, connection_id, data }).ok()?;
               },
               Message::Pong(data) => {
                 let Ok(bytes) = data.as_ref().try_into() else {
                     break;
                 };
                 let timestamp = u128::from_le_bytes(bytes);
-                message_sender.send(ServerMessage::Pong { client_id, timestamp}).ok()?;
+                message_sender.send(ServerMessage::Pong { client_id, connection_id, timestamp }).ok()?;
               },
               Message::Close(_) => break,
               _ => ()
@@ -236,7 +359,55 @@ where
         }
     }
     message_sender
-        .send(ServerMessage::Disconnect { client_id })
+        .send(ServerMessage::Disconnect {
+            client_id,
+            connection_id,
+        })
+        .ok()?;
+    Some(())
+}
+
+/// Feeds a bot what the room sends it and hands its answers to the room.
+async fn bot_loop<ToClient, ToServer, Internal>(
+    message_sender: mpsc::UnboundedSender<ServerMessage<ToClient, ToServer, Internal>>,
+    mut to_bot: UnboundedReceiver<ToClient>,
+    mut kill_receiver: broadcast::Receiver<()>,
+    client: Client<ToClient>,
+    mut bot: impl Bot<ToClient, ToServer>,
+) -> Option<()>
+where
+    ToClient: Send + 'static,
+    ToServer: Send + 'static,
+{
+    let client_id = client.get_id();
+    let connection_id = client.connection_id;
+    message_sender.send(ServerMessage::NewClient(client)).ok()?;
+    loop {
+        select! {
+          message = to_bot.recv() => {
+            // The handler dropped the bot's client.
+            let Some(message) = message else { break; };
+            // Bots may search for a while, which must not hold up the
+            // runtime's other tasks.
+            let (returned, replies) = task::spawn_blocking(move || {
+                let replies = bot.on_message(message);
+                (bot, replies)
+            }).await.ok()?;
+            bot = returned;
+            for message in replies {
+                message_sender.send(ServerMessage::Message { client_id, connection_id, message }).ok()?;
+            }
+          },
+          _ = kill_receiver.recv() => {
+            break;
+          },
+        }
+    }
+    message_sender
+        .send(ServerMessage::Disconnect {
+            client_id,
+            connection_id,
+        })
         .ok()?;
     Some(())
 }
@@ -250,21 +421,36 @@ async fn pass_messages<ToClient, ToServer, Internal>(
         Instant::now() + Duration::from_secs(5),
         Duration::from_secs(5),
     );
+    // The latest connection of every client. What a replaced one still sends,
+    // its disconnect included, would otherwise be taken for its successor's.
+    let mut connections = HashMap::new();
     loop {
+        let wakeup = socket_handler.next_wakeup();
         select! {
           Some(message) = channel.recv() => {
+            let is_current = |client_id, connection_id| connections.get(&client_id) == Some(&connection_id);
             match message {
-                ServerMessage::NewClient(client) => socket_handler.on_connect(client).await,
-                ServerMessage::Message{client_id,message} => socket_handler.on_message(client_id,message).await,
+                ServerMessage::NewClient(client) => {
+                    connections.insert(client.get_id(), client.connection_id);
+                    socket_handler.on_connect(client).await
+                },
+                ServerMessage::Message{client_id,connection_id,message} if is_current(client_id, connection_id) => socket_handler.on_message(client_id,message).await,
                 ServerMessage::InternalMessage(internal_message) => socket_handler.on_internal_message(internal_message).await,
-                ServerMessage::Disconnect{client_id} => socket_handler.on_disconnect(client_id).await,
-                ServerMessage::Ping { client_id, data } => socket_handler.on_ping(client_id, data).await,
-                ServerMessage::Pong { client_id, timestamp } => socket_handler.on_pong(client_id, timestamp).await,
+                ServerMessage::Disconnect{client_id,connection_id} if is_current(client_id, connection_id) => {
+                    connections.remove(&client_id);
+                    socket_handler.on_disconnect(client_id).await
+                },
+                ServerMessage::Ping { client_id, connection_id, data } if is_current(client_id, connection_id) => socket_handler.on_ping(client_id, data).await,
+                ServerMessage::Pong { client_id, connection_id, timestamp } if is_current(client_id, connection_id) => socket_handler.on_pong(client_id, timestamp).await,
+                ServerMessage::Message { .. } | ServerMessage::Disconnect { .. } | ServerMessage::Ping { .. } | ServerMessage::Pong { .. } => {},
             };
           },
           _ = interval.tick() => {
             socket_handler.tick().await;
           },
+          _ = time::sleep_until(wakeup.unwrap_or_else(Instant::now)), if wakeup.is_some() => {
+            socket_handler.on_wakeup().await;
+          },
           _ = kill_receiver.recv() => {
             break;
           },
@@ -1,35 +1,51 @@
 use std::{
     collections::HashMap,
-    path::PathBuf,
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use backend_commons::client_info::{ClientData, ClientListMessage, ClientMessage};
+use backend_commons::{
+    chat::{ChatListMessage, ChatMessage, MAX_CHAT_MESSAGE_LENGTH},
+    checkpoint::{Checkpoint, CheckpointAction, CheckpointListMessage},
+    client_info::{ClientData, ClientListMessage, ClientMessage, ClientRole},
+    store::EventPosition,
+};
 use test_back::{
     ToClient, ToServer,
     board::{
+        comments::{CommentAction, CommentEvent},
         common::BoardEvent,
         global_board::{EventSender, GlobalBoard},
+        roles::{Role, ShareLink},
     },
     store::{
         StoringObserver,
         serialization::{BoardLoader, CURRENT_VERSION},
     },
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
+    auth::now_secs,
+    chat_history::ChatHistory,
+    checkpoints::Checkpoints,
+    data_dir,
     event_store::EventStore,
     socket_endpoint::{Client, SocketHandler},
+    token::{Key, ShareGrant},
 };
 
 const LOG_FILE_SIZE_LIMIT: u64 = 1024;
+const CHAT_HISTORY_LIMIT: usize = 200;
 
 pub struct Test {
     clients: Clients,
     board: GlobalBoard,
     name: String,
     observer: StoringObserver<EventStore>,
+    checkpoints: Checkpoints,
+    chat: ChatHistory,
+    auth_key: Key,
+    share_grants: HashMap<u64, ShareGrant>,
 }
 
 struct Clients {
@@ -44,6 +60,12 @@ impl EventSender for Clients {
             client.send(message).await;
         }
     }
+
+    async fn send_comment_event(&mut self, client_id: u64, event: CommentEvent) {
+        if let Some(client) = self.clients.get_mut(&client_id) {
+            client.send(ToClient::CommentEvent(event)).await;
+        }
+    }
 }
 
 impl Clients {
@@ -68,25 +90,236 @@ impl Clients {
     pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Client<ToClient>> {
         self.clients.values_mut()
     }
+
+    pub fn ids(&self) -> Vec<u64> {
+        self.clients.keys().copied().collect()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&u64, &mut Client<ToClient>)> {
+        self.clients.iter_mut()
+    }
 }
 
 impl Test {
-    pub async fn new(name: String) -> Self {
-        let path = PathBuf::from("data").join(&name);
+    pub async fn new(name: String, auth_key: Key) -> Self {
+        let path = data_dir::test_room(&name).await.unwrap();
         let mut board_loader = BoardLoader::new();
         let store = EventStore::open(&path, CURRENT_VERSION, &mut board_loader)
             .await
             .unwrap();
 
+        let checkpoints = Checkpoints::open(&path).await.unwrap();
+        let chat = ChatHistory::open(&path, CHAT_HISTORY_LIMIT).await.unwrap();
+
         let board = board_loader.get_board();
-        let board = GlobalBoard::from_board(board);
+        let board = GlobalBoard::from_board(board, rand::random());
 
         Self {
             clients: Clients::new(),
             board,
             name,
             observer: StoringObserver::new(store),
+            checkpoints,
+            chat,
+            auth_key,
+            share_grants: HashMap::new(),
+        }
+    }
+
+    /// The user's own role, raised by the share link they joined with for as
+    /// long as that link is outstanding.
+    fn role(&self, client_id: u64) -> Role {
+        let shared = self
+            .share_grants
+            .get(&client_id)
+            .and_then(|grant| self.board.share_link(grant.link))
+            .filter(|link| link.is_valid(now_secs()))
+            .map(|link| link.role)
+            .unwrap_or_default();
+        self.board.role(client_id).max(shared)
+    }
+
+    /// Editors act on the board on their turn, owners at any time.
+    fn may_act(&self, client_id: u64) -> bool {
+        let role = self.role(client_id);
+        role.can_edit() && (role.is_owner() || self.board.seating().may_act(client_id))
+    }
+
+    /// The seat and role the client list shows for `client_id`. Without
+    /// seats every editor plays.
+    fn seat_and_role(&self, client_id: u64) -> (Option<usize>, ClientRole) {
+        let seating = self.board.seating();
+        let seat = seating.seat_of(client_id);
+        let plays = match seating.is_enabled() {
+            true => seat.is_some(),
+            false => self.role(client_id).can_edit(),
+        };
+        let role = match plays {
+            true => ClientRole::Player,
+            false => ClientRole::Spectator,
+        };
+        (seat, role)
+    }
+
+    fn client_data(&self, client_id: u64, name: String) -> ClientData {
+        let (seat, role) = self.seat_and_role(client_id);
+        ClientData {
+            id: client_id,
+            name,
+            ping: 0,
+            seat,
+            role,
+        }
+    }
+
+    /// Tells everyone the seat and role of every client, after seating or
+    /// roles changed.
+    async fn send_seats(&mut self) {
+        for id in self.clients.ids() {
+            let (seat, role) = self.seat_and_role(id);
+            self.broadcast(ToClient::ClientListMessage(ClientListMessage::Update(
+                id,
+                ClientMessage::Seat { seat, role },
+            )))
+            .await;
+        }
+    }
+
+    async fn on_seating(&mut self, client_id: u64, message: ToServer) {
+        let role = self.role(client_id);
+        let mut seating = self.board.seating().clone();
+        let changed = match message {
+            ToServer::SetSeats(count) if role.is_owner() => {
+                seating.resize(count);
+                true
+            }
+            ToServer::Sit(seat) if role.can_edit() => seating.sit(client_id, seat),
+            ToServer::Stand => seating.stand(client_id),
+            ToServer::EndTurn if role.is_owner() || seating.to_move() == Some(client_id) => {
+                seating.end_turn();
+                true
+            }
+            _ => false,
+        };
+        if !changed {
+            return;
+        }
+        self.board
+            .set_seating(seating, &mut self.clients, &mut self.observer)
+            .await;
+        self.send_seats().await;
+    }
+
+    async fn create_share_link(&mut self, client_id: u64, role: Role, valid_for_secs: u64) {
+        if role.is_owner() {
+            return;
+        }
+        let id = rand::random();
+        let link = ShareLink {
+            role,
+            expires_at: now_secs().saturating_add(valid_for_secs),
+        };
+        let grant = ShareGrant {
+            room: self.name.clone(),
+            link: id,
+            role,
+            expires_at: link.expires_at,
+        };
+        let Ok(token) = self.auth_key.get_share_token(grant) else {
+            return;
+        };
+        self.board
+            .create_share_link(id, link, &mut self.clients, &mut self.observer)
+            .await;
+        if let Some(client) = self.clients.get_mut(&client_id) {
+            let path = format!("/test/{}/share/{}", self.name, token);
+            client.send(ToClient::ShareLink { id, path }).await;
+        }
+    }
+
+    async fn on_chat(&mut self, client_id: u64, text: String) {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+            return;
+        }
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        let message = ChatMessage {
+            author: client_id,
+            author_name: client.get_user_data().username,
+            text: text.to_owned(),
+            sent_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        };
+        if let Err(error) = self.chat.push(message.clone()).await {
+            warn!("Room {} cannot store chat message: {}", self.name, error);
         }
+        self.broadcast(ToClient::ChatListMessage(ChatListMessage::Added(message)))
+            .await;
+    }
+
+    async fn on_roll(&mut self, client_id: u64, dice: u64) {
+        if !self.may_act(client_id) {
+            return;
+        }
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        let roller_name = client.get_user_data().username;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.board
+            .roll(
+                client_id,
+                &roller_name,
+                now,
+                dice,
+                &mut self.clients,
+                &mut self.observer,
+            )
+            .await;
+    }
+
+    async fn on_comment_action(&mut self, client_id: u64, action: CommentAction) {
+        let role = self.role(client_id);
+        let allowed = match &action {
+            CommentAction::Add { .. } => true,
+            CommentAction::SetResolved { .. } => role.can_edit(),
+            CommentAction::Delete { object, comment } => {
+                role.is_owner()
+                    || self
+                        .board
+                        .thread(*object)
+                        .and_then(|thread| thread.comment(*comment))
+                        .is_some_and(|comment| comment.author == client_id)
+            }
+        };
+        if !allowed {
+            return;
+        }
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        let author_name = client.get_user_data().username;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.board
+            .apply_comment(
+                client_id,
+                &author_name,
+                now,
+                action,
+                &mut self.clients,
+                &mut self.observer,
+            )
+            .await;
     }
 
     async fn broadcast(&mut self, message: ToClient) {
@@ -94,39 +327,170 @@ impl Test {
             client.send(message.clone()).await;
         }
     }
+
+    async fn on_checkpoint_action(&mut self, action: CheckpointAction) {
+        match action {
+            CheckpointAction::Save(name) => {
+                let store = self.observer.get_store_mut();
+                store.flush().await.unwrap();
+                let checkpoint = Checkpoint {
+                    name,
+                    position: store.position(),
+                    created_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as u64,
+                };
+                self.checkpoints.insert(checkpoint.clone()).await.unwrap();
+                self.broadcast(ToClient::CheckpointListMessage(
+                    CheckpointListMessage::Saved(checkpoint),
+                ))
+                .await;
+            }
+            CheckpointAction::Delete(name) => {
+                if self.checkpoints.remove(&name).await.unwrap() {
+                    self.broadcast(ToClient::CheckpointListMessage(
+                        CheckpointListMessage::Deleted(name),
+                    ))
+                    .await;
+                }
+            }
+            CheckpointAction::Restore(name) => {
+                let Some(checkpoint) = self.checkpoints.get(&name) else {
+                    return;
+                };
+                self.restore(checkpoint.position).await;
+            }
+            CheckpointAction::RestoreTo(position) => self.restore(position).await,
+        }
+    }
+
+    async fn restore(&mut self, position: EventPosition) {
+        let mut board_loader = BoardLoader::new();
+        let store = self.observer.get_store_mut();
+        if let Err(error) = store.load_at(position, &mut board_loader).await {
+            warn!(
+                "Room {} cannot restore {:?}: {}",
+                self.name, position, error
+            );
+            return;
+        }
+        info!("Room {} restored to {:?}", self.name, position);
+        self.board.restore(board_loader.get_board());
+        // The restored state becomes the new head of the history, so it is
+        // still in place after a restart.
+        self.observer.snapshot(self.board.get_state()).await;
+        for (&id, client) in self.clients.iter_mut() {
+            let board = self.board.client_state(id);
+            client.send(ToClient::NewBoard(Box::new(board))).await;
+        }
+    }
 }
 
 impl SocketHandler<ToClient, ToServer, ()> for Test {
     async fn on_connect(&mut self, mut client: Client<ToClient>) {
         let id = client.get_id();
         let user_data = client.get_user_data();
-        self.board.new_client(id);
+        // A reconnect replaces the connection it comes back from, which may
+        // not have been seen leaving.
+        self.clients.remove(&id);
+        match client.get_share_grant() {
+            Some(grant) => self.share_grants.insert(id, grant),
+            None => self.share_grants.remove(&id),
+        };
+        self.board.new_client(id, now_secs());
         client
-            .send(ToClient::NewBoard(self.board.get_state()))
+            .send(ToClient::NewBoard(Box::new(self.board.client_state(id))))
             .await;
+        client
+            .send(ToClient::CheckpointListMessage(CheckpointListMessage::Set(
+                self.checkpoints.list(),
+            )))
+            .await;
+        client
+            .send(ToClient::ChatListMessage(ChatListMessage::Set(
+                self.chat.list(),
+            )))
+            .await;
+        let present = self
+            .clients
+            .values_mut()
+            .map(|present| (present.get_id(), present.get_user_data().username))
+            .collect::<Vec<_>>();
+        for (present, name) in present {
+            let data = self.client_data(present, name);
+            client
+                .send(ToClient::ClientListMessage(ClientListMessage::Joined(data)))
+                .await;
+        }
         self.clients.insert(id, client);
-        self.broadcast(ToClient::ClientListMessage(ClientListMessage::Joined(
-            ClientData {
-                id,
-                name: user_data.username,
-                ping: 0,
-            },
-        )))
-        .await;
+        self.board
+            .claim_ownership(id, &mut self.clients, &mut self.observer)
+            .await;
+        let data = self.client_data(id, user_data.username);
+        self.broadcast(ToClient::ClientListMessage(ClientListMessage::Joined(data)))
+            .await;
     }
 
     async fn on_message(&mut self, client_id: u64, message: ToServer) {
         match message {
+            ToServer::Session(session) => self.board.resume(client_id, session),
+            ToServer::Chat(text) => self.on_chat(client_id, text).await,
             ToServer::BoardAction(action) => {
+                if !self.may_act(client_id) {
+                    self.board
+                        .reject(client_id, action, &mut self.clients)
+                        .await;
+                    return;
+                }
                 self.board
                     .apply(client_id, action, &mut self.clients, &mut self.observer)
                     .await;
             }
+            ToServer::CommentAction(action) => self.on_comment_action(client_id, action).await,
+            ToServer::Roll(dice) => self.on_roll(client_id, dice).await,
+            ToServer::CheckpointAction(action) => {
+                if self.role(client_id).is_owner() {
+                    self.on_checkpoint_action(action).await;
+                }
+            }
+            ToServer::SetRole { user, role } => {
+                // Owners cannot demote themselves, so a room keeps its owner.
+                if self.role(client_id).is_owner() && user != client_id {
+                    self.board
+                        .set_role(user, role, &mut self.clients, &mut self.observer)
+                        .await;
+                    self.send_seats().await;
+                }
+            }
+            ToServer::CreateShareLink {
+                role,
+                valid_for_secs,
+            } => {
+                if self.role(client_id).is_owner() {
+                    self.create_share_link(client_id, role, valid_for_secs)
+                        .await;
+                }
+            }
+            ToServer::RevokeShareLink(id) => {
+                if self.role(client_id).is_owner() {
+                    self.board
+                        .revoke_share_link(id, &mut self.clients, &mut self.observer)
+                        .await;
+                    self.send_seats().await;
+                }
+            }
+            message @ (ToServer::SetSeats(_)
+            | ToServer::Sit(_)
+            | ToServer::Stand
+            | ToServer::EndTurn) => self.on_seating(client_id, message).await,
         }
     }
 
     async fn on_disconnect(&mut self, client_id: u64) {
         self.clients.remove(&client_id);
+        self.share_grants.remove(&client_id);
+        self.board.remove_client(client_id, now_secs());
         self.broadcast(ToClient::ClientListMessage(ClientListMessage::Quit(
             client_id,
         )))
@@ -141,7 +505,7 @@ impl SocketHandler<ToClient, ToServer, ()> for Test {
         } else {
             self.observer.get_store_mut().flush().await.unwrap();
         }
-        if self.clients.clients.is_empty() {}
+        self.board.prune_sessions(now_secs());
         for client in self.clients.values_mut() {
             client.ping().await;
         }
@@ -159,7 +523,7 @@ impl SocketHandler<ToClient, ToServer, ()> for Test {
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis();
-        let ping = now.checked_sub(timestamp).unwrap_or(0) as u32;
+        let ping = now.saturating_sub(timestamp) as u32;
         self.broadcast(ToClient::ClientListMessage(ClientListMessage::Update(
             client_id,
             ClientMessage::Ping(ping),
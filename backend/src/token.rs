@@ -1,3 +1,4 @@
+use backend_commons::client_info::Role;
 use hmac::{Hmac, Mac, digest::InvalidLength};
 use jwt::{Error, Header, SignWithKey, Token, VerifyWithKey};
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,7 @@ pub enum AuthError {
     KeyError(InvalidLength),
     SigningError(Error),
     InvalidToken(Error),
+    Expired,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -19,6 +21,24 @@ struct AuthenticationToken {
     id: u64,
 }
 
+#[derive(Serialize, Deserialize)]
+struct ShareToken {
+    room: String,
+    link: u64,
+    role: Role,
+    exp: u64,
+}
+
+/// Access to a room granted by a share link.
+#[derive(Clone, Debug)]
+pub struct ShareGrant {
+    pub room: String,
+    pub link: u64,
+    pub role: Role,
+    /// Seconds since the Unix epoch.
+    pub expires_at: u64,
+}
+
 #[derive(Clone)]
 pub struct UserData {
     pub id: u64,
@@ -27,8 +47,7 @@ pub struct UserData {
 
 impl Key {
     pub fn new(secret: String) -> Result<Self, AuthError> {
-        let internal =
-            Hmac::new_from_slice(secret.as_bytes()).map_err(|e| AuthError::KeyError(e))?;
+        let internal = Hmac::new_from_slice(secret.as_bytes()).map_err(AuthError::KeyError)?;
         Ok(Key(internal))
     }
 
@@ -39,17 +58,47 @@ impl Key {
         };
         claims
             .sign_with_key(&self.0)
-            .map_err(|e| AuthError::SigningError(e))
+            .map_err(AuthError::SigningError)
     }
 
     pub fn get_user_data(&self, token: &str) -> Result<UserData, AuthError> {
         let token: Token<Header, AuthenticationToken, _> = token
             .verify_with_key(&self.0)
-            .map_err(|e| AuthError::InvalidToken(e))?;
+            .map_err(AuthError::InvalidToken)?;
         let claims = token.claims();
         Ok(UserData {
             username: claims.sub.to_owned(),
             id: claims.id,
         })
     }
+
+    pub fn get_share_token(&self, grant: ShareGrant) -> Result<String, AuthError> {
+        let claims = ShareToken {
+            room: grant.room,
+            link: grant.link,
+            role: grant.role,
+            exp: grant.expires_at,
+        };
+        claims
+            .sign_with_key(&self.0)
+            .map_err(AuthError::SigningError)
+    }
+
+    /// Checks the signature and expiry of a share token. Whether the link was
+    /// revoked is up to the room.
+    pub fn get_share_grant(&self, token: &str, now: u64) -> Result<ShareGrant, AuthError> {
+        let token: Token<Header, ShareToken, _> = token
+            .verify_with_key(&self.0)
+            .map_err(AuthError::InvalidToken)?;
+        let claims = token.claims();
+        if claims.exp <= now {
+            return Err(AuthError::Expired);
+        }
+        Ok(ShareGrant {
+            room: claims.room.to_owned(),
+            link: claims.link,
+            role: claims.role,
+            expires_at: claims.exp,
+        })
+    }
 }
@@ -0,0 +1,260 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use game_back::game::Outcome;
+use menu_back::leaderboard::{LEADERBOARD_SIZE, Leaderboard, LeaderboardEntry};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::{create_dir_all, read, try_exists},
+    sync::Mutex,
+};
+
+use crate::atomic_file::write_atomically;
+
+/// Shared by the menu, which shows leaderboards, and the game rooms, which
+/// rate their matches.
+pub type SharedUsers = Arc<Mutex<UserStore>>;
+
+const INITIAL_RATING: f64 = 1500.0;
+/// Most a rating can change after one match.
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct Rating {
+    rating: f64,
+    games: u32,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: INITIAL_RATING,
+            games: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct User {
+    /// The name the user last played under.
+    name: String,
+    /// Elo rating by game type.
+    ratings: HashMap<String, Rating>,
+}
+
+/// Users who played rated matches, saved as a whole after every change.
+pub struct UserStore {
+    path: PathBuf,
+    users: HashMap<u64, User>,
+}
+
+/// Score of the first player in a match against the second.
+fn score(outcome: Outcome) -> f64 {
+    match outcome {
+        Outcome::Win(0) => 1.0,
+        Outcome::Win(_) => 0.0,
+        Outcome::Draw => 0.5,
+    }
+}
+
+impl UserStore {
+    pub async fn open(path: &Path) -> Result<Self> {
+        create_dir_all(path).await?;
+        let path = path.join("users");
+        let users = if try_exists(&path).await? {
+            let data = read(&path).await?;
+            postcard::from_bytes(&data).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Failed to deserialize users: {e}"),
+                )
+            })?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self { path, users })
+    }
+
+    /// Updates the Elo ratings of both players of a two-player match of
+    /// `kind`. `players` are by seat, with their ids and names.
+    pub async fn rate(
+        &mut self,
+        kind: &str,
+        players: [(u64, &str); 2],
+        outcome: Outcome,
+    ) -> Result<()> {
+        let [first, second] = players.map(|(id, _)| {
+            self.users
+                .get(&id)
+                .and_then(|user| user.ratings.get(kind))
+                .copied()
+                .unwrap_or_default()
+        });
+        let expected = 1.0 / (1.0 + 10f64.powf((second.rating - first.rating) / 400.0));
+        let change = K_FACTOR * (score(outcome) - expected);
+        for ((id, name), (rating, change)) in players
+            .into_iter()
+            .zip([(first, change), (second, -change)])
+        {
+            let user = self.users.entry(id).or_default();
+            user.name = name.to_owned();
+            user.ratings.insert(
+                kind.to_owned(),
+                Rating {
+                    rating: rating.rating + change,
+                    games: rating.games + 1,
+                },
+            );
+        }
+        self.save().await
+    }
+
+    pub fn leaderboard(&self, kind: &str) -> Leaderboard {
+        let mut entries: Vec<_> = self
+            .users
+            .iter()
+            .filter_map(|(&id, user)| {
+                let rating = user.ratings.get(kind)?;
+                Some(LeaderboardEntry {
+                    user: id,
+                    name: user.name.clone(),
+                    rating: rating.rating,
+                    games: rating.games,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        entries.truncate(LEADERBOARD_SIZE);
+        Leaderboard {
+            kind: kind.to_owned(),
+            entries,
+        }
+    }
+
+    async fn save(&self) -> Result<()> {
+        let data = postcard::to_stdvec(&self.users).unwrap();
+        write_atomically(&self.path, &data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use tokio::fs::remove_dir_all;
+
+    use super::*;
+
+    const ALICE: u64 = 1;
+    const BOB: u64 = 2;
+
+    /// A store in a directory of its own, with Alice rated 1600 and Bob 1400
+    /// at chess.
+    async fn store(name: &str) -> UserStore {
+        let dir = temp_dir().join(format!("user_store_{name}_{}", std::process::id()));
+        let _ = remove_dir_all(&dir).await;
+        let mut store = UserStore::open(&dir).await.unwrap();
+        for (id, name, rating, games) in [(ALICE, "Alice", 1600.0, 3), (BOB, "Bob", 1400.0, 5)] {
+            store.users.insert(
+                id,
+                User {
+                    name: name.to_owned(),
+                    ratings: HashMap::from([("chess".to_owned(), Rating { rating, games })]),
+                },
+            );
+        }
+        store
+    }
+
+    fn rating(store: &UserStore, id: u64) -> Rating {
+        store.users[&id].ratings["chess"]
+    }
+
+    #[tokio::test]
+    async fn ratings_move_by_the_same_amount_both_ways() {
+        // Alice is expected to score 1 / (1 + 10^(-200 / 400)) ≈ 0.76.
+        let cases = [
+            ("win", Outcome::Win(0), 7.688),
+            ("draw", Outcome::Draw, -8.312),
+            ("loss", Outcome::Win(1), -24.312),
+        ];
+        for (name, outcome, change) in cases {
+            let mut store = store(name).await;
+            store
+                .rate("chess", [(ALICE, "Alice"), (BOB, "Bobby")], outcome)
+                .await
+                .unwrap();
+            let (alice, bob) = (rating(&store, ALICE), rating(&store, BOB));
+            assert!((alice.rating - 1600.0 - change).abs() < 0.001, "{name}");
+            assert!((bob.rating - 1400.0 + change).abs() < 0.001, "{name}");
+            assert_eq!((alice.games, bob.games), (4, 6));
+            assert_eq!(store.users[&BOB].name, "Bobby");
+
+            let reopened = UserStore::open(store.path.parent().unwrap()).await.unwrap();
+            assert_eq!(rating(&reopened, ALICE).rating, alice.rating);
+            remove_dir_all(store.path.parent().unwrap()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn new_players_start_from_the_initial_rating() {
+        let mut store = store("new").await;
+        store
+            .rate("go", [(ALICE, "Alice"), (BOB, "Bob")], Outcome::Win(1))
+            .await
+            .unwrap();
+        let go = |id| store.users[&id].ratings["go"];
+        assert_eq!(go(ALICE).rating, INITIAL_RATING - K_FACTOR / 2.0);
+        assert_eq!(go(BOB).rating, INITIAL_RATING + K_FACTOR / 2.0);
+        assert_eq!((go(ALICE).games, go(BOB).games), (1, 1));
+        assert_eq!(rating(&store, ALICE).games, 3);
+        remove_dir_all(store.path.parent().unwrap()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn leaderboards_keep_the_best_rated_of_the_kind() {
+        let mut store = store("leaderboard").await;
+        for id in 10..10 + LEADERBOARD_SIZE as u64 {
+            store.users.insert(
+                id,
+                User {
+                    name: format!("Player {id}"),
+                    ratings: HashMap::from([(
+                        "chess".to_owned(),
+                        Rating {
+                            rating: 1000.0 + id as f64,
+                            games: 1,
+                        },
+                    )]),
+                },
+            );
+        }
+        store.users.get_mut(&ALICE).unwrap().ratings.clear();
+
+        let leaderboard = store.leaderboard("chess");
+        assert_eq!(leaderboard.kind, "chess");
+        assert_eq!(leaderboard.entries.len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard.entries[0].user, BOB);
+        assert_eq!(leaderboard.entries[0].name, "Bob");
+        assert!(
+            leaderboard
+                .entries
+                .windows(2)
+                .all(|pair| pair[0].rating >= pair[1].rating)
+        );
+        // The lowest rated player did not make it, nor did Alice, who has no
+        // chess rating.
+        assert!(
+            leaderboard
+                .entries
+                .iter()
+                .all(|entry| ![ALICE, 10].contains(&entry.user))
+        );
+        assert!(store.leaderboard("go").entries.is_empty());
+        remove_dir_all(store.path.parent().unwrap()).await.unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub author: u64,
+    pub author_name: String,
+    pub text: String,
+    /// Milliseconds since the Unix epoch.
+    pub sent_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChatListMessage {
+    /// Recent history, sent on join.
+    Set(Vec<ChatMessage>),
+    Added(ChatMessage),
+}
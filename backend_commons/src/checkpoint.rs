@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::store::EventPosition;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub name: String,
+    pub position: EventPosition,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CheckpointAction {
+    Save(String),
+    Delete(String),
+    Restore(String),
+    RestoreTo(EventPosition),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CheckpointListMessage {
+    Set(Vec<Checkpoint>),
+    Saved(Checkpoint),
+    Deleted(String),
+}
@@ -1,10 +1,41 @@
 use serde::{Deserialize, Serialize};
 
+/// Whether a client takes part in the match or only watches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientRole {
+    Player,
+    #[default]
+    Spectator,
+}
+
+/// Access level of a user in a test board room, which share links grant.
+/// Users without an explicit role are viewers.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    #[default]
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl Role {
+    pub fn can_edit(self) -> bool {
+        self >= Role::Editor
+    }
+
+    pub fn is_owner(self) -> bool {
+        self == Role::Owner
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientData {
     pub id: u64,
     pub name: String,
     pub ping: u32,
+    /// The seat the client plays in, for rooms with seats.
+    pub seat: Option<usize>,
+    pub role: ClientRole,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,4 +48,9 @@ pub enum ClientListMessage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientMessage {
     Ping(u32),
+    /// The client took or left a seat, or its role changed.
+    Seat {
+        seat: Option<usize>,
+        role: ClientRole,
+    },
 }
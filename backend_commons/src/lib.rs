@@ -1,2 +1,4 @@
+pub mod chat;
+pub mod checkpoint;
 pub mod client_info;
 pub mod store;
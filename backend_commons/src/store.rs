@@ -1,5 +1,7 @@
 use std::io::Result;
 
+use serde::{Deserialize, Serialize};
+
 pub trait StateBuilder {
     fn load_state(&mut self, version: u64, data: Vec<u8>) -> Result<()>;
     fn load_event(&mut self, version: u64, data: Vec<u8>) -> Result<()>;
@@ -9,3 +11,11 @@ pub trait Store {
     fn apply_event(&mut self, data: &[u8]) -> impl Future<Output = Result<()>>;
     fn snapshot(&mut self, data: &[u8]) -> impl Future<Output = Result<()>>;
 }
+
+/// A point in the history of a store: the state after the first `event`
+/// events of the log segment `log`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EventPosition {
+    pub log: u64,
+    pub event: u64,
+}
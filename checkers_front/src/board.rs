@@ -0,0 +1,138 @@
+use frontend_commons::command::{Command, CommandConsumer};
+use game_back::checkers::Square;
+
+#[derive(Debug, Clone)]
+pub enum BoardCommand {
+    Square(Square),
+    Sit(usize),
+    Stand,
+    Resign,
+}
+
+pub struct Board {
+    board: ts::Board,
+}
+
+impl Board {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, BoardCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            board: ts::Board::new(ts::BoardBackend::new(Box::new(consumer))),
+        }
+    }
+
+    /// Side length of the board and the names of the sides, by seat.
+    pub fn set_variant(&mut self, size: usize, colors: [&str; 2]) {
+        self.board.set_variant(size as u32, colors[0], colors[1]);
+    }
+
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.board.set_flipped(flipped);
+    }
+
+    /// One character per square: `a` or `A` for a man or king of seat 0,
+    /// `b` or `B` for seat 1, a space for empty squares.
+    pub fn set_pieces(&mut self, pieces: &str) {
+        self.board.set_pieces(pieces);
+    }
+
+    pub fn set_marks(&mut self, path: Vec<Square>, targets: Vec<Square>, last: Vec<Square>) {
+        self.board.set_marks(path, targets, last);
+    }
+
+    pub fn set_seats(&mut self, first: &str, second: &str) {
+        self.board.set_seats(first, second);
+    }
+
+    pub fn set_status(&mut self, text: &str) {
+        self.board.set_status(text);
+    }
+}
+
+impl<State, Cmd> ts::BoardObserver for CommandConsumer<State, BoardCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_square(&mut self, square: u8) {
+        self.consume(BoardCommand::Square(square));
+    }
+
+    fn on_sit(&mut self, seat: u32) {
+        self.consume(BoardCommand::Sit(seat as usize));
+    }
+
+    fn on_stand(&mut self) {
+        self.consume(BoardCommand::Stand);
+    }
+
+    fn on_resign(&mut self) {
+        self.consume(BoardCommand::Resign);
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait BoardObserver {
+        fn on_square(&mut self, square: u8);
+        fn on_sit(&mut self, seat: u32);
+        fn on_stand(&mut self);
+        fn on_resign(&mut self);
+    }
+
+    #[wasm_bindgen]
+    pub struct BoardBackend {
+        observer: Box<dyn BoardObserver>,
+    }
+
+    impl BoardBackend {
+        pub fn new(observer: Box<dyn BoardObserver>) -> BoardBackend {
+            BoardBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl BoardBackend {
+        pub fn on_square(&mut self, square: u8) {
+            self.observer.on_square(square);
+        }
+        pub fn on_sit(&mut self, seat: u32) {
+            self.observer.on_sit(seat);
+        }
+        pub fn on_stand(&mut self) {
+            self.observer.on_stand();
+        }
+        pub fn on_resign(&mut self) {
+            self.observer.on_resign();
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = board)]
+    unsafe extern "C" {
+        pub type Board;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: BoardBackend) -> Board;
+
+        #[wasm_bindgen(method)]
+        pub fn set_variant(this: &Board, size: u32, first: &str, second: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_flipped(this: &Board, flipped: bool);
+
+        #[wasm_bindgen(method)]
+        pub fn set_pieces(this: &Board, pieces: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_marks(this: &Board, path: Vec<u8>, targets: Vec<u8>, last: Vec<u8>);
+
+        #[wasm_bindgen(method)]
+        pub fn set_seats(this: &Board, first: &str, second: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_status(this: &Board, text: &str);
+    }
+}
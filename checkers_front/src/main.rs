@@ -0,0 +1,232 @@
+mod board;
+
+use std::collections::HashMap;
+
+use backend_commons::client_info::ClientListMessage;
+use frontend_commons::{
+    chat::{Chat, ChatCommand},
+    clock::Clock,
+    command::{Command, CommandConsumerGenerator},
+    socket::{Socket, SocketCommand},
+};
+use game_back::{
+    checkers::{CheckersEnd, CheckersMove, CheckersView, Square},
+    game::{GameState, Outcome},
+};
+use log::{Level, debug, info};
+use web_sys::js_sys::Date;
+
+use crate::board::{Board, BoardCommand};
+
+type ToClient = game_back::ToClient<CheckersView>;
+type ToServer = game_back::ToServer<CheckersMove>;
+
+struct CheckersState {
+    socket: Socket<ToClient, ToServer>,
+    chat: Chat,
+    clock: Clock,
+    board: Board,
+    /// Names of the users in the room, to show who sits where.
+    names: HashMap<u64, String>,
+    game: Option<GameState<CheckersView>>,
+    /// Squares of the move being entered, starting with the piece.
+    path: Vec<Square>,
+}
+
+#[derive(Debug)]
+enum CheckersCommand {
+    /// Boxed, as states are much larger than the other commands.
+    Socket(Box<SocketCommand<ToClient>>),
+    Chat(ChatCommand),
+    Board(BoardCommand),
+}
+
+fn status(game: &GameState<CheckersView>) -> String {
+    let view = &game.view;
+    let colors = view.variant.colors();
+    match (game.outcome, view.end) {
+        (Some(Outcome::Win(seat)), Some(CheckersEnd::Blocked { .. })) => {
+            format!("{} wins", colors[seat])
+        }
+        (Some(Outcome::Win(seat)), _) if game.ended_on_time() => {
+            format!("{} wins on time", colors[seat])
+        }
+        (Some(Outcome::Win(seat)), _) => format!("{} wins by resignation", colors[seat]),
+        (Some(Outcome::Draw), _) => "Draw by repetition".to_owned(),
+        (None, _) => format!("{} to move", colors[view.to_move]),
+    }
+}
+
+impl CheckersState {
+    fn render(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let view = &game.view;
+        let pieces: String = view
+            .board
+            .iter()
+            .map(|piece| match piece {
+                Some(piece) => match (piece.seat, piece.king) {
+                    (0, false) => 'a',
+                    (0, true) => 'A',
+                    (_, false) => 'b',
+                    (_, true) => 'B',
+                },
+                None => ' ',
+            })
+            .collect();
+        let targets = view
+            .legal_moves
+            .iter()
+            .filter(|played| played.path.starts_with(&self.path) && !self.path.is_empty())
+            .filter_map(|played| played.path.get(self.path.len()).copied())
+            .collect();
+        let last = view
+            .last_move
+            .as_ref()
+            .map(|played| played.path.clone())
+            .unwrap_or_default();
+        let seat_name = |seat: usize| match game.seats.get(seat).copied().flatten() {
+            Some(user) => self
+                .names
+                .get(&user)
+                .cloned()
+                .unwrap_or_else(|| format!("#{user}")),
+            None => "(free)".to_owned(),
+        };
+        let (first, second) = (seat_name(0), seat_name(1));
+        self.board
+            .set_variant(view.variant.size(), view.variant.colors());
+        self.board.set_flipped(game.seat == Some(1));
+        self.board.set_pieces(&pieces);
+        self.board.set_marks(self.path.clone(), targets, last);
+        self.board.set_seats(&first, &second);
+        match &game.clock {
+            Some(clock) => self.clock.set(
+                view.variant.colors(),
+                &clock.remaining,
+                clock.running,
+                clock.delay_left,
+            ),
+            None => self.clock.hide(),
+        }
+        self.board.set_status(&status(game));
+    }
+
+    fn receive(&mut self, message: ToClient) {
+        debug!("{:?}", message);
+        match message {
+            ToClient::State(game) => {
+                self.game = Some(game);
+                self.path = Vec::new();
+                self.render();
+            }
+            ToClient::Rejected(reason) => {
+                self.chat.notice("checkers", Date::now() as u64, &reason);
+            }
+            ToClient::ChatListMessage(message) => self.chat.consume(message),
+            ToClient::ClientListMessage(message) => {
+                if let ClientListMessage::Joined(data) = message {
+                    self.names.insert(data.id, data.name);
+                    self.render();
+                }
+            }
+        }
+    }
+
+    /// Clicking a piece that can move starts a move, then each click adds
+    /// a square it lands on until the move is complete.
+    fn click(&mut self, square: Square) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let legal_moves = &game.view.legal_moves;
+        let mut path = self.path.clone();
+        path.push(square);
+        if self.path.is_empty()
+            || !legal_moves
+                .iter()
+                .any(|played| played.path.starts_with(&path))
+        {
+            path = vec![square];
+        }
+        if legal_moves.iter().any(|played| played.path == path) {
+            self.socket.send(ToServer::Move(CheckersMove { path }));
+            self.path = Vec::new();
+        } else if legal_moves
+            .iter()
+            .any(|played| played.path.starts_with(&path))
+        {
+            self.path = path;
+        } else {
+            self.path = Vec::new();
+        }
+        self.render();
+    }
+}
+
+impl Command<CheckersState> for CheckersCommand {
+    fn apply(self, state: &mut CheckersState) {
+        match self {
+            CheckersCommand::Chat(ChatCommand::Send(text)) => {
+                state.socket.send(ToServer::Chat(text));
+            }
+            CheckersCommand::Board(command) => match command {
+                BoardCommand::Square(square) => state.click(square),
+                BoardCommand::Sit(seat) => state.socket.send(ToServer::Sit(seat)),
+                BoardCommand::Stand => state.socket.send(ToServer::Stand),
+                BoardCommand::Resign => state.socket.send(ToServer::Resign),
+            },
+            CheckersCommand::Socket(command) => {
+                if let SocketCommand::Data(message) = *command {
+                    state.receive(message);
+                }
+            }
+        }
+    }
+}
+
+impl From<SocketCommand<ToClient>> for CheckersCommand {
+    fn from(value: SocketCommand<ToClient>) -> Self {
+        CheckersCommand::Socket(Box::new(value))
+    }
+}
+
+impl From<ChatCommand> for CheckersCommand {
+    fn from(value: ChatCommand) -> Self {
+        CheckersCommand::Chat(value)
+    }
+}
+
+impl From<BoardCommand> for CheckersCommand {
+    fn from(value: BoardCommand) -> Self {
+        CheckersCommand::Board(value)
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(Level::Debug).unwrap();
+    info!("Hello, world!");
+    // Settings such as `?variant=international&time=300` are passed on to
+    // the server, which uses them if the room is new.
+    let query = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+    let mut consumer_generator: CommandConsumerGenerator<_, CheckersCommand> =
+        CommandConsumerGenerator::new();
+    let state = CheckersState {
+        socket: Socket::new(
+            consumer_generator.make_consumer(),
+            &format!("socket{query}"),
+        ),
+        chat: Chat::new(consumer_generator.make_consumer()),
+        clock: Clock::new(),
+        board: Board::new(consumer_generator.make_consumer()),
+        names: HashMap::new(),
+        game: None,
+        path: Vec::new(),
+    };
+    consumer_generator.activate(state);
+}
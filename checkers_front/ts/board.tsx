@@ -0,0 +1,144 @@
+import JSX from "commons/createElement";
+
+export interface BoardObserver {
+    on_square(square: number): void;
+    on_sit(seat: number): void;
+    on_stand(): void;
+    on_resign(): void;
+}
+
+const CELL = 56;
+const LIGHT = "#f0d9b5";
+const DARK = "#8b5a2b";
+const SELECTED = "#f7ec74";
+const TARGET = "#aad576";
+const LAST = "#b5a042";
+const PIECES = { "Black": "#222", "White": "#f5f5f5" };
+
+// Squares are numbered row by row from the top left corner, seat 0 starting
+// at the bottom.
+export class Board {
+    private observer: BoardObserver;
+    private squares: HTMLElement[] = [];
+    private pieces: HTMLElement[] = [];
+    private grid: HTMLElement;
+    private status: HTMLElement;
+    private first: HTMLElement;
+    private second: HTMLElement;
+    private sitFirst: HTMLElement;
+    private sitSecond: HTMLElement;
+    private colors = ["Black", "White"];
+    private size = 0;
+    private flipped = false;
+
+    public constructor(observer: BoardObserver) {
+        this.observer = observer;
+        this.grid = <div style="display: grid; border: 2px solid #333; user-select: none; align-self: flex-start"></div>;
+
+        this.status = <div style="margin: 8px 0; font-weight: bold"></div>;
+        this.first = <span></span>;
+        this.second = <span></span>;
+        this.sitFirst = <button></button>;
+        this.sitFirst.addEventListener("click", _ => observer.on_sit(0));
+        this.sitSecond = <button></button>;
+        this.sitSecond.addEventListener("click", _ => observer.on_sit(1));
+        const stand = <button>Stand up</button>;
+        stand.addEventListener("click", _ => observer.on_stand());
+        const resign = <button>Resign</button>;
+        resign.addEventListener("click", _ => observer.on_resign());
+
+        document.body.appendChild(<div style="display: flex; gap: 16px; padding: 16px; font-family: sans-serif; font-size: 14px">
+            {this.grid}
+            <div style="width: 280px">
+                <div>{this.first}</div>
+                <div>{this.second}</div>
+                {this.status}
+                <div>{this.sitFirst} {this.sitSecond} {stand} {resign}</div>
+            </div>
+        </div>);
+    }
+
+    // Rebuilds the grid when the size changes, and names the sides.
+    public set_variant(size: number, first: string, second: string) {
+        this.colors = [first, second];
+        this.sitFirst.innerText = `Play ${first.toLowerCase()}`;
+        this.sitSecond.innerText = `Play ${second.toLowerCase()}`;
+        if (this.size === size) {
+            return;
+        }
+        this.size = size;
+        this.squares = [];
+        this.pieces = [];
+        this.grid.style.gridTemplateColumns = `repeat(${size}, ${CELL}px)`;
+        this.grid.style.gridTemplateRows = `repeat(${size}, ${CELL}px)`;
+        for (let square = 0; square < size * size; square++) {
+            const piece = <div style={`width: ${CELL - 12}px; height: ${CELL - 12}px; border-radius: 50%; box-sizing: border-box; display: flex; align-items: center; justify-content: center; font-size: 28px`}></div>;
+            const element = <div style="display: flex; align-items: center; justify-content: center; cursor: pointer">{piece}</div>;
+            element.addEventListener("click", _ => this.observer.on_square(square));
+            this.squares.push(element);
+            this.pieces.push(piece);
+        }
+        this.layOut();
+    }
+
+    private layOut() {
+        const order = this.flipped ? [...this.squares].reverse() : this.squares;
+        this.grid.replaceChildren(...order);
+    }
+
+    // Seat 1 sees the board from its side.
+    public set_flipped(flipped: boolean) {
+        if (this.flipped !== flipped) {
+            this.flipped = flipped;
+            this.layOut();
+        }
+    }
+
+    // One character per square: 'a' for a man of seat 0, 'A' for its king,
+    // 'b' and 'B' for seat 1, a space for empty squares.
+    public set_pieces(pieces: string) {
+        for (let square = 0; square < this.pieces.length; square++) {
+            const piece = pieces.charAt(square);
+            const style = this.pieces[square].style;
+            if (piece === " ") {
+                style.background = "none";
+                style.border = "none";
+                this.pieces[square].innerText = "";
+                continue;
+            }
+            const color = this.colors[piece.toLowerCase() === "a" ? 0 : 1];
+            style.background = PIECES[color];
+            style.border = "2px solid #555";
+            style.color = color === "Black" ? "#f5f5f5" : "#222";
+            this.pieces[square].innerText = piece === piece.toUpperCase() ? "♔" : "";
+        }
+    }
+
+    // The squares of the move being entered, where it can continue, and
+    // the last move.
+    public set_marks(path: Uint8Array, targets: Uint8Array, last: Uint8Array) {
+        for (let square = 0; square < this.squares.length; square++) {
+            const dark = (Math.floor(square / this.size) + square % this.size) % 2 === 1;
+            let color = dark ? DARK : LIGHT;
+            if (last.includes(square)) {
+                color = LAST;
+            }
+            if (targets.includes(square)) {
+                color = TARGET;
+            }
+            if (path.includes(square)) {
+                color = SELECTED;
+            }
+            this.squares[square].style.background = color;
+        }
+    }
+
+    public set_seats(first: string, second: string) {
+        this.first.innerText = `${this.colors[0]}: ${first}`;
+        this.second.innerText = `${this.colors[1]}: ${second}`;
+    }
+
+    public set_status(text: string) {
+        this.status.innerText = text;
+    }
+}
@@ -0,0 +1,18 @@
+// specify used components
+
+import * as _socket from 'commons/socket'
+import * as _chat from 'commons/chat'
+import * as _clock from 'commons/clock'
+import * as _board from './ts/board'
+
+declare var socket;
+socket = _socket;
+
+declare var chat;
+chat = _chat;
+
+declare var clock;
+clock = _clock;
+
+declare var board;
+board = _board;
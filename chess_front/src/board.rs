@@ -0,0 +1,147 @@
+use frontend_commons::command::{Command, CommandConsumer};
+use game_back::chess::Square;
+
+#[derive(Debug, Clone)]
+pub enum BoardCommand {
+    Square(Square),
+    Sit(usize),
+    Stand,
+    Resign,
+    /// Letter of the piece pawns are promoted to.
+    Promotion(String),
+}
+
+pub struct Board {
+    board: ts::Board,
+}
+
+impl Board {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, BoardCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            board: ts::Board::new(ts::BoardBackend::new(Box::new(consumer))),
+        }
+    }
+
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.board.set_flipped(flipped);
+    }
+
+    /// One character per square, a space for empty ones.
+    pub fn set_pieces(&mut self, pieces: &str) {
+        self.board.set_pieces(pieces);
+    }
+
+    pub fn set_marks(&mut self, selected: Option<Square>, targets: Vec<Square>, last: Vec<Square>) {
+        let selected = selected.map_or(-1, i32::from);
+        self.board.set_marks(selected, targets, last);
+    }
+
+    pub fn set_seats(&mut self, white: &str, black: &str) {
+        self.board.set_seats(white, black);
+    }
+
+    pub fn set_status(&mut self, text: &str) {
+        self.board.set_status(text);
+    }
+
+    pub fn set_history(&mut self, text: &str) {
+        self.board.set_history(text);
+    }
+}
+
+impl<State, Cmd> ts::BoardObserver for CommandConsumer<State, BoardCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_square(&mut self, square: u8) {
+        self.consume(BoardCommand::Square(square));
+    }
+
+    fn on_sit(&mut self, seat: u32) {
+        self.consume(BoardCommand::Sit(seat as usize));
+    }
+
+    fn on_stand(&mut self) {
+        self.consume(BoardCommand::Stand);
+    }
+
+    fn on_resign(&mut self) {
+        self.consume(BoardCommand::Resign);
+    }
+
+    fn on_promotion(&mut self, piece: String) {
+        self.consume(BoardCommand::Promotion(piece));
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait BoardObserver {
+        fn on_square(&mut self, square: u8);
+        fn on_sit(&mut self, seat: u32);
+        fn on_stand(&mut self);
+        fn on_resign(&mut self);
+        fn on_promotion(&mut self, piece: String);
+    }
+
+    #[wasm_bindgen]
+    pub struct BoardBackend {
+        observer: Box<dyn BoardObserver>,
+    }
+
+    impl BoardBackend {
+        pub fn new(observer: Box<dyn BoardObserver>) -> BoardBackend {
+            BoardBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl BoardBackend {
+        pub fn on_square(&mut self, square: u8) {
+            self.observer.on_square(square);
+        }
+        pub fn on_sit(&mut self, seat: u32) {
+            self.observer.on_sit(seat);
+        }
+        pub fn on_stand(&mut self) {
+            self.observer.on_stand();
+        }
+        pub fn on_resign(&mut self) {
+            self.observer.on_resign();
+        }
+        pub fn on_promotion(&mut self, piece: String) {
+            self.observer.on_promotion(piece);
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = board)]
+    unsafe extern "C" {
+        pub type Board;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: BoardBackend) -> Board;
+
+        #[wasm_bindgen(method)]
+        pub fn set_flipped(this: &Board, flipped: bool);
+
+        #[wasm_bindgen(method)]
+        pub fn set_pieces(this: &Board, pieces: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_marks(this: &Board, selected: i32, targets: Vec<u8>, last: Vec<u8>);
+
+        #[wasm_bindgen(method)]
+        pub fn set_seats(this: &Board, white: &str, black: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_status(this: &Board, text: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_history(this: &Board, text: &str);
+    }
+}
@@ -0,0 +1,260 @@
+mod board;
+
+use std::collections::HashMap;
+
+use backend_commons::client_info::ClientListMessage;
+use frontend_commons::{
+    chat::{Chat, ChatCommand},
+    clock::Clock,
+    command::{Command, CommandConsumerGenerator},
+    socket::{Socket, SocketCommand},
+};
+use game_back::{
+    chess::{ChessEnd, ChessMove, ChessView, Color, Kind, Square},
+    game::{GameState, Outcome},
+};
+use log::{Level, debug, info};
+use web_sys::js_sys::Date;
+
+use crate::board::{Board, BoardCommand};
+
+type ToClient = game_back::ToClient<ChessView>;
+type ToServer = game_back::ToServer<ChessMove>;
+
+struct ChessState {
+    socket: Socket<ToClient, ToServer>,
+    chat: Chat,
+    clock: Clock,
+    board: Board,
+    /// Names of the users in the room, to show who sits where.
+    names: HashMap<u64, String>,
+    game: Option<GameState<ChessView>>,
+    /// Square of the piece about to be moved.
+    selected: Option<Square>,
+    promotion: Kind,
+}
+
+#[derive(Debug)]
+enum ChessCommand {
+    Socket(SocketCommand<ToClient>),
+    Chat(ChatCommand),
+    Board(BoardCommand),
+}
+
+fn glyph(color: Color, kind: Kind) -> char {
+    let glyphs = match color {
+        Color::White => ['♙', '♘', '♗', '♖', '♕', '♔'],
+        Color::Black => ['♟', '♞', '♝', '♜', '♛', '♚'],
+    };
+    glyphs[kind as usize]
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+fn status(game: &GameState<ChessView>) -> String {
+    let view = &game.view;
+    match (game.outcome, view.end) {
+        (Some(Outcome::Win(seat)), Some(ChessEnd::Checkmate { .. })) => {
+            format!("{} wins by checkmate", color_name(Color::from_seat(seat)))
+        }
+        (Some(Outcome::Win(seat)), _) if game.ended_on_time() => {
+            format!("{} wins on time", color_name(Color::from_seat(seat)))
+        }
+        (Some(Outcome::Win(seat)), _) => {
+            format!("{} wins by resignation", color_name(Color::from_seat(seat)))
+        }
+        (Some(Outcome::Draw), end) => match end {
+            Some(ChessEnd::Stalemate) => "Draw by stalemate",
+            Some(ChessEnd::InsufficientMaterial) => "Draw by insufficient material",
+            Some(ChessEnd::FiftyMoves) => "Draw by the fifty-move rule",
+            Some(ChessEnd::Repetition) => "Draw by repetition",
+            _ => "Draw",
+        }
+        .to_owned(),
+        (None, _) => {
+            let check = if view.check { ", check" } else { "" };
+            format!("{} to move{}", color_name(view.to_move), check)
+        }
+    }
+}
+
+/// Such as `1. e4 e5 2. Nf3`.
+fn history(moves: &[String]) -> String {
+    moves
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| format!("{}. {}", i + 1, pair.join(" ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ChessState {
+    fn render(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let view = &game.view;
+        let pieces: String = view
+            .board
+            .iter()
+            .map(|piece| piece.map_or(' ', |piece| glyph(piece.color, piece.kind)))
+            .collect();
+        let targets = view
+            .legal_moves
+            .iter()
+            .filter(|played| Some(played.from) == self.selected)
+            .map(|played| played.to)
+            .collect();
+        let last = view
+            .last_move
+            .map(|played| vec![played.from, played.to])
+            .unwrap_or_default();
+        let seat_name = |seat: usize| match game.seats.get(seat).copied().flatten() {
+            Some(user) => self
+                .names
+                .get(&user)
+                .cloned()
+                .unwrap_or_else(|| format!("#{user}")),
+            None => "(free)".to_owned(),
+        };
+        let (white, black) = (seat_name(0), seat_name(1));
+        self.board
+            .set_flipped(game.seat == Some(Color::Black.seat()));
+        self.board.set_pieces(&pieces);
+        self.board.set_marks(self.selected, targets, last);
+        self.board.set_seats(&white, &black);
+        match &game.clock {
+            Some(clock) => self.clock.set(
+                [color_name(Color::White), color_name(Color::Black)],
+                &clock.remaining,
+                clock.running,
+                clock.delay_left,
+            ),
+            None => self.clock.hide(),
+        }
+        self.board.set_status(&status(game));
+        self.board.set_history(&history(&view.history));
+    }
+
+    /// Clicking one of your pieces selects it, clicking where it can go
+    /// then moves it.
+    fn click(&mut self, square: Square) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let legal_moves = &game.view.legal_moves;
+        if let Some(from) = self.selected {
+            let played = legal_moves.iter().find(|played| {
+                played.from == from
+                    && played.to == square
+                    && played.promotion.is_none_or(|kind| kind == self.promotion)
+            });
+            if let Some(&played) = played {
+                self.socket.send(ToServer::Move(played));
+                self.selected = None;
+                self.render();
+                return;
+            }
+        }
+        let movable = legal_moves.iter().any(|played| played.from == square);
+        self.selected = movable.then_some(square);
+        self.render();
+    }
+
+    fn set_promotion(&mut self, letter: &str) {
+        self.promotion = match letter {
+            "R" => Kind::Rook,
+            "B" => Kind::Bishop,
+            "N" => Kind::Knight,
+            _ => Kind::Queen,
+        };
+    }
+}
+
+impl Command<ChessState> for ChessCommand {
+    fn apply(self, state: &mut ChessState) {
+        match self {
+            ChessCommand::Chat(ChatCommand::Send(text)) => {
+                state.socket.send(ToServer::Chat(text));
+            }
+            ChessCommand::Board(command) => match command {
+                BoardCommand::Square(square) => state.click(square),
+                BoardCommand::Sit(seat) => state.socket.send(ToServer::Sit(seat)),
+                BoardCommand::Stand => state.socket.send(ToServer::Stand),
+                BoardCommand::Resign => state.socket.send(ToServer::Resign),
+                BoardCommand::Promotion(letter) => state.set_promotion(&letter),
+            },
+            ChessCommand::Socket(SocketCommand::Data(message)) => {
+                debug!("{:?}", message);
+                match message {
+                    ToClient::State(game) => {
+                        state.game = Some(game);
+                        state.selected = None;
+                        state.render();
+                    }
+                    ToClient::Rejected(reason) => {
+                        state.chat.notice("chess", Date::now() as u64, &reason);
+                    }
+                    ToClient::ChatListMessage(message) => state.chat.consume(message),
+                    ToClient::ClientListMessage(message) => {
+                        if let ClientListMessage::Joined(data) = message {
+                            state.names.insert(data.id, data.name);
+                            state.render();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl From<SocketCommand<ToClient>> for ChessCommand {
+    fn from(value: SocketCommand<ToClient>) -> Self {
+        ChessCommand::Socket(value)
+    }
+}
+
+impl From<ChatCommand> for ChessCommand {
+    fn from(value: ChatCommand) -> Self {
+        ChessCommand::Chat(value)
+    }
+}
+
+impl From<BoardCommand> for ChessCommand {
+    fn from(value: BoardCommand) -> Self {
+        ChessCommand::Board(value)
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(Level::Debug).unwrap();
+    info!("Hello, world!");
+    // The time control, like `?time=300&increment=2`, is passed on to the
+    // server, which uses it if the room is new.
+    let query = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+    let mut consumer_generator: CommandConsumerGenerator<_, ChessCommand> =
+        CommandConsumerGenerator::new();
+    let state = ChessState {
+        socket: Socket::new(
+            consumer_generator.make_consumer(),
+            &format!("socket{query}"),
+        ),
+        chat: Chat::new(consumer_generator.make_consumer()),
+        clock: Clock::new(),
+        board: Board::new(consumer_generator.make_consumer()),
+        names: HashMap::new(),
+        game: None,
+        selected: None,
+        promotion: Kind::Queen,
+    };
+    consumer_generator.activate(state);
+}
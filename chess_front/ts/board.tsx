@@ -0,0 +1,126 @@
+import JSX from "commons/createElement";
+
+export interface BoardObserver {
+    on_square(square: number): void;
+    on_sit(seat: number): void;
+    on_stand(): void;
+    on_resign(): void;
+    on_promotion(piece: string): void;
+}
+
+const LIGHT = "#f0d9b5";
+const DARK = "#b58863";
+const SELECTED = "#f7ec74";
+const TARGET = "#aad576";
+const LAST = "#cdd26a";
+
+// Squares are numbered from a1 = 0 to h8 = 63, rank by rank.
+export class Board {
+    private squares: HTMLElement[] = [];
+    private grid: HTMLElement;
+    private status: HTMLElement;
+    private white: HTMLElement;
+    private black: HTMLElement;
+    private history: HTMLElement;
+    private flipped = false;
+
+    public constructor(observer: BoardObserver) {
+        this.grid = <div style="display: grid; grid-template-columns: repeat(8, 64px); grid-template-rows: repeat(8, 64px); border: 2px solid #333; user-select: none"></div>;
+        for (let square = 0; square < 64; square++) {
+            const element = <div style="display: flex; align-items: center; justify-content: center; font-size: 48px; cursor: pointer"></div>;
+            element.addEventListener("click", _ => observer.on_square(square));
+            this.squares.push(element);
+        }
+        this.layOut();
+
+        this.status = <div style="margin: 8px 0; font-weight: bold"></div>;
+        this.white = <span></span>;
+        this.black = <span></span>;
+        const sitWhite = <button>Play white</button>;
+        sitWhite.addEventListener("click", _ => observer.on_sit(0));
+        const sitBlack = <button>Play black</button>;
+        sitBlack.addEventListener("click", _ => observer.on_sit(1));
+        const stand = <button>Stand up</button>;
+        stand.addEventListener("click", _ => observer.on_stand());
+        const resign = <button>Resign</button>;
+        resign.addEventListener("click", _ => observer.on_resign());
+        const promotion = <select>
+            <option value="Q">Queen</option>
+            <option value="R">Rook</option>
+            <option value="B">Bishop</option>
+            <option value="N">Knight</option>
+        </select> as HTMLSelectElement;
+        promotion.addEventListener("change", _ => observer.on_promotion(promotion.value));
+        this.history = <div style="max-height: 400px; overflow-y: auto; white-space: pre-wrap"></div>;
+
+        document.body.appendChild(<div style="display: flex; gap: 16px; padding: 16px; font-family: sans-serif; font-size: 14px">
+            {this.grid}
+            <div style="width: 280px">
+                <div>White: {this.white}</div>
+                <div>Black: {this.black}</div>
+                {this.status}
+                <div>{sitWhite} {sitBlack} {stand} {resign}</div>
+                <label>Promote to {promotion}</label>
+                {this.history}
+            </div>
+        </div>);
+    }
+
+    private layOut() {
+        const order = [];
+        for (let row = 0; row < 8; row++) {
+            for (let column = 0; column < 8; column++) {
+                const rank = this.flipped ? row : 7 - row;
+                const file = this.flipped ? 7 - column : column;
+                order.push(this.squares[rank * 8 + file]);
+            }
+        }
+        this.grid.replaceChildren(...order);
+    }
+
+    // Black sees the board from its side.
+    public set_flipped(flipped: boolean) {
+        if (this.flipped !== flipped) {
+            this.flipped = flipped;
+            this.layOut();
+        }
+    }
+
+    // One character per square, a space for empty ones.
+    public set_pieces(pieces: string) {
+        for (let square = 0; square < 64; square++) {
+            this.squares[square].innerText = pieces.charAt(square).trim();
+        }
+    }
+
+    // -1 when nothing is selected.
+    public set_marks(selected: number, targets: Uint8Array, last: Uint8Array) {
+        for (let square = 0; square < 64; square++) {
+            const light = (Math.floor(square / 8) + square % 8) % 2 === 1;
+            let color = light ? LIGHT : DARK;
+            if (last.includes(square)) {
+                color = LAST;
+            }
+            if (targets.includes(square)) {
+                color = TARGET;
+            }
+            if (square === selected) {
+                color = SELECTED;
+            }
+            this.squares[square].style.background = color;
+        }
+    }
+
+    public set_seats(white: string, black: string) {
+        this.white.innerText = white;
+        this.black.innerText = black;
+    }
+
+    public set_status(text: string) {
+        this.status.innerText = text;
+    }
+
+    public set_history(text: string) {
+        this.history.innerText = text;
+    }
+}
@@ -0,0 +1,134 @@
+use frontend_commons::command::{Command, CommandConsumer};
+
+#[derive(Debug, Clone)]
+pub enum BoardCommand {
+    Column(u8),
+    Sit(usize),
+    Stand,
+    Resign,
+    AddBot(usize),
+    RemoveBot(usize),
+}
+
+pub struct Board {
+    board: ts::Board,
+}
+
+impl Board {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, BoardCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            board: ts::Board::new(ts::BoardBackend::new(Box::new(consumer))),
+        }
+    }
+
+    /// One character per cell, `r`, `y` or a space.
+    pub fn set_cells(&mut self, cells: &str, last: Option<usize>, line: &[usize]) {
+        let last = last.map_or(-1, |last| last as i32);
+        let line = line.iter().map(|&index| index as u32).collect();
+        self.board.set_cells(cells, last, line);
+    }
+
+    pub fn set_seats(&mut self, red: &str, yellow: &str) {
+        self.board.set_seats(red, yellow);
+    }
+
+    pub fn set_status(&mut self, text: &str) {
+        self.board.set_status(text);
+    }
+}
+
+impl<State, Cmd> ts::BoardObserver for CommandConsumer<State, BoardCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_column(&mut self, column: u8) {
+        self.consume(BoardCommand::Column(column));
+    }
+
+    fn on_sit(&mut self, seat: u32) {
+        self.consume(BoardCommand::Sit(seat as usize));
+    }
+
+    fn on_stand(&mut self) {
+        self.consume(BoardCommand::Stand);
+    }
+
+    fn on_resign(&mut self) {
+        self.consume(BoardCommand::Resign);
+    }
+
+    fn on_add_bot(&mut self, seat: u32) {
+        self.consume(BoardCommand::AddBot(seat as usize));
+    }
+
+    fn on_remove_bot(&mut self, seat: u32) {
+        self.consume(BoardCommand::RemoveBot(seat as usize));
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait BoardObserver {
+        fn on_column(&mut self, column: u8);
+        fn on_sit(&mut self, seat: u32);
+        fn on_stand(&mut self);
+        fn on_resign(&mut self);
+        fn on_add_bot(&mut self, seat: u32);
+        fn on_remove_bot(&mut self, seat: u32);
+    }
+
+    #[wasm_bindgen]
+    pub struct BoardBackend {
+        observer: Box<dyn BoardObserver>,
+    }
+
+    impl BoardBackend {
+        pub fn new(observer: Box<dyn BoardObserver>) -> BoardBackend {
+            BoardBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl BoardBackend {
+        pub fn on_column(&mut self, column: u8) {
+            self.observer.on_column(column);
+        }
+        pub fn on_sit(&mut self, seat: u32) {
+            self.observer.on_sit(seat);
+        }
+        pub fn on_stand(&mut self) {
+            self.observer.on_stand();
+        }
+        pub fn on_resign(&mut self) {
+            self.observer.on_resign();
+        }
+        pub fn on_add_bot(&mut self, seat: u32) {
+            self.observer.on_add_bot(seat);
+        }
+        pub fn on_remove_bot(&mut self, seat: u32) {
+            self.observer.on_remove_bot(seat);
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = board)]
+    unsafe extern "C" {
+        pub type Board;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: BoardBackend) -> Board;
+
+        #[wasm_bindgen(method)]
+        pub fn set_cells(this: &Board, cells: &str, last: i32, line: Vec<u32>);
+
+        #[wasm_bindgen(method)]
+        pub fn set_seats(this: &Board, red: &str, yellow: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_status(this: &Board, text: &str);
+    }
+}
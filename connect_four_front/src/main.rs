@@ -0,0 +1,183 @@
+mod board;
+
+use std::collections::HashMap;
+
+use backend_commons::client_info::ClientListMessage;
+use frontend_commons::{
+    chat::{Chat, ChatCommand},
+    clock::Clock,
+    command::{Command, CommandConsumerGenerator},
+    socket::{Socket, SocketCommand},
+};
+use game_back::{
+    connect_four::{ConnectFourMove, ConnectFourView, Disc},
+    game::{GameState, Outcome},
+};
+use log::{Level, debug, info};
+use web_sys::js_sys::Date;
+
+use crate::board::{Board, BoardCommand};
+
+type ToClient = game_back::ToClient<ConnectFourView>;
+type ToServer = game_back::ToServer<ConnectFourMove>;
+
+struct ConnectFourState {
+    socket: Socket<ToClient, ToServer>,
+    chat: Chat,
+    clock: Clock,
+    board: Board,
+    /// Names of the users in the room, to show who sits where.
+    names: HashMap<u64, String>,
+    game: Option<GameState<ConnectFourView>>,
+}
+
+#[derive(Debug)]
+enum ConnectFourCommand {
+    Socket(SocketCommand<ToClient>),
+    Chat(ChatCommand),
+    Board(BoardCommand),
+}
+
+fn disc_name(disc: Disc) -> &'static str {
+    match disc {
+        Disc::Red => "Red",
+        Disc::Yellow => "Yellow",
+    }
+}
+
+fn status(game: &GameState<ConnectFourView>) -> String {
+    let view = &game.view;
+    match game.outcome {
+        Some(Outcome::Win(seat)) if game.ended_on_time() => {
+            format!("{} wins on time", disc_name(Disc::from_seat(seat)))
+        }
+        Some(Outcome::Win(seat)) if view.line.is_empty() => {
+            format!("{} wins by resignation", disc_name(Disc::from_seat(seat)))
+        }
+        Some(Outcome::Win(seat)) => format!("{} wins", disc_name(Disc::from_seat(seat))),
+        Some(Outcome::Draw) => "Draw".to_owned(),
+        None => format!("{} to move", disc_name(view.to_move)),
+    }
+}
+
+impl ConnectFourState {
+    fn render(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let view = &game.view;
+        let cells: String = view
+            .board
+            .iter()
+            .map(|cell| match cell {
+                Some(Disc::Red) => 'r',
+                Some(Disc::Yellow) => 'y',
+                None => ' ',
+            })
+            .collect();
+        let seat_name = |seat: usize| match game.seats.get(seat).copied().flatten() {
+            Some(user) => self
+                .names
+                .get(&user)
+                .cloned()
+                .unwrap_or_else(|| format!("#{user}")),
+            None => "(free)".to_owned(),
+        };
+        let (red, yellow) = (seat_name(0), seat_name(1));
+        self.board.set_cells(&cells, view.last, &view.line);
+        self.board.set_seats(&red, &yellow);
+        match &game.clock {
+            Some(clock) => self.clock.set(
+                [disc_name(Disc::Red), disc_name(Disc::Yellow)],
+                &clock.remaining,
+                clock.running,
+                clock.delay_left,
+            ),
+            None => self.clock.hide(),
+        }
+        self.board.set_status(&status(game));
+    }
+}
+
+impl Command<ConnectFourState> for ConnectFourCommand {
+    fn apply(self, state: &mut ConnectFourState) {
+        match self {
+            ConnectFourCommand::Chat(ChatCommand::Send(text)) => {
+                state.socket.send(ToServer::Chat(text));
+            }
+            ConnectFourCommand::Board(command) => state.socket.send(match command {
+                BoardCommand::Column(column) => ToServer::Move(ConnectFourMove { column }),
+                BoardCommand::Sit(seat) => ToServer::Sit(seat),
+                BoardCommand::Stand => ToServer::Stand,
+                BoardCommand::Resign => ToServer::Resign,
+                BoardCommand::AddBot(seat) => ToServer::AddBot(seat),
+                BoardCommand::RemoveBot(seat) => ToServer::RemoveBot(seat),
+            }),
+            ConnectFourCommand::Socket(SocketCommand::Data(message)) => {
+                debug!("{:?}", message);
+                match message {
+                    ToClient::State(game) => {
+                        state.game = Some(game);
+                        state.render();
+                    }
+                    ToClient::Rejected(reason) => {
+                        state
+                            .chat
+                            .notice("connect four", Date::now() as u64, &reason);
+                    }
+                    ToClient::ChatListMessage(message) => state.chat.consume(message),
+                    ToClient::ClientListMessage(message) => {
+                        if let ClientListMessage::Joined(data) = message {
+                            state.names.insert(data.id, data.name);
+                            state.render();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl From<SocketCommand<ToClient>> for ConnectFourCommand {
+    fn from(value: SocketCommand<ToClient>) -> Self {
+        ConnectFourCommand::Socket(value)
+    }
+}
+
+impl From<ChatCommand> for ConnectFourCommand {
+    fn from(value: ChatCommand) -> Self {
+        ConnectFourCommand::Chat(value)
+    }
+}
+
+impl From<BoardCommand> for ConnectFourCommand {
+    fn from(value: BoardCommand) -> Self {
+        ConnectFourCommand::Board(value)
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(Level::Debug).unwrap();
+    info!("Hello, world!");
+    // The time control, like `?time=60`, is passed on to the server, which
+    // uses it if the room is new.
+    let query = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+    let mut consumer_generator: CommandConsumerGenerator<_, ConnectFourCommand> =
+        CommandConsumerGenerator::new();
+    let state = ConnectFourState {
+        socket: Socket::new(
+            consumer_generator.make_consumer(),
+            &format!("socket{query}"),
+        ),
+        chat: Chat::new(consumer_generator.make_consumer()),
+        clock: Clock::new(),
+        board: Board::new(consumer_generator.make_consumer()),
+        names: HashMap::new(),
+        game: None,
+    };
+    consumer_generator.activate(state);
+}
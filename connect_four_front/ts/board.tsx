@@ -0,0 +1,79 @@
+import JSX from "commons/createElement";
+
+export interface BoardObserver {
+    on_column(column: number): void;
+    on_sit(seat: number): void;
+    on_stand(): void;
+    on_resign(): void;
+    on_add_bot(seat: number): void;
+    on_remove_bot(seat: number): void;
+}
+
+const COLUMNS = 7;
+const ROWS = 6;
+const CELL = 64;
+const COLORS = { " ": "#fff", "r": "#e33", "y": "#fd3" };
+
+// Cells are numbered row by row from the bottom left corner.
+export class Board {
+    private cells: HTMLElement[] = [];
+    private status: HTMLElement;
+    private red: HTMLElement;
+    private yellow: HTMLElement;
+
+    public constructor(observer: BoardObserver) {
+        const grid = <div style={`display: grid; grid-template-columns: repeat(${COLUMNS}, ${CELL}px); grid-template-rows: repeat(${ROWS}, ${CELL}px); background: #1b4fb8; padding: 8px; border-radius: 8px; user-select: none; align-self: flex-start`}></div>;
+        for (let index = 0; index < COLUMNS * ROWS; index++) {
+            const cell = <div style={`margin: 6px; border-radius: 50%; box-sizing: border-box; cursor: pointer`}></div>;
+            cell.addEventListener("click", _ => observer.on_column(index % COLUMNS));
+            this.cells.push(cell);
+        }
+        const order = [];
+        for (let row = ROWS - 1; row >= 0; row--) {
+            for (let column = 0; column < COLUMNS; column++) {
+                order.push(this.cells[row * COLUMNS + column]);
+            }
+        }
+        grid.replaceChildren(...order);
+
+        this.status = <div style="margin: 8px 0; font-weight: bold"></div>;
+        this.red = <span></span>;
+        this.yellow = <span></span>;
+        const button = (text: string, action: () => void) => {
+            const element = <button>{text}</button>;
+            element.addEventListener("click", _ => action());
+            return element;
+        };
+
+        document.body.appendChild(<div style="display: flex; gap: 16px; padding: 16px; font-family: sans-serif; font-size: 14px">
+            {grid}
+            <div style="width: 280px">
+                <div>Red: {this.red}</div>
+                <div>Yellow: {this.yellow}</div>
+                {this.status}
+                <div>{button("Play red", () => observer.on_sit(0))} {button("Play yellow", () => observer.on_sit(1))} {button("Stand up", () => observer.on_stand())}</div>
+                <div style="margin-top: 8px">{button("Computer plays red", () => observer.on_add_bot(0))} {button("Computer plays yellow", () => observer.on_add_bot(1))}</div>
+                <div style="margin-top: 8px">{button("Remove computers", () => { observer.on_remove_bot(0); observer.on_remove_bot(1); })} {button("Resign", () => observer.on_resign())}</div>
+            </div>
+        </div>);
+    }
+
+    // One character per cell: 'r', 'y' or a space. The last disc and the
+    // winning line are outlined.
+    public set_cells(cells: string, last: number, line: Uint32Array) {
+        for (let index = 0; index < this.cells.length; index++) {
+            const style = this.cells[index].style;
+            style.background = COLORS[cells.charAt(index)];
+            style.border = line.includes(index) ? "4px solid #2c2" : index === last ? "4px solid #333" : "none";
+        }
+    }
+
+    public set_seats(red: string, yellow: string) {
+        this.red.innerText = red;
+        this.yellow.innerText = yellow;
+    }
+
+    public set_status(text: string) {
+        this.status.innerText = text;
+    }
+}
@@ -1,4 +1,8 @@
 #!/bin/bash
 (cd backend && cargo watch -x "run") &
 (cd menu_front && trunk watch) &
-(cd test_front && trunk watch)
\ No newline at end of file
+(cd test_front && trunk watch) &
+(cd chess_front && trunk watch) &
+(cd go_front && trunk watch) &
+(cd connect_four_front && trunk watch) &
+(cd checkers_front && trunk watch)
\ No newline at end of file
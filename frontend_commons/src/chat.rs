@@ -0,0 +1,96 @@
+use backend_commons::chat::{ChatListMessage, ChatMessage};
+
+use crate::command::{Command, CommandConsumer};
+
+#[derive(Debug)]
+pub enum ChatCommand {
+    Send(String),
+}
+
+pub struct Chat {
+    chat: ts::Chat,
+}
+
+impl Chat {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, ChatCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            chat: ts::Chat::new(ts::ChatBackend::new(Box::new(consumer))),
+        }
+    }
+
+    pub fn consume(&mut self, message: ChatListMessage) {
+        match message {
+            ChatListMessage::Set(messages) => {
+                self.chat.clear();
+                for message in messages {
+                    self.add_message(message);
+                }
+            }
+            ChatListMessage::Added(message) => self.add_message(message),
+        }
+    }
+
+    /// Shows a line that is not part of the chat history, such as the
+    /// outcome of something done on the board.
+    pub fn notice(&mut self, author_name: &str, sent_at: u64, text: &str) {
+        self.chat.add_message(author_name, sent_at as f64, text);
+    }
+
+    fn add_message(&mut self, message: ChatMessage) {
+        self.chat
+            .add_message(&message.author_name, message.sent_at as f64, &message.text);
+    }
+}
+
+impl<State, Cmd> ts::ChatObserver for CommandConsumer<State, ChatCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_send(&mut self, text: String) {
+        self.consume(ChatCommand::Send(text));
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait ChatObserver {
+        fn on_send(&mut self, text: String);
+    }
+
+    #[wasm_bindgen]
+    pub struct ChatBackend {
+        observer: Box<dyn ChatObserver>,
+    }
+
+    impl ChatBackend {
+        pub fn new(observer: Box<dyn ChatObserver>) -> ChatBackend {
+            ChatBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl ChatBackend {
+        pub fn on_send(&mut self, text: String) {
+            self.observer.on_send(text);
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = chat)]
+    unsafe extern "C" {
+        pub type Chat;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: ChatBackend) -> Chat;
+
+        #[wasm_bindgen(method)]
+        pub fn clear(this: &Chat);
+
+        #[wasm_bindgen(method)]
+        pub fn add_message(this: &Chat, author: &str, sent_at: f64, text: &str);
+    }
+}
@@ -1,19 +1,45 @@
-use backend_commons::client_info::{ClientData, ClientMessage};
+use backend_commons::client_info::{ClientData, ClientMessage, ClientRole};
 
 pub struct ClientInfo {
+    name: String,
     client_info: ts::ClientInfo,
 }
 
+fn role_name(role: ClientRole) -> &'static str {
+    match role {
+        ClientRole::Player => "player",
+        ClientRole::Spectator => "spectator",
+    }
+}
+
 impl ClientInfo {
     pub fn new(data: ClientData) -> Self {
         Self {
-            client_info: ts::ClientInfo::new(&data.name, data.ping),
+            client_info: ts::ClientInfo::new(
+                &data.name,
+                data.ping,
+                data.seat.map(|seat| seat as u32),
+                role_name(data.role),
+            ),
+            name: data.name,
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Takes the client off the list once it left.
+    pub fn remove(self) {
+        self.client_info.remove();
+    }
+
     pub fn consume(&mut self, message: ClientMessage) {
         match message {
             ClientMessage::Ping(ping) => self.client_info.setPing(ping),
+            ClientMessage::Seat { seat, role } => self
+                .client_info
+                .setSeat(seat.map(|seat| seat as u32), role_name(role)),
         }
     }
 }
@@ -26,9 +52,15 @@ mod ts {
         pub type ClientInfo;
 
         #[wasm_bindgen(constructor)]
-        pub fn new(name: &str, ping: u32) -> ClientInfo;
+        pub fn new(name: &str, ping: u32, seat: Option<u32>, role: &str) -> ClientInfo;
 
         #[wasm_bindgen(method)]
         pub fn setPing(this: &ClientInfo, ping: u32);
+
+        #[wasm_bindgen(method)]
+        pub fn setSeat(this: &ClientInfo, seat: Option<u32>, role: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn remove(this: &ClientInfo);
     }
 }
@@ -0,0 +1,71 @@
+/// Chess clock of a two-player match. It counts the running side down on
+/// its own between updates from the server.
+pub struct Clock {
+    clock: ts::Clock,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Self {
+            clock: ts::Clock::new(),
+        }
+    }
+
+    /// Times are in milliseconds, as of when the update was sent. The
+    /// running side's clock only starts going down once `delay_left` ran
+    /// out.
+    pub fn set(
+        &mut self,
+        names: [&str; 2],
+        remaining: &[u64],
+        running: Option<usize>,
+        delay_left: u64,
+    ) {
+        let time = |seat: usize| remaining.get(seat).copied().unwrap_or_default() as f64;
+        self.clock.set(
+            names[0],
+            names[1],
+            time(0),
+            time(1),
+            running.map_or(-1, |seat| seat as i32),
+            delay_left as f64,
+        );
+    }
+
+    /// Hides the clock, for untimed matches.
+    pub fn hide(&mut self) {
+        self.clock.hide();
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    #[wasm_bindgen(js_namespace = clock)]
+    unsafe extern "C" {
+        pub type Clock;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new() -> Clock;
+
+        #[wasm_bindgen(method)]
+        pub fn set(
+            this: &Clock,
+            first: &str,
+            second: &str,
+            first_time: f64,
+            second_time: f64,
+            running: i32,
+            delay_left: f64,
+        );
+
+        #[wasm_bindgen(method)]
+        pub fn hide(this: &Clock);
+    }
+}
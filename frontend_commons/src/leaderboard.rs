@@ -0,0 +1,90 @@
+use crate::command::{Command, CommandConsumer};
+
+#[derive(Debug)]
+pub enum LeaderboardCommand {
+    /// A game type was picked, whose leaderboard should be shown.
+    Select(String),
+}
+
+/// Ratings table with a tab for each game type.
+pub struct Leaderboard {
+    leaderboard: ts::Leaderboard,
+}
+
+impl Leaderboard {
+    pub fn new<State, Cmd>(
+        consumer: CommandConsumer<State, LeaderboardCommand, Cmd>,
+        kinds: &[&str],
+    ) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        let leaderboard = ts::Leaderboard::new(ts::LeaderboardBackend::new(Box::new(consumer)));
+        for kind in kinds {
+            leaderboard.add_kind(kind);
+        }
+        Self { leaderboard }
+    }
+
+    /// Replaces the table with the players of `kind`, best first.
+    pub fn set<'a>(&mut self, kind: &str, rows: impl IntoIterator<Item = (&'a str, f64, u32)>) {
+        self.leaderboard.clear(kind);
+        for (rank, (name, rating, games)) in rows.into_iter().enumerate() {
+            self.leaderboard
+                .add_row(rank as u32 + 1, name, rating.round(), games);
+        }
+    }
+}
+
+impl<State, Cmd> ts::LeaderboardObserver for CommandConsumer<State, LeaderboardCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_select(&mut self, kind: String) {
+        self.consume(LeaderboardCommand::Select(kind));
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait LeaderboardObserver {
+        fn on_select(&mut self, kind: String);
+    }
+
+    #[wasm_bindgen]
+    pub struct LeaderboardBackend {
+        observer: Box<dyn LeaderboardObserver>,
+    }
+
+    impl LeaderboardBackend {
+        pub fn new(observer: Box<dyn LeaderboardObserver>) -> LeaderboardBackend {
+            LeaderboardBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl LeaderboardBackend {
+        pub fn on_select(&mut self, kind: String) {
+            self.observer.on_select(kind);
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = leaderboard)]
+    unsafe extern "C" {
+        pub type Leaderboard;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: LeaderboardBackend) -> Leaderboard;
+
+        #[wasm_bindgen(method)]
+        pub fn add_kind(this: &Leaderboard, kind: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn clear(this: &Leaderboard, kind: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn add_row(this: &Leaderboard, rank: u32, name: &str, rating: f64, games: u32);
+    }
+}
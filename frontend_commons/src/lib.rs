@@ -1,4 +1,7 @@
+pub mod chat;
 pub mod client_info;
+pub mod clock;
 pub mod command;
+pub mod leaderboard;
 pub mod list;
 pub mod socket;
@@ -6,6 +6,9 @@ use crate::command::{Command, CommandConsumer};
 
 #[derive(Debug)]
 pub enum SocketCommand<ToClient> {
+    /// The connection was (re)established. Messages sent while handling this
+    /// command go out before the ones queued while disconnected.
+    Open,
     Data(ToClient),
     Error,
     Close,
@@ -37,6 +40,7 @@ where
         }
     }
 
+    /// Messages sent while disconnected are queued and sent after reconnecting.
     pub fn send(&mut self, message: ToServer) {
         self.socket.send(serde_cbor::to_vec(&message).unwrap());
     }
@@ -48,6 +52,10 @@ where
     Cmd: Command<State>,
     ToClient: DeserializeOwned,
 {
+    fn on_open(&mut self) {
+        self.consume(SocketCommand::Open);
+    }
+
     fn on_data(&mut self, data: Vec<u8>) {
         self.consume(SocketCommand::Data(serde_cbor::from_slice(&data).unwrap()));
     }
@@ -66,6 +74,7 @@ mod ts {
     use web_sys::js_sys::Uint8Array;
 
     pub trait SocketObserver {
+        fn on_open(&mut self);
         fn on_data(&mut self, data: Vec<u8>);
         fn on_error(&mut self);
         fn on_close(&mut self);
@@ -78,12 +87,15 @@ mod ts {
 
     impl SocketBackend {
         pub fn new(observer: Box<dyn SocketObserver>) -> SocketBackend {
-            SocketBackend { observer: observer }
+            SocketBackend { observer }
         }
     }
 
     #[wasm_bindgen]
     impl SocketBackend {
+        pub fn on_open(&mut self) {
+            self.observer.on_open();
+        }
         pub fn on_data(&mut self, data: Uint8Array) {
             self.observer.on_data(data.to_vec());
         }
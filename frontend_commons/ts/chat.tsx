@@ -0,0 +1,51 @@
+import JSX from "./createElement";
+
+export interface ChatObserver {
+  on_send(text: string): void
+}
+
+export class Chat {
+  private element: HTMLElement;
+  private messages: HTMLElement;
+  private input: HTMLInputElement;
+
+  public constructor(observer: ChatObserver) {
+    this.messages = <div style="max-height: 240px; overflow-y: auto"></div>;
+    this.input = <input type="text" placeholder="Say something" style="width: 100%; box-sizing: border-box"></input> as HTMLInputElement;
+    this.input.addEventListener("keydown", (event: KeyboardEvent) => {
+      if (event.key !== "Enter") {
+        return;
+      }
+      const text = this.input.value.trim();
+      if (text.length > 0) {
+        observer.on_send(text);
+      }
+      this.input.value = "";
+    });
+    this.element = <div style="position: fixed; bottom: 0; left: 0; width: 320px; padding: 8px; background: rgba(255, 255, 255, 0.9); font-family: sans-serif; font-size: 14px">
+      {this.messages}
+      {this.input}
+    </div>;
+    // Keeps pages that listen on the whole document from seeing clicks
+    // meant for the chat.
+    for (const type of ["mousedown", "mouseup", "wheel"]) {
+      this.element.addEventListener(type, event => event.stopPropagation());
+    }
+    document.body.appendChild(this.element);
+  }
+
+  public clear() {
+    this.messages.replaceChildren();
+  }
+
+  public add_message(author: string, sent_at: number, text: string) {
+    const time = new Date(sent_at).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+    const atBottom = this.messages.scrollTop + this.messages.clientHeight >= this.messages.scrollHeight - 4;
+    this.messages.appendChild(<div style="white-space: pre-wrap; overflow-wrap: anywhere">
+      <small>{time}</small> <b>{author}</b>: {text}
+    </div>);
+    if (atBottom) {
+      this.messages.scrollTop = this.messages.scrollHeight;
+    }
+  }
+}
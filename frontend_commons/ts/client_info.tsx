@@ -3,25 +3,40 @@ import JSX from "./createElement";
 export interface ClientData {
   name: string,
   ping: number,
+  /** Zero-based seat, if the client has one. */
+  seat?: number,
+  role: string,
 }
 
 export class ClientInfo {
   private element: HTMLElement;
   private clientData: ClientData
 
-  public constructor(clientData: ClientData) {
-    this.clientData = clientData;
+  public constructor(name: string, ping: number, seat: number | undefined, role: string) {
+    this.clientData = { name, ping, seat, role };
     this.element = <div></div>;
     this.updateElement();
     document.body.appendChild(this.element);
   }
 
   private updateElement() {
-    this.element.innerText = `${this.clientData.name} (${this.clientData.ping}ms)`;
+    const { name, ping, seat, role } = this.clientData;
+    const place = seat === undefined ? role : `${role}, seat ${seat + 1}`;
+    this.element.innerText = `${name} (${place}, ${ping}ms)`;
   }
 
   public setPing(ping: number) {
     this.clientData.ping = ping;
     this.updateElement();
   }
-}
\ No newline at end of file
+
+  public setSeat(seat: number | undefined, role: string) {
+    this.clientData.seat = seat;
+    this.clientData.role = role;
+    this.updateElement();
+  }
+
+  public remove() {
+    this.element.remove();
+  }
+}
@@ -0,0 +1,64 @@
+import JSX from "./createElement";
+
+function format(ms: number): string {
+  const total = Math.max(0, ms);
+  const seconds = Math.floor(total / 1000);
+  const hours = Math.floor(seconds / 3600);
+  const minutes = Math.floor(seconds / 60) % 60;
+  const pad = (value: number) => value.toString().padStart(2, "0");
+  if (hours > 0) {
+    return `${hours}:${pad(minutes)}:${pad(seconds % 60)}`;
+  }
+  // Tenths matter when time is short.
+  if (seconds < 10) {
+    return `${minutes}:${pad(seconds)}.${Math.floor(total / 100) % 10}`;
+  }
+  return `${minutes}:${pad(seconds % 60)}`;
+}
+
+export class Clock {
+  private element: HTMLElement;
+  private names: HTMLElement[];
+  private times: HTMLElement[];
+  private remaining = [0, 0];
+  private running = -1;
+  private delayLeft = 0;
+  // When the last update arrived, the running side counts down from there.
+  private received = 0;
+
+  public constructor() {
+    this.names = [<span></span>, <span></span>];
+    this.times = [<b style="font-family: monospace; font-size: 20px"></b>, <b style="font-family: monospace; font-size: 20px"></b>];
+    this.element = <div style="position: fixed; top: 8px; right: 8px; padding: 8px; background: rgba(255, 255, 255, 0.9); font-family: sans-serif; font-size: 14px; display: none">
+      <div>{this.names[0]} {this.times[0]}</div>
+      <div>{this.names[1]} {this.times[1]}</div>
+    </div>;
+    document.body.appendChild(this.element);
+    setInterval(() => this.render(), 100);
+  }
+
+  public set(first: string, second: string, firstTime: number, secondTime: number, running: number, delayLeft: number) {
+    this.names[0].innerText = first;
+    this.names[1].innerText = second;
+    this.remaining = [firstTime, secondTime];
+    this.running = running;
+    this.delayLeft = delayLeft;
+    this.received = performance.now();
+    this.element.style.display = "block";
+    this.render();
+  }
+
+  public hide() {
+    this.element.style.display = "none";
+  }
+
+  private render() {
+    const elapsed = Math.max(0, performance.now() - this.received - this.delayLeft);
+    for (const seat of [0, 1]) {
+      const running = seat === this.running;
+      const left = running ? this.remaining[seat] - elapsed : this.remaining[seat];
+      this.times[seat].innerText = format(left);
+      this.times[seat].style.color = running ? (left <= 0 ? "#c00" : "#000") : "#888";
+    }
+  }
+}
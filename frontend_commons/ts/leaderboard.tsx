@@ -0,0 +1,45 @@
+import JSX from "./createElement";
+
+export interface LeaderboardObserver {
+  on_select(kind: string): void
+}
+
+export class Leaderboard {
+  private element: HTMLElement;
+  private tabs: HTMLElement;
+  private title: HTMLElement;
+  private rows: HTMLElement;
+  private observer: LeaderboardObserver;
+
+  public constructor(observer: LeaderboardObserver) {
+    this.observer = observer;
+    this.tabs = <div></div>;
+    this.title = <h3></h3>;
+    this.rows = <tbody></tbody>;
+    this.element = <div style="font-family: sans-serif; font-size: 14px; margin-top: 16px">
+      <h2>Leaderboards</h2>
+      {this.tabs}
+      {this.title}
+      <table>
+        <thead><tr><th>#</th><th>Player</th><th>Rating</th><th>Games</th></tr></thead>
+        {this.rows}
+      </table>
+    </div>;
+    document.body.appendChild(this.element);
+  }
+
+  public add_kind(kind: string) {
+    const tab: HTMLButtonElement = <button>{kind}</button>;
+    tab.addEventListener("click", _ => { this.observer.on_select(kind) });
+    this.tabs.appendChild(tab);
+  }
+
+  public clear(kind: string) {
+    this.title.innerText = kind;
+    this.rows.replaceChildren();
+  }
+
+  public add_row(rank: number, name: string, rating: number, games: number) {
+    this.rows.appendChild(<tr><td>{String(rank)}</td><td>{name}</td><td>{String(rating)}</td><td>{String(games)}</td></tr>);
+  }
+}
@@ -1,28 +1,57 @@
 export interface SocketObserver {
+    on_open(): void;
     on_data(data: Uint8Array): void;
     on_close(): void;
     on_error(): void;
 }
 
+const MIN_RECONNECT_DELAY = 500;
+const MAX_RECONNECT_DELAY = 10000;
+
 export class Socket {
     private socket: WebSocket;
+    private observer: SocketObserver;
+    private url: string;
+    private queue: Uint8Array[] = [];
+    private reconnectDelay = MIN_RECONNECT_DELAY;
 
     public constructor(observer: SocketObserver, path: String) {
         const protocol = location.protocol === "http:" ? "ws:" : "wss:";
-        this.socket = new WebSocket(`${protocol}//${location.host}${location.pathname}/${path}`);
+        this.observer = observer;
+        this.url = `${protocol}//${location.host}${location.pathname}/${path}`;
+        this.connect();
+    }
+
+    private connect() {
+        this.socket = new WebSocket(this.url);
+        this.socket.addEventListener("open", _ => {
+            this.reconnectDelay = MIN_RECONNECT_DELAY;
+            // The observer may send a handshake, which has to go out before
+            // anything queued while the connection was down.
+            this.observer.on_open();
+            const queue = this.queue;
+            this.queue = [];
+            queue.forEach(data => this.send(data));
+        });
         this.socket.addEventListener("message", async e => {
             const data: Blob = e.data;
-            observer.on_data(new Uint8Array(await data.arrayBuffer()))
+            this.observer.on_data(new Uint8Array(await data.arrayBuffer()))
         });
         this.socket.addEventListener("error", async _ => {
-            observer.on_error();
+            this.observer.on_error();
         });
         this.socket.addEventListener("close", async _ => {
-            observer.on_close();
+            this.observer.on_close();
+            setTimeout(() => this.connect(), this.reconnectDelay);
+            this.reconnectDelay = Math.min(this.reconnectDelay * 2, MAX_RECONNECT_DELAY);
         });
     }
 
     public send(data: Uint8Array) {
-        this.socket.send(data.buffer);
+        if (this.socket.readyState === WebSocket.OPEN) {
+            this.socket.send(data.buffer);
+        } else {
+            this.queue.push(data);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,605 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, Outcome};
+
+/// Squares are numbered row by row from the top left corner. Only dark
+/// squares, where the row and column add up to an odd number, are used.
+/// Seat 0 starts at the bottom.
+pub type Square = u8;
+
+const DIRECTIONS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    /// 8×8, men only capture forward, kings move one square, any capture
+    /// sequence may be chosen and Black moves first.
+    #[default]
+    English,
+    /// 10×10, men also capture backward, kings fly, the longest capture is
+    /// mandatory and White moves first.
+    International,
+}
+
+impl Variant {
+    pub fn size(self) -> usize {
+        match self {
+            Variant::English => 8,
+            Variant::International => 10,
+        }
+    }
+
+    /// Rows of men each side starts with.
+    fn rows(self) -> usize {
+        match self {
+            Variant::English => 3,
+            Variant::International => 4,
+        }
+    }
+
+    /// Names of the sides, by seat.
+    pub fn colors(self) -> [&'static str; 2] {
+        match self {
+            Variant::English => ["Black", "White"],
+            Variant::International => ["White", "Black"],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub seat: usize,
+    pub king: bool,
+}
+
+/// The starting square then every square landed on, so a multi-jump is one
+/// move.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CheckersMove {
+    pub path: Vec<Square>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CheckersSettings {
+    pub variant: Variant,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckersEnd {
+    /// The side to move has no piece or no move left.
+    Blocked { winner: usize },
+    /// The same position for the third time.
+    Repetition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckersError {
+    Illegal,
+    MustCapture,
+}
+
+impl fmt::Display for CheckersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckersError::Illegal => write!(f, "that move is not legal"),
+            CheckersError::MustCapture => {
+                write!(f, "capturing is mandatory, as far as possible")
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CheckersView {
+    pub variant: Variant,
+    /// Indexed by [`Square`].
+    pub board: Vec<Option<Piece>>,
+    pub to_move: usize,
+    /// Moves the receiver can play now, empty unless it is their turn.
+    pub legal_moves: Vec<CheckersMove>,
+    pub last_move: Option<CheckersMove>,
+    pub end: Option<CheckersEnd>,
+}
+
+/// A move with what it does to the board.
+#[derive(Clone, Debug)]
+struct Legal {
+    path: Vec<Square>,
+    captured: Vec<Square>,
+}
+
+/// Everything that decides which moves are legal, so equal positions are
+/// repetitions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Position {
+    variant: Variant,
+    board: Vec<Option<Piece>>,
+    to_move: usize,
+}
+
+impl Position {
+    fn start(variant: Variant) -> Self {
+        let size = variant.size();
+        let board = (0..size * size)
+            .map(|square| {
+                let (row, column) = (square / size, square % size);
+                if (row + column) % 2 == 0 {
+                    None
+                } else if row < variant.rows() {
+                    Some(Piece {
+                        seat: 1,
+                        king: false,
+                    })
+                } else if row >= size - variant.rows() {
+                    Some(Piece {
+                        seat: 0,
+                        king: false,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Self {
+            variant,
+            board,
+            to_move: 0,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.variant.size()
+    }
+
+    fn step(&self, square: Square, (dr, dc): (isize, isize)) -> Option<Square> {
+        let size = self.size() as isize;
+        let (row, column) = (square as isize / size + dr, square as isize % size + dc);
+        ((0..size).contains(&row) && (0..size).contains(&column))
+            .then_some((row * size + column) as Square)
+    }
+
+    fn forward(seat: usize) -> isize {
+        match seat {
+            0 => -1,
+            _ => 1,
+        }
+    }
+
+    fn promotes(&self, piece: Piece, square: Square) -> bool {
+        let row = square as usize / self.size();
+        !piece.king
+            && match piece.seat {
+                0 => row == 0,
+                _ => row == self.size() - 1,
+            }
+    }
+
+    fn flies(&self, piece: Piece) -> bool {
+        piece.king && self.variant == Variant::International
+    }
+
+    fn may_go(&self, piece: Piece, (dr, _): (isize, isize), capturing: bool) -> bool {
+        piece.king
+            || dr == Position::forward(piece.seat)
+            || (capturing && self.variant == Variant::International)
+    }
+
+    /// Adds every complete capture sequence continuing `path` to `out`.
+    /// Captured pieces stay on `board` until the move ends, so they block
+    /// and cannot be jumped twice.
+    fn captures_from(
+        &self,
+        board: &[Option<Piece>],
+        piece: Piece,
+        path: &mut Vec<Square>,
+        captured: &mut Vec<Square>,
+        out: &mut Vec<Legal>,
+    ) {
+        let from = *path.last().unwrap();
+        let mut extended = false;
+        for direction in DIRECTIONS {
+            if !self.may_go(piece, direction, true) {
+                continue;
+            }
+            let mut over = self.step(from, direction);
+            while self.flies(piece)
+                && let Some(square) = over
+                && board[square as usize].is_none()
+            {
+                over = self.step(square, direction);
+            }
+            let Some(over) = over else {
+                continue;
+            };
+            match board[over as usize] {
+                Some(jumped) if jumped.seat != piece.seat && !captured.contains(&over) => {}
+                _ => continue,
+            }
+            let mut land = self.step(over, direction);
+            while let Some(to) = land
+                && board[to as usize].is_none()
+            {
+                extended = true;
+                path.push(to);
+                captured.push(over);
+                // English men are crowned at once, which ends the move.
+                if self.variant == Variant::English && self.promotes(piece, to) {
+                    out.push(Legal {
+                        path: path.clone(),
+                        captured: captured.clone(),
+                    });
+                } else {
+                    self.captures_from(board, piece, path, captured, out);
+                }
+                path.pop();
+                captured.pop();
+                if !self.flies(piece) {
+                    break;
+                }
+                land = self.step(to, direction);
+            }
+        }
+        if !extended && !captured.is_empty() {
+            out.push(Legal {
+                path: path.clone(),
+                captured: captured.clone(),
+            });
+        }
+    }
+
+    fn legal_moves(&self) -> Vec<Legal> {
+        let own = |square: usize| {
+            self.board[square]
+                .filter(|piece| piece.seat == self.to_move)
+                .map(|piece| (square as Square, piece))
+        };
+        let pieces: Vec<_> = (0..self.board.len()).filter_map(own).collect();
+
+        let mut captures = Vec::new();
+        for &(square, piece) in &pieces {
+            let mut board = self.board.clone();
+            board[square as usize] = None;
+            self.captures_from(
+                &board,
+                piece,
+                &mut vec![square],
+                &mut Vec::new(),
+                &mut captures,
+            );
+        }
+        if !captures.is_empty() {
+            if self.variant == Variant::International {
+                let longest = captures.iter().map(|legal| legal.captured.len()).max();
+                captures.retain(|legal| Some(legal.captured.len()) == longest);
+            }
+            return captures;
+        }
+
+        let mut moves = Vec::new();
+        for (square, piece) in pieces {
+            for direction in DIRECTIONS {
+                if !self.may_go(piece, direction, false) {
+                    continue;
+                }
+                let mut to = self.step(square, direction);
+                while let Some(target) = to
+                    && self.board[target as usize].is_none()
+                {
+                    moves.push(Legal {
+                        path: vec![square, target],
+                        captured: Vec::new(),
+                    });
+                    if !self.flies(piece) {
+                        break;
+                    }
+                    to = self.step(target, direction);
+                }
+            }
+        }
+        moves
+    }
+
+    fn play(&self, legal: &Legal) -> Position {
+        let mut next = self.clone();
+        let from = legal.path[0] as usize;
+        let to = *legal.path.last().unwrap();
+        let mut piece = next.board[from].take().unwrap();
+        for &square in &legal.captured {
+            next.board[square as usize] = None;
+        }
+        if self.promotes(piece, to) {
+            piece.king = true;
+        }
+        next.board[to as usize] = Some(piece);
+        next.to_move = 1 - self.to_move;
+        next
+    }
+}
+
+pub struct Checkers {
+    position: Position,
+    legal_moves: Vec<Legal>,
+    /// How often each position occurred since the last capture or man move,
+    /// as none of them can come back after one.
+    seen: HashMap<Position, u32>,
+    last_move: Option<CheckersMove>,
+    end: Option<CheckersEnd>,
+}
+
+impl Checkers {
+    pub fn variant(&self) -> Variant {
+        self.position.variant
+    }
+
+    pub fn board(&self) -> &[Option<Piece>] {
+        &self.position.board
+    }
+
+    pub fn end(&self) -> Option<CheckersEnd> {
+        self.end
+    }
+
+    fn detect_end(&self) -> Option<CheckersEnd> {
+        if self.legal_moves.is_empty() {
+            Some(CheckersEnd::Blocked {
+                winner: 1 - self.position.to_move,
+            })
+        } else if self
+            .seen
+            .get(&self.position)
+            .is_some_and(|&count| count >= 3)
+        {
+            Some(CheckersEnd::Repetition)
+        } else {
+            None
+        }
+    }
+}
+
+impl Game for Checkers {
+    const KIND: &'static str = "checkers";
+
+    type Settings = CheckersSettings;
+    type Move = CheckersMove;
+    type View = CheckersView;
+    type Error = CheckersError;
+
+    fn new(settings: &CheckersSettings) -> Self {
+        let position = Position::start(settings.variant);
+        Self {
+            legal_moves: position.legal_moves(),
+            seen: HashMap::from([(position.clone(), 1)]),
+            position,
+            last_move: None,
+            end: None,
+        }
+    }
+
+    fn seats(_: &CheckersSettings) -> usize {
+        2
+    }
+
+    fn view(&self, seat: Option<usize>) -> CheckersView {
+        let legal_moves = if seat.is_some() && seat == self.to_move() {
+            self.legal_moves
+                .iter()
+                .map(|legal| CheckersMove {
+                    path: legal.path.clone(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        CheckersView {
+            variant: self.position.variant,
+            board: self.position.board.clone(),
+            to_move: self.position.to_move,
+            legal_moves,
+            last_move: self.last_move.clone(),
+            end: self.end,
+        }
+    }
+
+    fn validate(&self, _: usize, played: &CheckersMove) -> Result<(), CheckersError> {
+        if self
+            .legal_moves
+            .iter()
+            .any(|legal| legal.path == played.path)
+        {
+            Ok(())
+        } else if self
+            .legal_moves
+            .iter()
+            .any(|legal| !legal.captured.is_empty())
+        {
+            Err(CheckersError::MustCapture)
+        } else {
+            Err(CheckersError::Illegal)
+        }
+    }
+
+    fn apply(&mut self, _: usize, played: &CheckersMove) {
+        let Some(legal) = self
+            .legal_moves
+            .iter()
+            .find(|legal| legal.path == played.path)
+        else {
+            return;
+        };
+        let irreversible = !legal.captured.is_empty()
+            || self.position.board[legal.path[0] as usize].is_some_and(|piece| !piece.king);
+        self.position = self.position.play(legal);
+        self.legal_moves = self.position.legal_moves();
+        self.last_move = Some(played.clone());
+        if irreversible {
+            self.seen.clear();
+        }
+        *self.seen.entry(self.position.clone()).or_default() += 1;
+        self.end = self.detect_end();
+    }
+
+    fn to_move(&self) -> Option<usize> {
+        match self.end {
+            Some(_) => None,
+            None => Some(self.position.to_move),
+        }
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.end.map(|end| match end {
+            CheckersEnd::Blocked { winner } => Outcome::Win(winner),
+            CheckersEnd::Repetition => Outcome::Draw,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAN: Piece = Piece {
+        seat: 0,
+        king: false,
+    };
+    const KING: Piece = Piece {
+        seat: 0,
+        king: true,
+    };
+    const ENEMY: Piece = Piece {
+        seat: 1,
+        king: false,
+    };
+    const ENEMY_KING: Piece = Piece {
+        seat: 1,
+        king: true,
+    };
+
+    /// A position with seat 0 to move and the pieces on the given rows and
+    /// columns.
+    fn position(variant: Variant, pieces: &[((usize, usize), Piece)]) -> Position {
+        let size = variant.size();
+        let mut board = vec![None; size * size];
+        for &((row, column), piece) in pieces {
+            assert_eq!((row + column) % 2, 1, "({row}, {column}) is not dark");
+            board[row * size + column] = Some(piece);
+        }
+        Position {
+            variant,
+            board,
+            to_move: 0,
+        }
+    }
+
+    fn paths(position: &Position) -> Vec<Vec<Square>> {
+        let mut paths: Vec<_> = position
+            .legal_moves()
+            .into_iter()
+            .map(|legal| legal.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn game_from(position: Position) -> Checkers {
+        Checkers {
+            legal_moves: position.legal_moves(),
+            seen: HashMap::from([(position.clone(), 1)]),
+            position,
+            last_move: None,
+            end: None,
+        }
+    }
+
+    #[test]
+    fn multi_jumps_are_searched_to_the_end() {
+        let position = position(
+            Variant::English,
+            &[
+                ((6, 1), MAN),
+                ((5, 2), ENEMY),
+                ((3, 2), ENEMY),
+                ((3, 4), ENEMY),
+            ],
+        );
+        assert_eq!(paths(&position), [vec![49, 35, 17], vec![49, 35, 21]]);
+
+        let mut captured = position.legal_moves()[0].captured.clone();
+        captured.sort();
+        assert_eq!(captured, [26, 42]);
+
+        let game = game_from(position);
+        let stopped = CheckersMove { path: vec![49, 35] };
+        assert_eq!(game.validate(0, &stopped), Err(CheckersError::MustCapture));
+    }
+
+    #[test]
+    fn flying_kings_land_anywhere_past_the_captured_piece() {
+        let position = position(
+            Variant::International,
+            &[((9, 0), KING), ((6, 3), ENEMY), ((0, 1), ENEMY)],
+        );
+        assert_eq!(
+            paths(&position),
+            [
+                vec![90, 9],
+                vec![90, 18],
+                vec![90, 27],
+                vec![90, 36],
+                vec![90, 45],
+                vec![90, 54],
+            ]
+        );
+    }
+
+    #[test]
+    fn international_captures_take_as_many_pieces_as_possible() {
+        let position = position(
+            Variant::International,
+            &[
+                ((6, 3), MAN),
+                ((5, 2), ENEMY),
+                ((5, 4), ENEMY),
+                ((3, 6), ENEMY),
+            ],
+        );
+        assert_eq!(paths(&position), [vec![63, 45, 27]]);
+
+        let game = game_from(position);
+        let shorter = CheckersMove { path: vec![63, 41] };
+        assert_eq!(game.validate(0, &shorter), Err(CheckersError::MustCapture));
+    }
+
+    #[test]
+    fn english_men_stop_when_crowned() {
+        let position = position(
+            Variant::English,
+            &[((2, 1), MAN), ((1, 2), ENEMY), ((1, 4), ENEMY)],
+        );
+        let legal_moves = position.legal_moves();
+        assert_eq!(paths(&position), [vec![17, 3]]);
+        assert_eq!(position.play(&legal_moves[0]).board[3], Some(KING));
+    }
+
+    #[test]
+    fn the_third_repetition_is_a_draw() {
+        let mut game = game_from(position(
+            Variant::English,
+            &[((7, 0), KING), ((0, 7), ENEMY_KING)],
+        ));
+        let shuffle = [vec![56, 49], vec![7, 14], vec![49, 56], vec![14, 7]];
+        for round in 0..2 {
+            assert_eq!(game.end(), None, "round {round}");
+            for path in &shuffle {
+                let played = CheckersMove { path: path.clone() };
+                let seat = game.position.to_move;
+                assert_eq!(game.validate(seat, &played), Ok(()));
+                game.apply(seat, &played);
+            }
+        }
+        assert_eq!(game.end(), Some(CheckersEnd::Repetition));
+        assert_eq!(game.outcome(), Some(Outcome::Draw));
+    }
+}
@@ -0,0 +1,1022 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Game, Match, Outcome},
+    history::{ExportInfo, Notation, date},
+};
+
+/// Squares are numbered from a1 = 0 to h8 = 63, rank by rank.
+pub type Square = u8;
+
+pub fn file(square: Square) -> u8 {
+    square % 8
+}
+
+pub fn rank(square: Square) -> u8 {
+    square / 8
+}
+
+/// Such as `e4`.
+pub fn square_name(square: Square) -> String {
+    format!("{}{}", (b'a' + file(square)) as char, rank(square) + 1)
+}
+
+fn offset(square: Square, files: i8, ranks: i8) -> Option<Square> {
+    let file = file(square) as i8 + files;
+    let rank = rank(square) as i8 + ranks;
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then_some((rank * 8 + file) as Square)
+}
+
+const KNIGHT_STEPS: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+const ROOK_STEPS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_STEPS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const KING_STEPS: [(i8, i8); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+const PROMOTIONS: [Kind; 4] = [Kind::Queen, Kind::Rook, Kind::Bishop, Kind::Knight];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub fn opponent(self) -> Self {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    /// White sits in the first seat.
+    pub fn seat(self) -> usize {
+        match self {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+
+    pub fn from_seat(seat: usize) -> Self {
+        match seat {
+            0 => Color::White,
+            _ => Color::Black,
+        }
+    }
+
+    fn forward(self) -> i8 {
+        match self {
+            Color::White => 1,
+            Color::Black => -1,
+        }
+    }
+
+    fn home_rank(self) -> u8 {
+        match self {
+            Color::White => 0,
+            Color::Black => 7,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl Kind {
+    /// Letter of the piece in algebraic notation, none for pawns.
+    pub fn letter(self) -> &'static str {
+        match self {
+            Kind::Pawn => "",
+            Kind::Knight => "N",
+            Kind::Bishop => "B",
+            Kind::Rook => "R",
+            Kind::Queen => "Q",
+            Kind::King => "K",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub color: Color,
+    pub kind: Kind,
+}
+
+/// Castling is a king move of two squares. Promotions name the piece, which
+/// is required on the last rank and forbidden elsewhere.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChessMove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<Kind>,
+}
+
+/// Everything that decides which moves are legal, so equal positions are
+/// repetitions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    board: [Option<Piece>; 64],
+    to_move: Color,
+    /// King side and queen side rights, by color.
+    castling: [[bool; 2]; 2],
+    /// Square a pawn skipped over on the last move, if an enemy pawn could
+    /// take it there.
+    en_passant: Option<Square>,
+}
+
+impl Position {
+    pub fn start() -> Self {
+        const BACK_RANK: [Kind; 8] = [
+            Kind::Rook,
+            Kind::Knight,
+            Kind::Bishop,
+            Kind::Queen,
+            Kind::King,
+            Kind::Bishop,
+            Kind::Knight,
+            Kind::Rook,
+        ];
+        let mut board = [None; 64];
+        for (file, kind) in BACK_RANK.into_iter().enumerate() {
+            for color in [Color::White, Color::Black] {
+                let home = color.home_rank() as usize * 8;
+                let pawns = (color.home_rank() as i8 + color.forward()) as usize * 8;
+                board[home + file] = Some(Piece { color, kind });
+                board[pawns + file] = Some(Piece {
+                    color,
+                    kind: Kind::Pawn,
+                });
+            }
+        }
+        Self {
+            board,
+            to_move: Color::White,
+            castling: [[true; 2]; 2],
+            en_passant: None,
+        }
+    }
+
+    pub fn piece(&self, square: Square) -> Option<Piece> {
+        self.board[square as usize]
+    }
+
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    fn king(&self, color: Color) -> Option<Square> {
+        (0..64).find(|&square| {
+            self.piece(square)
+                == Some(Piece {
+                    color,
+                    kind: Kind::King,
+                })
+        })
+    }
+
+    /// Whether a piece of `by` could capture on `square`.
+    pub fn attacked(&self, square: Square, by: Color) -> bool {
+        let holds = |square: Option<Square>, kinds: &[Kind]| {
+            square
+                .and_then(|square| self.piece(square))
+                .is_some_and(|piece| piece.color == by && kinds.contains(&piece.kind))
+        };
+        let pawn = [-1, 1]
+            .into_iter()
+            .any(|files| holds(offset(square, files, -by.forward()), &[Kind::Pawn]));
+        let knight = KNIGHT_STEPS
+            .iter()
+            .any(|&(files, ranks)| holds(offset(square, files, ranks), &[Kind::Knight]));
+        let king = KING_STEPS
+            .iter()
+            .any(|&(files, ranks)| holds(offset(square, files, ranks), &[Kind::King]));
+        let slider = |steps: &[(i8, i8)], kinds: &[Kind]| {
+            steps.iter().any(|&(files, ranks)| {
+                let mut current = square;
+                while let Some(next) = offset(current, files, ranks) {
+                    if self.piece(next).is_some() {
+                        return holds(Some(next), kinds);
+                    }
+                    current = next;
+                }
+                false
+            })
+        };
+        pawn || knight
+            || king
+            || slider(&ROOK_STEPS, &[Kind::Rook, Kind::Queen])
+            || slider(&BISHOP_STEPS, &[Kind::Bishop, Kind::Queen])
+    }
+
+    pub fn in_check(&self, color: Color) -> bool {
+        self.king(color)
+            .is_some_and(|king| self.attacked(king, color.opponent()))
+    }
+
+    /// Moves that follow how the pieces move, whether or not they leave the
+    /// king in check.
+    fn pseudo_moves(&self) -> Vec<ChessMove> {
+        let color = self.to_move;
+        let mut moves = Vec::new();
+        let push = |from: Square, to: Square, moves: &mut Vec<ChessMove>| {
+            moves.push(ChessMove {
+                from,
+                to,
+                promotion: None,
+            })
+        };
+        for from in 0..64 {
+            let Some(piece) = self.piece(from).filter(|piece| piece.color == color) else {
+                continue;
+            };
+            match piece.kind {
+                Kind::Pawn => self.pawn_moves(from, &mut moves),
+                Kind::Knight | Kind::King => {
+                    let steps = match piece.kind {
+                        Kind::Knight => &KNIGHT_STEPS,
+                        _ => &KING_STEPS,
+                    };
+                    for &(files, ranks) in steps {
+                        if let Some(to) = offset(from, files, ranks)
+                            && self.piece(to).is_none_or(|other| other.color != color)
+                        {
+                            push(from, to, &mut moves);
+                        }
+                    }
+                }
+                Kind::Bishop | Kind::Rook | Kind::Queen => {
+                    let steps: &[(i8, i8)] = match piece.kind {
+                        Kind::Bishop => &BISHOP_STEPS,
+                        Kind::Rook => &ROOK_STEPS,
+                        _ => &KING_STEPS,
+                    };
+                    for &(files, ranks) in steps {
+                        let mut current = from;
+                        while let Some(to) = offset(current, files, ranks) {
+                            match self.piece(to) {
+                                None => push(from, to, &mut moves),
+                                Some(other) => {
+                                    if other.color != color {
+                                        push(from, to, &mut moves);
+                                    }
+                                    break;
+                                }
+                            }
+                            current = to;
+                        }
+                    }
+                }
+            }
+        }
+        self.castling_moves(&mut moves);
+        moves
+    }
+
+    fn pawn_moves(&self, from: Square, moves: &mut Vec<ChessMove>) {
+        let color = self.to_move;
+        let last_rank = color.opponent().home_rank();
+        let mut push = |to: Square| {
+            if rank(to) == last_rank {
+                moves.extend(PROMOTIONS.map(|kind| ChessMove {
+                    from,
+                    to,
+                    promotion: Some(kind),
+                }));
+            } else {
+                moves.push(ChessMove {
+                    from,
+                    to,
+                    promotion: None,
+                });
+            }
+        };
+        if let Some(one) = offset(from, 0, color.forward())
+            && self.piece(one).is_none()
+        {
+            push(one);
+            let start_rank = (color.home_rank() as i8 + color.forward()) as u8;
+            if rank(from) == start_rank
+                && let Some(two) = offset(one, 0, color.forward())
+                && self.piece(two).is_none()
+            {
+                push(two);
+            }
+        }
+        for files in [-1, 1] {
+            let Some(to) = offset(from, files, color.forward()) else {
+                continue;
+            };
+            let captures = self.piece(to).is_some_and(|other| other.color != color);
+            if captures || self.en_passant == Some(to) {
+                push(to);
+            }
+        }
+    }
+
+    fn castling_moves(&self, moves: &mut Vec<ChessMove>) {
+        let color = self.to_move;
+        let home = color.home_rank() * 8;
+        let king = home + 4;
+        if self.piece(king)
+            != Some(Piece {
+                color,
+                kind: Kind::King,
+            })
+            || self.attacked(king, color.opponent())
+        {
+            return;
+        }
+        // Squares that must be empty, then those the king crosses.
+        let sides: [(&[u8], [u8; 2]); 2] = [(&[5, 6], [5, 6]), (&[1, 2, 3], [3, 2])];
+        for (side, (empty, crossed)) in sides.into_iter().enumerate() {
+            if !self.castling[color.seat()][side] {
+                continue;
+            }
+            let clear = empty.iter().all(|&file| self.piece(home + file).is_none());
+            let safe = crossed
+                .iter()
+                .all(|&file| !self.attacked(home + file, color.opponent()));
+            if clear && safe {
+                moves.push(ChessMove {
+                    from: king,
+                    to: home + crossed[1],
+                    promotion: None,
+                });
+            }
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<ChessMove> {
+        self.pseudo_moves()
+            .into_iter()
+            .filter(|&played| !self.play(played).in_check(self.to_move))
+            .collect()
+    }
+
+    /// Whether the move takes a piece.
+    fn captures(&self, played: ChessMove) -> bool {
+        self.piece(played.to).is_some()
+            || (self.en_passant == Some(played.to)
+                && self
+                    .piece(played.from)
+                    .is_some_and(|piece| piece.kind == Kind::Pawn))
+    }
+
+    /// The position after a move, which is assumed to be legal.
+    pub fn play(&self, played: ChessMove) -> Position {
+        let mut next = self.clone();
+        let color = self.to_move;
+        let Some(mut piece) = next.board[played.from as usize].take() else {
+            return next;
+        };
+        let files = file(played.to) as i8 - file(played.from) as i8;
+        match piece.kind {
+            Kind::Pawn if self.en_passant == Some(played.to) => {
+                let taken = rank(played.from) * 8 + file(played.to);
+                next.board[taken as usize] = None;
+            }
+            Kind::King if files.abs() == 2 => {
+                let home = color.home_rank() * 8;
+                let (rook_from, rook_to) = match files {
+                    2 => (home + 7, home + 5),
+                    _ => (home, home + 3),
+                };
+                next.board[rook_to as usize] = next.board[rook_from as usize].take();
+            }
+            _ => {}
+        }
+        if let Some(kind) = played.promotion {
+            piece.kind = kind;
+        }
+        next.board[played.to as usize] = Some(piece);
+
+        // Moving the king or a rook, or losing the rook, ends castling on
+        // that side.
+        for side_color in [Color::White, Color::Black] {
+            let home = side_color.home_rank() * 8;
+            for (side, corner) in [(0, home + 7), (1, home)] {
+                if [played.from, played.to].contains(&corner)
+                    || played.from == home + 4 && side_color == color
+                {
+                    next.castling[side_color.seat()][side] = false;
+                }
+            }
+        }
+
+        next.en_passant = None;
+        if piece.kind == Kind::Pawn && (rank(played.to) as i8 - rank(played.from) as i8).abs() == 2
+        {
+            let skipped = (played.from + played.to) / 2;
+            let takeable = [-1, 1].into_iter().any(|files| {
+                offset(played.to, files, 0).and_then(|square| self.piece(square))
+                    == Some(Piece {
+                        color: color.opponent(),
+                        kind: Kind::Pawn,
+                    })
+            });
+            if takeable {
+                next.en_passant = Some(skipped);
+            }
+        }
+        next.to_move = color.opponent();
+        next
+    }
+
+    /// The move in standard algebraic notation, such as `Nbd7`, `exd6`,
+    /// `O-O` or `e8=Q#`.
+    pub fn san(&self, played: ChessMove) -> String {
+        let Some(piece) = self.piece(played.from) else {
+            return String::new();
+        };
+        let files = file(played.to) as i8 - file(played.from) as i8;
+        let mut san = if piece.kind == Kind::King && files.abs() == 2 {
+            match files {
+                2 => "O-O".to_owned(),
+                _ => "O-O-O".to_owned(),
+            }
+        } else {
+            let mut san = piece.kind.letter().to_owned();
+            let captures = self.captures(played);
+            if piece.kind == Kind::Pawn {
+                if captures {
+                    san.push((b'a' + file(played.from)) as char);
+                }
+            } else {
+                let rivals: Vec<_> = self
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|other| {
+                        other.to == played.to
+                            && other.from != played.from
+                            && self.piece(other.from) == Some(piece)
+                    })
+                    .collect();
+                if !rivals.is_empty() {
+                    let name = square_name(played.from);
+                    if rivals
+                        .iter()
+                        .all(|other| file(other.from) != file(played.from))
+                    {
+                        san.push_str(&name[..1]);
+                    } else if rivals
+                        .iter()
+                        .all(|other| rank(other.from) != rank(played.from))
+                    {
+                        san.push_str(&name[1..]);
+                    } else {
+                        san.push_str(&name);
+                    }
+                }
+            }
+            if captures {
+                san.push('x');
+            }
+            san.push_str(&square_name(played.to));
+            if let Some(kind) = played.promotion {
+                san.push('=');
+                san.push_str(kind.letter());
+            }
+            san
+        };
+        let next = self.play(played);
+        if next.in_check(next.to_move) {
+            san.push(match next.legal_moves().is_empty() {
+                true => '#',
+                false => '+',
+            });
+        }
+        san
+    }
+
+    /// Neither side can mate: bare kings, or a king and one minor piece
+    /// against a bare king.
+    fn insufficient_material(&self) -> bool {
+        let mut minors = 0;
+        for piece in self.board.iter().flatten() {
+            match piece.kind {
+                Kind::King => {}
+                Kind::Knight | Kind::Bishop => minors += 1,
+                _ => return false,
+            }
+        }
+        minors <= 1
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessEnd {
+    Checkmate {
+        winner: Color,
+    },
+    Stalemate,
+    InsufficientMaterial,
+    /// Fifty moves by each side without a capture or pawn move.
+    FiftyMoves,
+    /// The same position for the third time.
+    Repetition,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ChessSettings {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChessError {
+    Illegal,
+}
+
+impl fmt::Display for ChessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChessError::Illegal => write!(f, "that move is not legal"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChessView {
+    /// Indexed by [`Square`].
+    pub board: Vec<Option<Piece>>,
+    pub to_move: Color,
+    pub check: bool,
+    /// Moves the receiver can play now, empty unless it is their turn.
+    pub legal_moves: Vec<ChessMove>,
+    pub last_move: Option<ChessMove>,
+    /// Every move so far in standard algebraic notation.
+    pub history: Vec<String>,
+    pub end: Option<ChessEnd>,
+}
+
+/// Draws by repetition and the fifty-move rule are applied as soon as they
+/// can be claimed.
+pub struct Chess {
+    position: Position,
+    legal_moves: Vec<ChessMove>,
+    /// How often each position occurred since the last capture or pawn move,
+    /// as none of them can come back after one.
+    seen: HashMap<Position, u32>,
+    halfmove_clock: u32,
+    last_move: Option<ChessMove>,
+    history: Vec<String>,
+    end: Option<ChessEnd>,
+}
+
+impl Chess {
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn end(&self) -> Option<ChessEnd> {
+        self.end
+    }
+
+    fn detect_end(&self) -> Option<ChessEnd> {
+        let color = self.position.to_move;
+        if self.legal_moves.is_empty() {
+            return Some(match self.position.in_check(color) {
+                true => ChessEnd::Checkmate {
+                    winner: color.opponent(),
+                },
+                false => ChessEnd::Stalemate,
+            });
+        }
+        if self.position.insufficient_material() {
+            Some(ChessEnd::InsufficientMaterial)
+        } else if self
+            .seen
+            .get(&self.position)
+            .is_some_and(|&count| count >= 3)
+        {
+            Some(ChessEnd::Repetition)
+        } else if self.halfmove_clock >= 100 {
+            Some(ChessEnd::FiftyMoves)
+        } else {
+            None
+        }
+    }
+}
+
+/// Longest line of PGN movetext.
+const PGN_LINE_LENGTH: usize = 79;
+
+fn pgn_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The match as a PGN game, with the seven-tag roster and its moves.
+fn pgn(game: &Match<Chess>, info: &ExportInfo) -> String {
+    let result = match game.outcome() {
+        Some(Outcome::Win(0)) => "1-0",
+        Some(Outcome::Win(_)) => "0-1",
+        Some(Outcome::Draw) => "1/2-1/2",
+        None => "*",
+    };
+    let date = match info.finished_at.map(date) {
+        Some((year, month, day)) => format!("{year:04}.{month:02}.{day:02}"),
+        None => "????.??.??".to_owned(),
+    };
+    let termination = if game.flagged().is_some() {
+        "time forfeit"
+    } else if game.outcome().is_some() {
+        "normal"
+    } else {
+        "unterminated"
+    };
+    let tags = [
+        ("Event", "Casual game"),
+        ("Site", &info.room),
+        ("Date", &date),
+        ("Round", "-"),
+        ("White", info.player(Color::White.seat())),
+        ("Black", info.player(Color::Black.seat())),
+        ("Result", result),
+        ("Termination", termination),
+    ];
+    let mut text = String::new();
+    for (name, value) in tags {
+        text.push_str(&format!("[{name} \"{}\"]\n", pgn_string(value)));
+    }
+    text.push('\n');
+
+    let mut tokens = Vec::new();
+    for (ply, san) in game.game().history().iter().enumerate() {
+        if ply % 2 == 0 {
+            tokens.push(format!("{}.", ply / 2 + 1));
+        }
+        tokens.push(san.clone());
+    }
+    tokens.push(result.to_owned());
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > PGN_LINE_LENGTH {
+            text.push_str(&line);
+            text.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    text.push_str(&line);
+    text.push('\n');
+    text
+}
+
+impl Game for Chess {
+    const KIND: &'static str = "chess";
+
+    type Settings = ChessSettings;
+    type Move = ChessMove;
+    type View = ChessView;
+    type Error = ChessError;
+
+    fn new(_: &ChessSettings) -> Self {
+        let position = Position::start();
+        Self {
+            legal_moves: position.legal_moves(),
+            seen: HashMap::from([(position.clone(), 1)]),
+            position,
+            halfmove_clock: 0,
+            last_move: None,
+            history: Vec::new(),
+            end: None,
+        }
+    }
+
+    fn seats(_: &ChessSettings) -> usize {
+        2
+    }
+
+    fn view(&self, seat: Option<usize>) -> ChessView {
+        let legal_moves = if seat.is_some() && seat == self.to_move() {
+            self.legal_moves.clone()
+        } else {
+            Vec::new()
+        };
+        ChessView {
+            board: self.position.board.to_vec(),
+            to_move: self.position.to_move,
+            check: self.position.in_check(self.position.to_move),
+            legal_moves,
+            last_move: self.last_move,
+            history: self.history.clone(),
+            end: self.end,
+        }
+    }
+
+    fn validate(&self, _: usize, played: &ChessMove) -> Result<(), ChessError> {
+        match self.legal_moves.contains(played) {
+            true => Ok(()),
+            false => Err(ChessError::Illegal),
+        }
+    }
+
+    fn apply(&mut self, _: usize, played: &ChessMove) {
+        let played = *played;
+        let irreversible = self.position.captures(played)
+            || self
+                .position
+                .piece(played.from)
+                .is_some_and(|piece| piece.kind == Kind::Pawn);
+        self.history.push(self.position.san(played));
+        self.position = self.position.play(played);
+        self.legal_moves = self.position.legal_moves();
+        self.last_move = Some(played);
+        if irreversible {
+            self.halfmove_clock = 0;
+            self.seen.clear();
+        } else {
+            self.halfmove_clock += 1;
+        }
+        *self.seen.entry(self.position.clone()).or_default() += 1;
+        self.end = self.detect_end();
+    }
+
+    fn to_move(&self) -> Option<usize> {
+        match self.end {
+            Some(_) => None,
+            None => Some(self.position.to_move.seat()),
+        }
+    }
+
+    fn export(game: &Match<Self>, info: &ExportInfo) -> Option<Notation> {
+        Some(Notation {
+            extension: "pgn",
+            content_type: "application/x-chess-pgn",
+            text: pgn(game, info),
+        })
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.end.map(|end| match end {
+            ChessEnd::Checkmate { winner } => Outcome::Win(winner.seat()),
+            _ => Outcome::Draw,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -";
+
+    /// The position of a FEN record, without the move counters.
+    fn fen(record: &str) -> Position {
+        let fields: Vec<&str> = record.split(' ').collect();
+        let mut board = [None; 64];
+        for (row, pieces) in fields[0].split('/').enumerate() {
+            let mut square = (7 - row as u8) * 8;
+            for letter in pieces.chars() {
+                if let Some(empty) = letter.to_digit(10) {
+                    square += empty as u8;
+                    continue;
+                }
+                let kind = match letter.to_ascii_lowercase() {
+                    'p' => Kind::Pawn,
+                    'n' => Kind::Knight,
+                    'b' => Kind::Bishop,
+                    'r' => Kind::Rook,
+                    'q' => Kind::Queen,
+                    _ => Kind::King,
+                };
+                let color = match letter.is_ascii_uppercase() {
+                    true => Color::White,
+                    false => Color::Black,
+                };
+                board[square as usize] = Some(Piece { color, kind });
+                square += 1;
+            }
+        }
+        let castling =
+            [['K', 'Q'], ['k', 'q']].map(|sides| sides.map(|side| fields[2].contains(side)));
+        Position {
+            board,
+            to_move: match fields[1] {
+                "w" => Color::White,
+                _ => Color::Black,
+            },
+            castling,
+            en_passant: fields.get(3).and_then(|&name| square(name)),
+        }
+    }
+
+    fn square(name: &str) -> Option<Square> {
+        let &[file, rank] = name.as_bytes() else {
+            return None;
+        };
+        Some((rank - b'1') * 8 + file - b'a')
+    }
+
+    fn chess_move(from: &str, to: &str) -> ChessMove {
+        ChessMove {
+            from: square(from).unwrap(),
+            to: square(to).unwrap(),
+            promotion: None,
+        }
+    }
+
+    fn perft(position: &Position, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        position
+            .legal_moves()
+            .into_iter()
+            .map(|played| perft(&position.play(played), depth - 1))
+            .sum()
+    }
+
+    fn game_from(position: Position) -> Chess {
+        Chess {
+            legal_moves: position.legal_moves(),
+            seen: HashMap::from([(position.clone(), 1)]),
+            position,
+            halfmove_clock: 0,
+            last_move: None,
+            history: Vec::new(),
+            end: None,
+        }
+    }
+
+    fn play_all(game: &mut Chess, moves: &[(&str, &str)]) {
+        for &(from, to) in moves {
+            let played = chess_move(from, to);
+            let seat = game.position.to_move.seat();
+            assert_eq!(game.validate(seat, &played), Ok(()), "{from}{to}");
+            game.apply(seat, &played);
+        }
+    }
+
+    #[test]
+    fn perft_from_the_start() {
+        let position = Position::start();
+        let counts: Vec<u64> = (1..=3).map(|depth| perft(&position, depth)).collect();
+        assert_eq!(counts, [20, 400, 8902]);
+    }
+
+    #[test]
+    fn perft_from_kiwipete() {
+        let position = fen(KIWIPETE);
+        let counts: Vec<u64> = (1..=3).map(|depth| perft(&position, depth)).collect();
+        assert_eq!(counts, [48, 2039, 97862]);
+    }
+
+    #[test]
+    fn kings_cannot_castle_out_of_or_through_check() {
+        let through = fen("4kr2/8/8/8/8/8/8/R3K2R w KQ -");
+        let moves = through.legal_moves();
+        assert!(!moves.contains(&chess_move("e1", "g1")));
+        assert!(moves.contains(&chess_move("e1", "c1")));
+
+        let out_of = fen("4k3/8/8/8/8/8/8/R3K1rR w KQ -");
+        let moves = out_of.legal_moves();
+        assert!(!moves.contains(&chess_move("e1", "g1")));
+        assert!(!moves.contains(&chess_move("e1", "c1")));
+    }
+
+    #[test]
+    fn capturing_a_rook_ends_castling_on_its_side() {
+        let position = fen("r3k2r/8/8/8/4B3/8/8/R3K2R w KQkq -").play(chess_move("e4", "a8"));
+        assert_eq!(position.castling, [[true, true], [true, false]]);
+        assert!(!position.legal_moves().contains(&chess_move("e8", "c8")));
+        assert!(position.legal_moves().contains(&chess_move("e8", "g8")));
+    }
+
+    #[test]
+    fn pinned_pawns_cannot_take_en_passant() {
+        let pinned = fen("4k3/8/8/K2pP2r/8/8/8/8 w - d6");
+        assert!(!pinned.legal_moves().contains(&chess_move("e5", "d6")));
+
+        let free = fen("7k/8/8/3pP3/8/8/8/K7 w - d6");
+        assert!(free.legal_moves().contains(&chess_move("e5", "d6")));
+        let taken = free.play(chess_move("e5", "d6"));
+        assert_eq!(taken.piece(square("d5").unwrap()), None);
+    }
+
+    #[test]
+    fn promotions_are_required_on_the_last_rank_only() {
+        let game = game_from(fen("8/4P3/8/8/8/8/3P4/k3K3 w - -"));
+        let promote = |from, to, promotion| ChessMove {
+            promotion,
+            ..chess_move(from, to)
+        };
+        assert_eq!(
+            game.validate(0, &promote("e7", "e8", None)),
+            Err(ChessError::Illegal)
+        );
+        assert_eq!(
+            game.validate(0, &promote("e7", "e8", Some(Kind::King))),
+            Err(ChessError::Illegal)
+        );
+        assert_eq!(
+            game.validate(0, &promote("e7", "e8", Some(Kind::Knight))),
+            Ok(())
+        );
+        assert_eq!(
+            game.validate(0, &promote("d2", "d3", Some(Kind::Queen))),
+            Err(ChessError::Illegal)
+        );
+        assert_eq!(
+            game.position.san(promote("e7", "e8", Some(Kind::Queen))),
+            "e8=Q"
+        );
+    }
+
+    #[test]
+    fn san_names_the_file_or_rank_when_needed() {
+        let position = fen("4k3/8/8/R7/8/8/8/RN2KN2 w - -");
+        assert_eq!(position.san(chess_move("b1", "d2")), "Nbd2");
+        assert_eq!(position.san(chess_move("f1", "d2")), "Nfd2");
+        assert_eq!(position.san(chess_move("a1", "a3")), "R1a3");
+        assert_eq!(position.san(chess_move("a5", "a3")), "R5a3");
+        assert_eq!(position.san(chess_move("b1", "c3")), "Nc3");
+    }
+
+    #[test]
+    fn threefold_repetition_is_a_draw() {
+        let mut game = Chess::new(&ChessSettings {});
+        let shuffle = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+        play_all(&mut game, &shuffle);
+        assert_eq!(game.end(), None);
+        play_all(&mut game, &shuffle);
+        assert_eq!(game.end(), Some(ChessEnd::Repetition));
+        assert_eq!(game.outcome(), Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn fifty_moves_without_captures_or_pawn_moves_are_a_draw() {
+        let mut game = game_from(fen("4k3/8/8/8/8/8/4P3/4K1N1 w - -"));
+        game.halfmove_clock = 98;
+        play_all(&mut game, &[("g1", "f3")]);
+        assert_eq!(game.end(), None);
+        play_all(&mut game, &[("e8", "d8")]);
+        assert_eq!(game.end(), Some(ChessEnd::FiftyMoves));
+
+        let mut game = game_from(fen("4k3/8/8/8/8/8/4P3/4K1N1 w - -"));
+        game.halfmove_clock = 99;
+        play_all(&mut game, &[("e2", "e4")]);
+        assert_eq!(game.end(), None);
+    }
+
+    #[test]
+    fn pgn_lists_the_tags_and_moves() {
+        let mut game = Match::<Chess>::new(ChessSettings {}, None);
+        game.sit(1, 0).unwrap();
+        game.sit(2, 1).unwrap();
+        let moves = [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")];
+        for (ply, &(from, to)) in moves.iter().enumerate() {
+            game.play(ply as u64 % 2 + 1, chess_move(from, to), 0)
+                .unwrap();
+        }
+        let info = ExportInfo {
+            room: "the \"back\" room".to_owned(),
+            players: vec!["Alice".to_owned(), "Bob".to_owned()],
+            finished_at: Some(951_782_400_000),
+        };
+        let notation = Chess::export(&game, &info).unwrap();
+        assert_eq!(notation.extension, "pgn");
+        assert_eq!(
+            notation.text,
+            "[Event \"Casual game\"]\n\
+             [Site \"the \\\"back\\\" room\"]\n\
+             [Date \"2000.02.29\"]\n\
+             [Round \"-\"]\n\
+             [White \"Alice\"]\n\
+             [Black \"Bob\"]\n\
+             [Result \"0-1\"]\n\
+             [Termination \"normal\"]\n\
+             \n\
+             1. f3 e5 2. g4 Qh4# 0-1\n"
+        );
+    }
+}
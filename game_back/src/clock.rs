@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+
+/// How much thinking time each side gets, in milliseconds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeControl {
+    /// `initial` for the whole game.
+    SuddenDeath { initial: u64 },
+    /// `increment` is added after every move.
+    Fischer { initial: u64, increment: u64 },
+    /// The first `delay` of every move is free.
+    Delay { initial: u64, delay: u64 },
+}
+
+impl TimeControl {
+    fn initial(self) -> u64 {
+        match self {
+            TimeControl::SuddenDeath { initial }
+            | TimeControl::Fischer { initial, .. }
+            | TimeControl::Delay { initial, .. } => initial,
+        }
+    }
+
+    fn delay(self) -> u64 {
+        match self {
+            TimeControl::Delay { delay, .. } => delay,
+            _ => 0,
+        }
+    }
+}
+
+/// Chess clock of a match. Times are milliseconds since the Unix epoch,
+/// given by the room so that replaying a match gives the same clock.
+///
+/// Match records store it as is, so its serialized form has to stay stable.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Clock {
+    control: TimeControl,
+    /// Time left by seat, as of when its clock last stopped.
+    remaining: Vec<u64>,
+    /// The seat whose clock is running and since when.
+    running: Option<(usize, u64)>,
+}
+
+/// A clock as sent to clients, which count the running side down locally
+/// from when they got it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClockView {
+    /// Time left by seat when this was sent.
+    pub remaining: Vec<u64>,
+    pub running: Option<usize>,
+    /// Free time the running side has left before its clock goes down.
+    pub delay_left: u64,
+}
+
+impl ClockView {
+    /// The seat that ran out of time.
+    pub fn flagged(&self) -> Option<usize> {
+        self.remaining.iter().position(|&left| left == 0)
+    }
+}
+
+impl Clock {
+    pub fn new(control: TimeControl, seats: usize) -> Self {
+        Self {
+            control,
+            remaining: vec![control.initial(); seats],
+            running: None,
+        }
+    }
+
+    /// Time the running side has spent beyond its delay by `now`.
+    fn charged(&self, now: u64) -> u64 {
+        self.running.map_or(0, |(_, since)| {
+            now.saturating_sub(since)
+                .saturating_sub(self.control.delay())
+        })
+    }
+
+    pub fn remaining(&self, seat: usize, now: u64) -> u64 {
+        let left = self.remaining.get(seat).copied().unwrap_or_default();
+        match self.running {
+            Some((running, _)) if running == seat => left.saturating_sub(self.charged(now)),
+            _ => left,
+        }
+    }
+
+    /// When the running side runs out of time.
+    pub fn flag_at(&self) -> Option<u64> {
+        let (seat, since) = self.running?;
+        Some(since + self.control.delay() + self.remaining[seat])
+    }
+
+    /// The seat that ran out of time by `now`, if any.
+    pub fn flagged(&self, now: u64) -> Option<usize> {
+        let (seat, _) = self.running?;
+        (self.flag_at()? <= now).then_some(seat)
+    }
+
+    /// Stops `seat`'s clock after its move at `now`, and starts `next`'s
+    /// unless the game is over. The first move starts the clock for free.
+    pub fn press(&mut self, seat: usize, next: Option<usize>, now: u64) {
+        if let Some((running, _)) = self.running
+            && running == seat
+        {
+            self.remaining[seat] = self.remaining(seat, now);
+            if let TimeControl::Fischer { increment, .. } = self.control {
+                self.remaining[seat] += increment;
+            }
+        }
+        self.running = next.map(|next| (next, now));
+    }
+
+    /// Stops the clock at `now` when the game ends without a move.
+    pub fn stop(&mut self, now: u64) {
+        if let Some((seat, _)) = self.running {
+            self.remaining[seat] = self.remaining(seat, now);
+        }
+        self.running = None;
+    }
+
+    pub fn view(&self, now: u64) -> ClockView {
+        let delay_left = self.running.map_or(0, |(_, since)| {
+            self.control
+                .delay()
+                .saturating_sub(now.saturating_sub(since))
+        });
+        ClockView {
+            remaining: (0..self.remaining.len())
+                .map(|seat| self.remaining(seat, now))
+                .collect(),
+            running: self.running.map(|(seat, _)| seat),
+            delay_left,
+        }
+    }
+}
+
+/// Time control as chosen when creating a room, in seconds, like
+/// `?time=300&increment=2`. Without `time` the match is untimed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(default)]
+pub struct ClockSettings {
+    pub time: Option<u64>,
+    pub increment: Option<u64>,
+    pub delay: Option<u64>,
+}
+
+const MAX_TIME: u64 = 24 * 60 * 60;
+const MAX_BONUS: u64 = 60 * 60;
+
+impl ClockSettings {
+    pub fn is_valid(&self) -> bool {
+        let bonus_valid = match (self.increment, self.delay) {
+            (Some(_), Some(_)) => false,
+            (bonus, None) | (None, bonus) => bonus.is_none_or(|bonus| bonus <= MAX_BONUS),
+        };
+        match self.time {
+            Some(time) => (1..=MAX_TIME).contains(&time) && bonus_valid,
+            None => self.increment.is_none() && self.delay.is_none(),
+        }
+    }
+
+    /// `None` for an untimed match, or settings that are not
+    /// [`ClockSettings::is_valid`].
+    pub fn time_control(&self) -> Option<TimeControl> {
+        if !self.is_valid() {
+            return None;
+        }
+        let initial = self.time? * 1000;
+        Some(match (self.increment, self.delay) {
+            (Some(increment), _) => TimeControl::Fischer {
+                initial,
+                increment: increment * 1000,
+            },
+            (_, Some(delay)) => TimeControl::Delay {
+                initial,
+                delay: delay * 1000,
+            },
+            (None, None) => TimeControl::SuddenDeath { initial },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_first_move_is_free() {
+        let mut clock = Clock::new(
+            TimeControl::Fischer {
+                initial: 60_000,
+                increment: 2_000,
+            },
+            2,
+        );
+        assert_eq!(clock.flag_at(), None);
+        clock.press(0, Some(1), 1_000);
+        let view = clock.view(5_000);
+        assert_eq!(view.remaining, [60_000, 56_000]);
+        assert_eq!(view.running, Some(1));
+    }
+
+    #[test]
+    fn fischer_adds_the_increment_after_each_move() {
+        let mut clock = Clock::new(
+            TimeControl::Fischer {
+                initial: 60_000,
+                increment: 2_000,
+            },
+            2,
+        );
+        clock.press(0, Some(1), 1_000);
+        clock.press(1, Some(0), 11_000);
+        let view = clock.view(11_000);
+        assert_eq!(view.remaining, [60_000, 52_000]);
+        assert_eq!(view.delay_left, 0);
+        assert_eq!(clock.flag_at(), Some(71_000));
+        assert_eq!(clock.flagged(70_999), None);
+        assert_eq!(clock.flagged(71_000), Some(0));
+    }
+
+    #[test]
+    fn delay_is_spent_before_the_clock_goes_down() {
+        let mut clock = Clock::new(
+            TimeControl::Delay {
+                initial: 60_000,
+                delay: 5_000,
+            },
+            2,
+        );
+        clock.press(0, Some(1), 0);
+        let view = clock.view(3_000);
+        assert_eq!(view.remaining, [60_000, 60_000]);
+        assert_eq!(view.delay_left, 2_000);
+
+        clock.press(1, Some(0), 3_000);
+        assert_eq!(clock.remaining(1, 3_000), 60_000);
+        let view = clock.view(10_000);
+        assert_eq!(view.remaining, [58_000, 60_000]);
+        assert_eq!(view.delay_left, 0);
+        assert_eq!(clock.flag_at(), Some(68_000));
+        assert_eq!(clock.flagged(67_999), None);
+        assert_eq!(clock.flagged(68_000), Some(0));
+    }
+
+    #[test]
+    fn sudden_death_runs_out() {
+        let mut clock = Clock::new(TimeControl::SuddenDeath { initial: 10_000 }, 2);
+        clock.press(0, Some(1), 0);
+        clock.press(1, Some(0), 4_000);
+        assert_eq!(clock.view(4_000).remaining, [10_000, 6_000]);
+        assert_eq!(clock.flag_at(), Some(14_000));
+        assert_eq!(clock.flagged(13_999), None);
+        assert_eq!(clock.flagged(14_000), Some(0));
+        let view = clock.view(20_000);
+        assert_eq!(view.remaining, [0, 6_000]);
+        assert_eq!(view.flagged(), Some(0));
+    }
+
+    #[test]
+    fn stopping_keeps_the_time_left() {
+        let mut clock = Clock::new(TimeControl::SuddenDeath { initial: 10_000 }, 2);
+        clock.press(0, Some(1), 0);
+        clock.stop(3_000);
+        assert_eq!(clock.flag_at(), None);
+        assert_eq!(clock.flagged(100_000), None);
+        let view = clock.view(100_000);
+        assert_eq!(view.remaining, [10_000, 7_000]);
+        assert_eq!(view.running, None);
+    }
+
+    #[test]
+    fn settings_are_checked() {
+        let settings = |time, increment, delay| ClockSettings {
+            time,
+            increment,
+            delay,
+        };
+        assert!(settings(None, None, None).is_valid());
+        assert!(!settings(None, Some(2), None).is_valid());
+        assert!(!settings(None, None, Some(2)).is_valid());
+        assert!(!settings(Some(0), None, None).is_valid());
+        assert!(settings(Some(MAX_TIME), None, None).is_valid());
+        assert!(!settings(Some(MAX_TIME + 1), None, None).is_valid());
+        assert!(!settings(Some(300), Some(2), Some(2)).is_valid());
+        assert!(settings(Some(300), Some(MAX_BONUS), None).is_valid());
+        assert!(!settings(Some(300), Some(MAX_BONUS + 1), None).is_valid());
+        assert!(settings(Some(300), None, Some(MAX_BONUS)).is_valid());
+        assert!(!settings(Some(300), None, Some(MAX_BONUS + 1)).is_valid());
+
+        assert_eq!(settings(None, None, None).time_control(), None);
+        assert_eq!(
+            settings(Some(300), None, Some(5)).time_control(),
+            Some(TimeControl::Delay {
+                initial: 300_000,
+                delay: 5_000,
+            })
+        );
+        assert_eq!(settings(Some(300), Some(2), Some(2)).time_control(), None);
+    }
+}
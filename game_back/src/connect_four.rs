@@ -0,0 +1,411 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, Outcome};
+
+pub const COLUMNS: usize = 7;
+pub const ROWS: usize = 6;
+
+/// Cells are numbered row by row from the bottom left corner.
+pub fn cell(column: usize, row: usize) -> usize {
+    row * COLUMNS + column
+}
+
+/// Plies the computer player looks ahead.
+const BOT_DEPTH: u32 = 6;
+const WIN_SCORE: i32 = 1_000_000;
+/// Center columns first, so alpha-beta prunes more and ties favor them.
+const SEARCH_ORDER: [usize; COLUMNS] = [3, 2, 4, 1, 5, 0, 6];
+/// Horizontal, vertical and both diagonals.
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Disc {
+    Red,
+    Yellow,
+}
+
+impl Disc {
+    pub fn opponent(self) -> Disc {
+        match self {
+            Disc::Red => Disc::Yellow,
+            Disc::Yellow => Disc::Red,
+        }
+    }
+
+    /// Red plays first from seat 0.
+    pub fn seat(self) -> usize {
+        match self {
+            Disc::Red => 0,
+            Disc::Yellow => 1,
+        }
+    }
+
+    pub fn from_seat(seat: usize) -> Disc {
+        match seat {
+            0 => Disc::Red,
+            _ => Disc::Yellow,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectFourMove {
+    pub column: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ConnectFourSettings {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectFourError {
+    NoSuchColumn,
+    ColumnFull,
+}
+
+impl fmt::Display for ConnectFourError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectFourError::NoSuchColumn => write!(f, "there is no such column"),
+            ConnectFourError::ColumnFull => write!(f, "that column is full"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConnectFourView {
+    /// Indexed by [`cell`].
+    pub board: Vec<Option<Disc>>,
+    pub to_move: Disc,
+    /// Cell of the last disc dropped.
+    pub last: Option<usize>,
+    /// Cells of the four or more discs in a row that won.
+    pub line: Vec<usize>,
+    pub full: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Grid {
+    cells: [Option<Disc>; COLUMNS * ROWS],
+    heights: [usize; COLUMNS],
+}
+
+impl Grid {
+    fn from_view(view: &ConnectFourView) -> Option<Self> {
+        let cells = view.board.clone().try_into().ok()?;
+        let mut heights = [0; COLUMNS];
+        for (column, height) in heights.iter_mut().enumerate() {
+            *height = (0..ROWS)
+                .take_while(|&row| view.board[cell(column, row)].is_some())
+                .count();
+        }
+        Some(Self { cells, heights })
+    }
+
+    fn is_full(&self) -> bool {
+        self.heights.iter().all(|&height| height == ROWS)
+    }
+
+    /// Returns the row the disc landed on.
+    fn drop(&mut self, column: usize, disc: Disc) -> usize {
+        let row = self.heights[column];
+        self.cells[cell(column, row)] = Some(disc);
+        self.heights[column] += 1;
+        row
+    }
+
+    /// Whether the disc at `column`, `row` is part of four in a row. Cheaper
+    /// than [`Grid::line_through`], for the search.
+    fn wins_at(&self, column: usize, row: usize) -> bool {
+        let disc = self.cells[cell(column, row)];
+        let length = |dc: isize, dr: isize| {
+            [1, -1]
+                .into_iter()
+                .map(|sign| {
+                    (1..4)
+                        .take_while(|step| {
+                            let (c, r) = (
+                                column as isize + sign * step * dc,
+                                row as isize + sign * step * dr,
+                            );
+                            (0..COLUMNS as isize).contains(&c)
+                                && (0..ROWS as isize).contains(&r)
+                                && self.cells[cell(c as usize, r as usize)] == disc
+                        })
+                        .count()
+                })
+                .sum::<usize>()
+        };
+        DIRECTIONS.into_iter().any(|(dc, dr)| length(dc, dr) >= 3)
+    }
+
+    /// The longest line of four or more through a disc, if any.
+    fn line_through(&self, column: usize, row: usize) -> Option<Vec<usize>> {
+        let disc = self.cells[cell(column, row)]?;
+        let same = |column: isize, row: isize| {
+            (0..COLUMNS as isize).contains(&column)
+                && (0..ROWS as isize).contains(&row)
+                && self.cells[cell(column as usize, row as usize)] == Some(disc)
+        };
+        DIRECTIONS
+            .into_iter()
+            .map(|(dc, dr)| {
+                let mut line = vec![cell(column, row)];
+                for sign in [1, -1] {
+                    let (mut c, mut r) = (column as isize + sign * dc, row as isize + sign * dr);
+                    while same(c, r) {
+                        line.push(cell(c as usize, r as usize));
+                        c += sign * dc;
+                        r += sign * dr;
+                    }
+                }
+                line
+            })
+            .filter(|line| line.len() >= 4)
+            .max_by_key(|line| line.len())
+    }
+
+    /// How good the position looks for `disc`, from every window of four
+    /// cells and the control of the center column.
+    fn evaluate(&self, disc: Disc) -> i32 {
+        let mut score = 0;
+        for row in 0..ROWS {
+            match self.cells[cell(COLUMNS / 2, row)] {
+                Some(found) if found == disc => score += 3,
+                Some(_) => score -= 3,
+                None => {}
+            }
+        }
+        for (dc, dr) in DIRECTIONS {
+            for column in 0..COLUMNS as isize {
+                for row in 0..ROWS as isize {
+                    let (end_column, end_row) = (column + 3 * dc, row + 3 * dr);
+                    if end_column >= COLUMNS as isize || !(0..ROWS as isize).contains(&end_row) {
+                        continue;
+                    }
+                    let (mut mine, mut theirs) = (0, 0);
+                    for step in 0..4 {
+                        let index = cell((column + step * dc) as usize, (row + step * dr) as usize);
+                        match self.cells[index] {
+                            Some(found) if found == disc => mine += 1,
+                            Some(_) => theirs += 1,
+                            None => {}
+                        }
+                    }
+                    score += match (mine, theirs) {
+                        (3, 0) => 5,
+                        (2, 0) => 2,
+                        (0, 3) => -4,
+                        _ => 0,
+                    };
+                }
+            }
+        }
+        score
+    }
+
+    /// Negamax with alpha-beta pruning: the score of the best move for
+    /// `disc`, higher for quicker wins.
+    fn search(&self, disc: Disc, depth: u32, mut alpha: i32, beta: i32) -> i32 {
+        // Checking for a winning drop first saves searching the others.
+        for column in 0..COLUMNS {
+            if self.heights[column] < ROWS {
+                let mut next = *self;
+                let row = next.drop(column, disc);
+                if next.wins_at(column, row) {
+                    return WIN_SCORE + depth as i32;
+                }
+            }
+        }
+        let mut best = None;
+        for column in SEARCH_ORDER {
+            if self.heights[column] == ROWS {
+                continue;
+            }
+            let mut next = *self;
+            next.drop(column, disc);
+            let score = if depth == 0 {
+                next.evaluate(disc)
+            } else {
+                -next.search(disc.opponent(), depth - 1, -beta, -alpha)
+            };
+            best = Some(best.map_or(score, |best: i32| best.max(score)));
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        // A full board is a draw.
+        best.unwrap_or(0)
+    }
+}
+
+pub struct ConnectFour {
+    grid: Grid,
+    to_move: Disc,
+    last: Option<usize>,
+    line: Vec<usize>,
+}
+
+impl ConnectFour {
+    fn winner(&self) -> Option<Disc> {
+        let first = *self.line.first()?;
+        self.grid.cells[first]
+    }
+
+    fn is_over(&self) -> bool {
+        !self.line.is_empty() || self.grid.is_full()
+    }
+}
+
+impl Game for ConnectFour {
+    const KIND: &'static str = "connect_four";
+    const HAS_BOT: bool = true;
+
+    type Settings = ConnectFourSettings;
+    type Move = ConnectFourMove;
+    type View = ConnectFourView;
+    type Error = ConnectFourError;
+
+    fn new(_: &ConnectFourSettings) -> Self {
+        Self {
+            grid: Grid {
+                cells: [None; COLUMNS * ROWS],
+                heights: [0; COLUMNS],
+            },
+            to_move: Disc::Red,
+            last: None,
+            line: Vec::new(),
+        }
+    }
+
+    fn seats(_: &ConnectFourSettings) -> usize {
+        2
+    }
+
+    fn view(&self, _: Option<usize>) -> ConnectFourView {
+        ConnectFourView {
+            board: self.grid.cells.to_vec(),
+            to_move: self.to_move,
+            last: self.last,
+            line: self.line.clone(),
+            full: self.grid.is_full(),
+        }
+    }
+
+    fn validate(&self, _: usize, played: &ConnectFourMove) -> Result<(), ConnectFourError> {
+        match self.grid.heights.get(played.column as usize) {
+            None => Err(ConnectFourError::NoSuchColumn),
+            Some(&ROWS) => Err(ConnectFourError::ColumnFull),
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn apply(&mut self, _: usize, played: &ConnectFourMove) {
+        let column = played.column as usize;
+        let row = self.grid.drop(column, self.to_move);
+        self.last = Some(cell(column, row));
+        self.line = self.grid.line_through(column, row).unwrap_or_default();
+        self.to_move = self.to_move.opponent();
+    }
+
+    fn to_move(&self) -> Option<usize> {
+        match self.is_over() {
+            true => None,
+            false => Some(self.to_move.seat()),
+        }
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match self.winner() {
+            Some(disc) => Some(Outcome::Win(disc.seat())),
+            None if self.grid.is_full() => Some(Outcome::Draw),
+            None => None,
+        }
+    }
+
+    fn bot_move(view: &ConnectFourView, _: usize) -> Option<ConnectFourMove> {
+        let grid = Grid::from_view(view)?;
+        let disc = view.to_move;
+        let mut best: Option<(i32, usize)> = None;
+        let mut alpha = -2 * WIN_SCORE;
+        for column in SEARCH_ORDER {
+            if grid.heights[column] == ROWS {
+                continue;
+            }
+            let mut next = grid;
+            let row = next.drop(column, disc);
+            let score = if next.wins_at(column, row) {
+                WIN_SCORE + BOT_DEPTH as i32
+            } else {
+                -next.search(disc.opponent(), BOT_DEPTH - 1, -2 * WIN_SCORE, -alpha)
+            };
+            if best.is_none_or(|(best, _)| score > best) {
+                best = Some((score, column));
+                alpha = score;
+            }
+        }
+        best.map(|(_, column)| ConnectFourMove {
+            column: column as u8,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The game after dropping discs in these columns, Red first.
+    fn game_after(columns: &[u8]) -> ConnectFour {
+        let mut game = ConnectFour::new(&ConnectFourSettings {});
+        for &column in columns {
+            let played = ConnectFourMove { column };
+            let seat = game.to_move.seat();
+            assert_eq!(game.validate(seat, &played), Ok(()));
+            game.apply(seat, &played);
+        }
+        game
+    }
+
+    fn bot_column(game: &ConnectFour) -> Option<u8> {
+        let seat = game.to_move.seat();
+        ConnectFour::bot_move(&game.view(Some(seat)), seat).map(|played| played.column)
+    }
+
+    #[test]
+    fn the_bot_takes_an_immediate_win() {
+        let game = game_after(&[6, 0, 6, 0, 6, 1]);
+        assert_eq!(bot_column(&game), Some(6));
+    }
+
+    #[test]
+    fn the_bot_blocks_an_immediate_loss() {
+        let game = game_after(&[0, 5, 1, 5, 0, 5]);
+        assert_eq!(bot_column(&game), Some(5));
+    }
+
+    #[test]
+    fn diagonal_lines_are_found_both_ways() {
+        let game = game_after(&[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3]);
+        let mut line = game.line.clone();
+        line.sort();
+        assert_eq!(line, [cell(0, 0), cell(1, 1), cell(2, 2), cell(3, 3)]);
+        assert_eq!(game.outcome(), Some(Outcome::Win(Disc::Red.seat())));
+
+        let mut grid = ConnectFour::new(&ConnectFourSettings {}).grid;
+        for (column, row) in [(0, 4), (1, 3), (2, 2)] {
+            grid.cells[cell(column, row)] = Some(Disc::Yellow);
+        }
+        assert_eq!(grid.line_through(2, 2), None);
+        for (column, row) in [(3, 1), (4, 0)] {
+            grid.cells[cell(column, row)] = Some(Disc::Yellow);
+        }
+        let mut line = grid.line_through(2, 2).unwrap();
+        line.sort();
+        assert_eq!(
+            line,
+            [cell(4, 0), cell(3, 1), cell(2, 2), cell(1, 3), cell(0, 4)]
+        );
+    }
+}
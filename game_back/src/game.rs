@@ -0,0 +1,429 @@
+use std::fmt::{self, Debug, Display};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    clock::{Clock, ClockView, TimeControl},
+    history::{ExportInfo, Notation},
+    store::{MatchEvent, MatchRecord},
+};
+
+/// Rules of a turn-based game. Players are numbered by their seat, starting
+/// at zero.
+pub trait Game: Sized + Send + Sync + 'static {
+    /// Names the room type, in paths and on disk.
+    const KIND: &'static str;
+    /// Whether [`Game::bot_move`] can play for a seat.
+    const HAS_BOT: bool = false;
+
+    /// Chosen when a room is created, such as a board size or rule variant.
+    type Settings: Serialize + DeserializeOwned + Clone + Debug + Default + Send + Sync;
+    type Move: Serialize + DeserializeOwned + Clone + Debug + Send + Sync;
+    /// What a player, or a spectator, is shown of the game.
+    type View: Serialize + DeserializeOwned + Clone + Debug + Send + Sync;
+    type Error: Display + Send;
+
+    fn new(settings: &Self::Settings) -> Self;
+
+    fn seats(settings: &Self::Settings) -> usize;
+
+    /// The game as seen from `seat`, or by a spectator for `None`.
+    fn view(&self, seat: Option<usize>) -> Self::View;
+
+    fn validate(&self, seat: usize, played: &Self::Move) -> Result<(), Self::Error>;
+
+    /// Plays a move [`Game::validate`] accepted.
+    fn apply(&mut self, seat: usize, played: &Self::Move);
+
+    /// The seat whose turn it is, `None` once the game is over.
+    fn to_move(&self) -> Option<usize>;
+
+    fn outcome(&self) -> Option<Outcome>;
+
+    /// Picks a move for a computer player in `seat` when it is its turn,
+    /// from what that seat is shown.
+    fn bot_move(_view: &Self::View, _seat: usize) -> Option<Self::Move> {
+        None
+    }
+
+    /// Writes a match in the game's standard notation, for games that have
+    /// one.
+    fn export(_game: &Match<Self>, _info: &ExportInfo) -> Option<Notation> {
+        None
+    }
+}
+
+/// User id of the computer player in `seat`. Real user ids are random, so
+/// taking them from the top of the range does not clash in practice.
+pub fn bot_id(seat: usize) -> u64 {
+    u64::MAX - seat as u64
+}
+
+pub fn is_bot(user: u64) -> bool {
+    u64::MAX - user < MAX_BOTS
+}
+
+const MAX_BOTS: u64 = 64;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win(usize),
+    Draw,
+}
+
+/// A match as sent to one client.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameState<View> {
+    pub view: View,
+    /// The user in each seat.
+    pub seats: Vec<Option<u64>>,
+    /// The receiver's seat, if any.
+    pub seat: Option<usize>,
+    pub to_move: Option<usize>,
+    pub outcome: Option<Outcome>,
+    /// Moves played so far.
+    pub moves: usize,
+    pub clock: Option<ClockView>,
+}
+
+impl<View> GameState<View> {
+    /// Whether a side lost by running out of time.
+    pub fn ended_on_time(&self) -> bool {
+        self.outcome.is_some()
+            && self
+                .clock
+                .as_ref()
+                .is_some_and(|clock| clock.flagged().is_some())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchError<E> {
+    NoSuchSeat,
+    SeatTaken,
+    AlreadySeated,
+    NotSeated,
+    /// Play starts once every seat is taken.
+    WaitingForPlayers,
+    /// Seats are fixed while the match is on.
+    InProgress,
+    NotYourTurn,
+    Over,
+    /// Resigning needs exactly one opponent.
+    NotTwoPlayers,
+    NoBot,
+    Illegal(E),
+}
+
+impl<E: Display> Display for MatchError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchError::NoSuchSeat => write!(f, "there is no such seat"),
+            MatchError::SeatTaken => write!(f, "the seat is taken"),
+            MatchError::AlreadySeated => write!(f, "you already have a seat"),
+            MatchError::NotSeated => write!(f, "you are not playing"),
+            MatchError::WaitingForPlayers => write!(f, "waiting for every seat to be taken"),
+            MatchError::InProgress => write!(f, "seats cannot change during a match"),
+            MatchError::NotYourTurn => write!(f, "it is not your turn"),
+            MatchError::Over => write!(f, "the match is over"),
+            MatchError::NotTwoPlayers => write!(f, "only two-player matches can be resigned"),
+            MatchError::NoBot => write!(f, "this game has no computer player"),
+            MatchError::Illegal(error) => write!(f, "illegal move: {error}"),
+        }
+    }
+}
+
+/// One match of a game with the users seated at it and every move played,
+/// so it can be persisted and replayed.
+///
+/// Times are milliseconds since the Unix epoch, passed in by the caller.
+pub struct Match<G: Game> {
+    settings: G::Settings,
+    game: G,
+    seats: Vec<Option<u64>>,
+    moves: Vec<(usize, G::Move)>,
+    resigned: Option<usize>,
+    /// The seat that ran out of time.
+    flagged: Option<usize>,
+    clock: Option<Clock>,
+}
+
+impl<G: Game> Match<G> {
+    /// Only two-player matches are timed, as running out of time loses
+    /// against the single opponent.
+    pub fn new(settings: G::Settings, control: Option<TimeControl>) -> Self {
+        let seats = G::seats(&settings);
+        Self {
+            game: G::new(&settings),
+            seats: vec![None; seats],
+            settings,
+            moves: Vec::new(),
+            resigned: None,
+            flagged: None,
+            clock: control
+                .filter(|_| seats == 2)
+                .map(|control| Clock::new(control, seats)),
+        }
+    }
+
+    /// Rebuilds a match by replaying its moves, which were valid when they
+    /// were recorded.
+    pub fn from_record(record: MatchRecord<G::Settings, G::Move>) -> Self {
+        let mut game = Self::new(record.settings, None);
+        for (seat, user) in record.seats.into_iter().enumerate() {
+            if let Some(slot) = game.seats.get_mut(seat) {
+                *slot = user;
+            }
+        }
+        for (seat, played) in record.moves {
+            game.game.apply(seat, &played);
+            game.moves.push((seat, played));
+        }
+        game.resigned = record.resigned;
+        game.flagged = record.flagged;
+        game.clock = record.clock;
+        game
+    }
+
+    pub fn record(&self) -> MatchRecord<G::Settings, G::Move> {
+        MatchRecord {
+            settings: self.settings.clone(),
+            seats: self.seats.clone(),
+            moves: self.moves.clone(),
+            resigned: self.resigned,
+            flagged: self.flagged,
+            clock: self.clock.clone(),
+        }
+    }
+
+    pub fn apply_event(&mut self, event: MatchEvent<G::Move>) {
+        match event {
+            MatchEvent::Sat { seat, user } => {
+                if let Some(slot) = self.seats.get_mut(seat) {
+                    *slot = user;
+                }
+            }
+            MatchEvent::Moved { seat, played, at } => {
+                self.game.apply(seat, &played);
+                self.moves.push((seat, played));
+                let next = self.to_move();
+                if let Some(clock) = &mut self.clock {
+                    clock.press(seat, next, at);
+                }
+            }
+            MatchEvent::Resigned { seat, at } => {
+                self.resigned = Some(seat);
+                if let Some(clock) = &mut self.clock {
+                    clock.stop(at);
+                }
+            }
+            MatchEvent::Flagged { seat } => {
+                self.flagged = Some(seat);
+                if let Some(clock) = &mut self.clock
+                    && let Some(at) = clock.flag_at()
+                {
+                    clock.stop(at);
+                }
+            }
+        }
+    }
+
+    pub fn settings(&self) -> &G::Settings {
+        &self.settings
+    }
+
+    pub fn game(&self) -> &G {
+        &self.game
+    }
+
+    pub fn moves(&self) -> &[(usize, G::Move)] {
+        &self.moves
+    }
+
+    pub fn seats(&self) -> &[Option<u64>] {
+        &self.seats
+    }
+
+    /// The seat that resigned.
+    pub fn resigned(&self) -> Option<usize> {
+        self.resigned
+    }
+
+    /// The seat that ran out of time.
+    pub fn flagged(&self) -> Option<usize> {
+        self.flagged
+    }
+
+    /// The game as spectators saw it before the first move and after each
+    /// one, to replay it move by move.
+    pub fn replay(&self) -> Vec<G::View> {
+        let mut game = G::new(&self.settings);
+        let mut views = vec![game.view(None)];
+        for (seat, played) in &self.moves {
+            game.apply(*seat, played);
+            views.push(game.view(None));
+        }
+        views
+    }
+
+    /// Whether every seat is taken, which moves and resigning wait for.
+    pub fn is_full(&self) -> bool {
+        self.seats.iter().all(Option::is_some)
+    }
+
+    pub fn seat_of(&self, user: u64) -> Option<usize> {
+        self.seats.iter().position(|&seated| seated == Some(user))
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        match self.resigned.or(self.flagged) {
+            Some(seat) => Some(Outcome::Win(1 - seat)),
+            None => self.game.outcome(),
+        }
+    }
+
+    /// When the side to move runs out of time, for the room to call
+    /// [`Match::flag`] then.
+    pub fn flag_at(&self) -> Option<u64> {
+        self.clock.as_ref()?.flag_at()
+    }
+
+    /// Ends the match if the side to move ran out of time by `now`.
+    pub fn flag(&mut self, now: u64) -> Option<MatchEvent<G::Move>> {
+        let seat = self.clock.as_ref()?.flagged(now)?;
+        let event = MatchEvent::Flagged { seat };
+        self.apply_event(event.clone());
+        Some(event)
+    }
+
+    pub fn to_move(&self) -> Option<usize> {
+        match self.outcome() {
+            Some(_) => None,
+            None => self.game.to_move(),
+        }
+    }
+
+    fn in_progress(&self) -> bool {
+        !self.moves.is_empty() && self.outcome().is_none()
+    }
+
+    pub fn sit(
+        &mut self,
+        user: u64,
+        seat: usize,
+    ) -> Result<MatchEvent<G::Move>, MatchError<G::Error>> {
+        if self.in_progress() {
+            return Err(MatchError::InProgress);
+        }
+        if self.seat_of(user).is_some() {
+            return Err(MatchError::AlreadySeated);
+        }
+        let event = match self.seats.get(seat) {
+            None => return Err(MatchError::NoSuchSeat),
+            Some(Some(_)) => return Err(MatchError::SeatTaken),
+            Some(None) => MatchEvent::Sat {
+                seat,
+                user: Some(user),
+            },
+        };
+        self.apply_event(event.clone());
+        Ok(event)
+    }
+
+    pub fn stand(&mut self, user: u64) -> Result<MatchEvent<G::Move>, MatchError<G::Error>> {
+        if self.in_progress() {
+            return Err(MatchError::InProgress);
+        }
+        let seat = self.seat_of(user).ok_or(MatchError::NotSeated)?;
+        let event = MatchEvent::Sat { seat, user: None };
+        self.apply_event(event.clone());
+        Ok(event)
+    }
+
+    /// Seats a computer player, which the room has to attach as
+    /// [`bot_id`] for it to play.
+    pub fn add_bot(&mut self, seat: usize) -> Result<MatchEvent<G::Move>, MatchError<G::Error>> {
+        if !G::HAS_BOT || seat as u64 >= MAX_BOTS {
+            return Err(MatchError::NoBot);
+        }
+        self.sit(bot_id(seat), seat)
+    }
+
+    pub fn remove_bot(&mut self, seat: usize) -> Result<MatchEvent<G::Move>, MatchError<G::Error>> {
+        match self.seats.get(seat) {
+            Some(&Some(user)) if is_bot(user) => self.stand(user),
+            _ => Err(MatchError::NoBot),
+        }
+    }
+
+    /// Plays a move for the user's seat if it is their turn, the rules allow
+    /// it and their time has not run out.
+    pub fn play(
+        &mut self,
+        user: u64,
+        played: G::Move,
+        now: u64,
+    ) -> Result<MatchEvent<G::Move>, MatchError<G::Error>> {
+        let seat = self.seat_of(user).ok_or(MatchError::NotSeated)?;
+        if !self.is_full() {
+            return Err(MatchError::WaitingForPlayers);
+        }
+        if self
+            .clock
+            .as_ref()
+            .is_some_and(|clock| clock.flagged(now).is_some())
+        {
+            return Err(MatchError::Over);
+        }
+        match self.to_move() {
+            None => return Err(MatchError::Over),
+            Some(to_move) if to_move != seat => return Err(MatchError::NotYourTurn),
+            Some(_) => {}
+        }
+        self.game
+            .validate(seat, &played)
+            .map_err(MatchError::Illegal)?;
+        let event = MatchEvent::Moved {
+            seat,
+            played,
+            at: now,
+        };
+        self.apply_event(event.clone());
+        Ok(event)
+    }
+
+    pub fn resign(
+        &mut self,
+        user: u64,
+        now: u64,
+    ) -> Result<MatchEvent<G::Move>, MatchError<G::Error>> {
+        let seat = self.seat_of(user).ok_or(MatchError::NotSeated)?;
+        if self.seats.len() != 2 {
+            return Err(MatchError::NotTwoPlayers);
+        }
+        if !self.is_full() {
+            return Err(MatchError::WaitingForPlayers);
+        }
+        if self.outcome().is_some() {
+            return Err(MatchError::Over);
+        }
+        let event = MatchEvent::Resigned { seat, at: now };
+        self.apply_event(event.clone());
+        Ok(event)
+    }
+
+    /// The match as `user` sees it from their seat, or as a spectator, at
+    /// `now`.
+    pub fn state_for(&self, user: u64, now: u64) -> GameState<G::View> {
+        let seat = self.seat_of(user);
+        GameState {
+            view: self.game.view(seat),
+            seats: self.seats.clone(),
+            seat,
+            to_move: self.to_move(),
+            outcome: self.outcome(),
+            moves: self.moves.len(),
+            clock: self.clock.as_ref().map(|clock| clock.view(now)),
+        }
+    }
+}
@@ -0,0 +1,538 @@
+use std::{collections::HashSet, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Game, Match, Outcome},
+    history::{ExportInfo, Notation, date},
+};
+
+/// Intersections are numbered row by row from the top left corner.
+pub type Point = u16;
+
+/// Such as `D4`, skipping the letter I as usual. Rows count from the bottom.
+pub fn point_name(size: u8, point: Point) -> String {
+    let size = size as u16;
+    let column = b"ABCDEFGHJKLMNOPQRST"[(point % size) as usize] as char;
+    format!("{}{}", column, size - point / size)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stone {
+    Black,
+    White,
+}
+
+impl Stone {
+    pub fn opponent(self) -> Stone {
+        match self {
+            Stone::Black => Stone::White,
+            Stone::White => Stone::Black,
+        }
+    }
+
+    /// Black plays first from seat 0.
+    pub fn seat(self) -> usize {
+        match self {
+            Stone::Black => 0,
+            Stone::White => 1,
+        }
+    }
+
+    pub fn from_seat(seat: usize) -> Stone {
+        match seat {
+            0 => Stone::Black,
+            _ => Stone::White,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoMove {
+    Place(Point),
+    Pass,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GoSettings {
+    /// One of [`GoSettings::SIZES`].
+    pub size: u8,
+    /// Points given to White for moving second.
+    pub komi: f32,
+}
+
+impl GoSettings {
+    pub const SIZES: [u8; 3] = [9, 13, 19];
+
+    pub fn is_valid(&self) -> bool {
+        GoSettings::SIZES.contains(&self.size) && self.komi.is_finite()
+    }
+}
+
+impl Default for GoSettings {
+    fn default() -> Self {
+        Self {
+            size: 19,
+            komi: 7.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoError {
+    OffBoard,
+    Occupied,
+    Suicide,
+    /// The move would repeat an earlier position.
+    Ko,
+}
+
+impl fmt::Display for GoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoError::OffBoard => write!(f, "that point is not on the board"),
+            GoError::Occupied => write!(f, "that point is taken"),
+            GoError::Suicide => write!(f, "that stone would have no liberties"),
+            GoError::Ko => write!(f, "that move would repeat an earlier position"),
+        }
+    }
+}
+
+/// Area score, stones plus surrounded empty points, komi included.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GoScore {
+    pub black: f32,
+    pub white: f32,
+    /// Who owns each point, indexed by [`Point`].
+    pub territory: Vec<Option<Stone>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GoView {
+    pub size: u8,
+    /// Indexed by [`Point`].
+    pub board: Vec<Option<Stone>>,
+    pub to_move: Stone,
+    /// Stones each side took, by seat.
+    pub captures: [u32; 2],
+    pub last_move: Option<GoMove>,
+    /// Set once both players passed in a row.
+    pub score: Option<GoScore>,
+}
+
+/// Go with Tromp–Taylor scoring: every stone left on the board counts as
+/// alive, so dead stones have to be captured before passing. Suicide is
+/// forbidden and no position may repeat (positional superko).
+pub struct Go {
+    size: u8,
+    komi: f32,
+    board: Vec<Option<Stone>>,
+    to_move: Stone,
+    captures: [u32; 2],
+    /// Every position so far, for the superko rule.
+    seen: HashSet<Vec<Option<Stone>>>,
+    last_move: Option<GoMove>,
+    passes: u32,
+    score: Option<GoScore>,
+}
+
+impl Go {
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn board(&self) -> &[Option<Stone>] {
+        &self.board
+    }
+
+    pub fn score(&self) -> Option<&GoScore> {
+        self.score.as_ref()
+    }
+
+    fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> {
+        let size = self.size as u16;
+        let (row, column) = (point / size, point % size);
+        [
+            (row > 0).then(|| point - size),
+            (row + 1 < size).then_some(point + size),
+            (column > 0).then(|| point - 1),
+            (column + 1 < size).then_some(point + 1),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// The stones connected to `start` and whether they have a liberty.
+    fn group(&self, board: &[Option<Stone>], start: Point) -> (Vec<Point>, bool) {
+        let stone = board[start as usize];
+        let mut group = vec![start];
+        let mut visited = HashSet::from([start]);
+        let mut free = false;
+        let mut next = 0;
+        while next < group.len() {
+            let point = group[next];
+            next += 1;
+            for neighbor in self.neighbors(point) {
+                match board[neighbor as usize] {
+                    None => free = true,
+                    found if found == stone && visited.insert(neighbor) => {
+                        group.push(neighbor);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (group, free)
+    }
+
+    /// The board after `stone` is placed on `point`, with the number of
+    /// stones it captured.
+    fn place(&self, stone: Stone, point: Point) -> Result<(Vec<Option<Stone>>, u32), GoError> {
+        if point as usize >= self.board.len() {
+            return Err(GoError::OffBoard);
+        }
+        if self.board[point as usize].is_some() {
+            return Err(GoError::Occupied);
+        }
+        let mut board = self.board.clone();
+        board[point as usize] = Some(stone);
+        let mut captured = 0;
+        for neighbor in self.neighbors(point) {
+            if board[neighbor as usize] != Some(stone.opponent()) {
+                continue;
+            }
+            let (group, free) = self.group(&board, neighbor);
+            if !free {
+                captured += group.len() as u32;
+                for taken in group {
+                    board[taken as usize] = None;
+                }
+            }
+        }
+        if !self.group(&board, point).1 {
+            return Err(GoError::Suicide);
+        }
+        if self.seen.contains(&board) {
+            return Err(GoError::Ko);
+        }
+        Ok((board, captured))
+    }
+
+    fn area_score(&self) -> GoScore {
+        let mut territory = self.board.clone();
+        let mut visited = HashSet::new();
+        for start in 0..self.board.len() as Point {
+            if self.board[start as usize].is_some() || !visited.insert(start) {
+                continue;
+            }
+            let mut region = vec![start];
+            let mut borders = HashSet::new();
+            let mut next = 0;
+            while next < region.len() {
+                let point = region[next];
+                next += 1;
+                for neighbor in self.neighbors(point) {
+                    match self.board[neighbor as usize] {
+                        Some(stone) => {
+                            borders.insert(stone);
+                        }
+                        None if visited.insert(neighbor) => region.push(neighbor),
+                        None => {}
+                    }
+                }
+            }
+            if borders.len() == 1 {
+                let owner = borders.into_iter().next();
+                for point in region {
+                    territory[point as usize] = owner;
+                }
+            }
+        }
+        let count = |stone| {
+            territory
+                .iter()
+                .filter(|&&owner| owner == Some(stone))
+                .count() as f32
+        };
+        GoScore {
+            black: count(Stone::Black),
+            white: count(Stone::White) + self.komi,
+            territory,
+        }
+    }
+}
+
+fn sgf_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+/// Such as `B+R` after a resignation or `W+2.5` after counting.
+fn sgf_result(game: &Match<Go>) -> Option<String> {
+    let letter = |seat| match Stone::from_seat(seat) {
+        Stone::Black => 'B',
+        Stone::White => 'W',
+    };
+    let winner = match game.outcome()? {
+        Outcome::Win(seat) => letter(seat),
+        Outcome::Draw => return Some("0".to_owned()),
+    };
+    Some(if game.resigned().is_some() {
+        format!("{winner}+R")
+    } else if game.flagged().is_some() {
+        format!("{winner}+T")
+    } else {
+        let score = game.game().score()?;
+        format!("{winner}+{}", (score.black - score.white).abs())
+    })
+}
+
+/// The match as an SGF game record. Points are written as column then row
+/// letters from the top left corner, and a pass as an empty move.
+fn sgf(game: &Match<Go>, info: &ExportInfo) -> String {
+    let settings = game.settings();
+    let mut text = format!(
+        "(;FF[4]GM[1]CA[UTF-8]SZ[{}]KM[{}]RU[Tromp-Taylor]",
+        settings.size, settings.komi
+    );
+    text.push_str(&format!(
+        "PB[{}]PW[{}]GN[{}]",
+        sgf_text(info.player(Stone::Black.seat())),
+        sgf_text(info.player(Stone::White.seat())),
+        sgf_text(&info.room),
+    ));
+    if let Some((year, month, day)) = info.finished_at.map(date) {
+        text.push_str(&format!("DT[{year:04}-{month:02}-{day:02}]"));
+    }
+    if let Some(result) = sgf_result(game) {
+        text.push_str(&format!("RE[{result}]"));
+    }
+    let size = settings.size as u16;
+    for (seat, played) in game.moves() {
+        let color = match Stone::from_seat(*seat) {
+            Stone::Black => 'B',
+            Stone::White => 'W',
+        };
+        let point = match *played {
+            GoMove::Place(point) => {
+                let letter = |index: u16| (b'a' + index as u8) as char;
+                format!("{}{}", letter(point % size), letter(point / size))
+            }
+            GoMove::Pass => String::new(),
+        };
+        text.push_str(&format!("\n;{color}[{point}]"));
+    }
+    text.push_str(")\n");
+    text
+}
+
+impl Game for Go {
+    const KIND: &'static str = "go";
+
+    type Settings = GoSettings;
+    type Move = GoMove;
+    type View = GoView;
+    type Error = GoError;
+
+    fn new(settings: &GoSettings) -> Self {
+        let board = vec![None; settings.size as usize * settings.size as usize];
+        Self {
+            size: settings.size,
+            komi: settings.komi,
+            seen: HashSet::from([board.clone()]),
+            board,
+            to_move: Stone::Black,
+            captures: [0; 2],
+            last_move: None,
+            passes: 0,
+            score: None,
+        }
+    }
+
+    fn seats(_: &GoSettings) -> usize {
+        2
+    }
+
+    fn view(&self, _: Option<usize>) -> GoView {
+        GoView {
+            size: self.size,
+            board: self.board.clone(),
+            to_move: self.to_move,
+            captures: self.captures,
+            last_move: self.last_move,
+            score: self.score.clone(),
+        }
+    }
+
+    fn validate(&self, _: usize, played: &GoMove) -> Result<(), GoError> {
+        match *played {
+            GoMove::Place(point) => self.place(self.to_move, point).map(|_| ()),
+            GoMove::Pass => Ok(()),
+        }
+    }
+
+    fn apply(&mut self, _: usize, played: &GoMove) {
+        match *played {
+            GoMove::Place(point) => {
+                let Ok((board, captured)) = self.place(self.to_move, point) else {
+                    return;
+                };
+                self.seen.insert(board.clone());
+                self.board = board;
+                self.captures[self.to_move.seat()] += captured;
+                self.passes = 0;
+            }
+            GoMove::Pass => {
+                self.passes += 1;
+                if self.passes >= 2 {
+                    self.score = Some(self.area_score());
+                }
+            }
+        }
+        self.last_move = Some(*played);
+        self.to_move = self.to_move.opponent();
+    }
+
+    fn to_move(&self) -> Option<usize> {
+        match self.score {
+            Some(_) => None,
+            None => Some(self.to_move.seat()),
+        }
+    }
+
+    fn export(game: &Match<Self>, info: &ExportInfo) -> Option<Notation> {
+        Some(Notation {
+            extension: "sgf",
+            content_type: "application/x-go-sgf",
+            text: sgf(game, info),
+        })
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        let score = self.score.as_ref()?;
+        Some(if score.black > score.white {
+            Outcome::Win(Stone::Black.seat())
+        } else if score.white > score.black {
+            Outcome::Win(Stone::White.seat())
+        } else {
+            Outcome::Draw
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: GoSettings = GoSettings { size: 9, komi: 7.5 };
+
+    fn at(row: u16, column: u16) -> Point {
+        row * 9 + column
+    }
+
+    /// A 9×9 game with Black to move from the rows of the diagram, where
+    /// `B` and `W` are stones and the rest of the board is empty.
+    fn game_from(diagram: &[&str]) -> Go {
+        let mut game = Go::new(&SETTINGS);
+        for (row, line) in diagram.iter().enumerate() {
+            for (column, letter) in line.chars().enumerate() {
+                game.board[row * 9 + column] = match letter {
+                    'B' => Some(Stone::Black),
+                    'W' => Some(Stone::White),
+                    _ => None,
+                };
+            }
+        }
+        game.seen = HashSet::from([game.board.clone()]);
+        game
+    }
+
+    fn play(game: &mut Go, played: GoMove) {
+        let seat = game.to_move.seat();
+        assert_eq!(game.validate(seat, &played), Ok(()), "{played:?}");
+        game.apply(seat, &played);
+    }
+
+    #[test]
+    fn ko_cannot_be_retaken_at_once() {
+        let mut game = game_from(&[
+            ".BW", //
+            "BW.W", ".BW",
+        ]);
+        play(&mut game, GoMove::Place(at(1, 2)));
+        assert_eq!(game.board[at(1, 1) as usize], None);
+        assert_eq!(game.captures, [1, 0]);
+        assert_eq!(game.validate(1, &GoMove::Place(at(1, 1))), Err(GoError::Ko));
+
+        play(&mut game, GoMove::Place(at(8, 8)));
+        play(&mut game, GoMove::Place(at(8, 0)));
+        play(&mut game, GoMove::Place(at(1, 1)));
+        assert_eq!(game.board[at(1, 2) as usize], None);
+    }
+
+    #[test]
+    fn stones_without_liberties_are_allowed_only_if_they_capture() {
+        let mut game = game_from(&[
+            ".WB", //
+            "WB.",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "........W",
+            ".......W.",
+        ]);
+        assert_eq!(
+            game.validate(0, &GoMove::Place(at(8, 8))),
+            Err(GoError::Suicide)
+        );
+        play(&mut game, GoMove::Place(at(0, 0)));
+        assert_eq!(game.board[at(0, 0) as usize], Some(Stone::Black));
+        assert_eq!(game.board[at(0, 1) as usize], None);
+        assert_eq!(game.board[at(1, 0) as usize], Some(Stone::White));
+        assert_eq!(game.captures, [1, 0]);
+    }
+
+    #[test]
+    fn counting_adds_stones_territory_and_komi() {
+        let wall: Vec<&str> = vec!["....BW..."; 9];
+        let mut game = game_from(&wall);
+        play(&mut game, GoMove::Pass);
+        assert_eq!(game.score(), None);
+        play(&mut game, GoMove::Pass);
+
+        let score = game.score().unwrap();
+        assert_eq!((score.black, score.white), (45.0, 43.5));
+        assert_eq!(score.territory[at(0, 0) as usize], Some(Stone::Black));
+        assert_eq!(score.territory[at(8, 8) as usize], Some(Stone::White));
+        assert_eq!(game.outcome(), Some(Outcome::Win(Stone::Black.seat())));
+        assert_eq!(game.to_move(), None);
+    }
+
+    #[test]
+    fn sgf_lists_the_properties_and_moves() {
+        let settings = GoSettings { size: 9, komi: 6.5 };
+        let mut game = Match::<Go>::new(settings, None);
+        game.sit(1, 0).unwrap();
+        game.sit(2, 1).unwrap();
+        game.play(1, GoMove::Place(at(2, 6)), 0).unwrap();
+        game.play(2, GoMove::Pass, 0).unwrap();
+        game.play(1, GoMove::Pass, 0).unwrap();
+        let info = ExportInfo {
+            room: "[corner]".to_owned(),
+            players: vec!["Alice".to_owned(), "Bob".to_owned()],
+            finished_at: Some(951_782_400_000),
+        };
+        let notation = Go::export(&game, &info).unwrap();
+        assert_eq!(notation.extension, "sgf");
+        assert_eq!(
+            notation.text,
+            "(;FF[4]GM[1]CA[UTF-8]SZ[9]KM[6.5]RU[Tromp-Taylor]\
+             PB[Alice]PW[Bob]GN[[corner\\]]DT[2000-02-29]RE[B+74.5]\n\
+             ;B[gc]\n\
+             ;W[]\n\
+             ;B[])\n"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::Outcome;
+
+/// A user at a finished match, named as when it ended.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Player {
+    pub id: u64,
+    pub name: String,
+}
+
+/// Entry of a user's match history, pointing at the room whose event log
+/// holds the match.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchSummary {
+    /// [`crate::game::Game::KIND`] of the room.
+    pub kind: String,
+    pub room: String,
+    /// The player in each seat.
+    pub players: Vec<Option<Player>>,
+    pub outcome: Outcome,
+    pub moves: usize,
+    /// Milliseconds since the Unix epoch.
+    pub finished_at: u64,
+}
+
+/// What an export needs besides the match itself.
+#[derive(Clone, Debug, Default)]
+pub struct ExportInfo {
+    pub room: String,
+    /// Names of the players by seat.
+    pub players: Vec<String>,
+    /// When the match ended, in milliseconds since the Unix epoch.
+    pub finished_at: Option<u64>,
+}
+
+impl ExportInfo {
+    pub fn player(&self, seat: usize) -> &str {
+        self.players.get(seat).map_or("?", String::as_str)
+    }
+}
+
+/// A match written in a game's standard notation.
+#[derive(Clone, Debug)]
+pub struct Notation {
+    /// File extension, such as `pgn`.
+    pub extension: &'static str,
+    pub content_type: &'static str,
+    pub text: String,
+}
+
+/// Year, month and day of a time in milliseconds since the Unix epoch, in
+/// UTC.
+pub fn date(millis: u64) -> (u64, u64, u64) {
+    // Days to civil date, counting in 400-year eras starting in March.
+    let days = millis / 86_400_000 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400_000;
+
+    #[test]
+    fn dates_start_at_the_epoch() {
+        assert_eq!(date(0), (1970, 1, 1));
+        assert_eq!(date(DAY - 1), (1970, 1, 1));
+        assert_eq!(date(DAY), (1970, 1, 2));
+    }
+
+    #[test]
+    fn leap_days_follow_the_gregorian_rules() {
+        // 2000 is a leap year, as it divides by 400.
+        let leap_day = 951_782_400_000;
+        assert_eq!(date(leap_day - 1), (2000, 2, 28));
+        assert_eq!(date(leap_day), (2000, 2, 29));
+        assert_eq!(date(leap_day + DAY - 1), (2000, 2, 29));
+        assert_eq!(date(leap_day + DAY), (2000, 3, 1));
+
+        // 2100 is not, as it divides by 100 only.
+        let march = 4_107_542_400_000;
+        assert_eq!(date(march - 1), (2100, 2, 28));
+        assert_eq!(date(march), (2100, 3, 1));
+    }
+}
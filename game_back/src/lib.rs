@@ -0,0 +1,41 @@
+pub mod checkers;
+pub mod chess;
+pub mod clock;
+pub mod connect_four;
+pub mod game;
+pub mod go;
+pub mod history;
+pub mod store;
+
+use backend_commons::{chat::ChatListMessage, client_info::ClientListMessage};
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameState;
+
+/// Messages of a room hosting one match of a [`game::Game`], whose views are
+/// `View`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ToClient<View> {
+    ClientListMessage(ClientListMessage),
+    ChatListMessage(ChatListMessage),
+    /// The match as seen from the receiver's seat, sent on joining and after
+    /// every change.
+    State(GameState<View>),
+    /// Why a seat request or move was refused, only sent to its author.
+    Rejected(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ToServer<Move> {
+    Chat(String),
+    /// Claims a free seat. Seats can only change before the first move and
+    /// after the match ended.
+    Sit(usize),
+    Stand,
+    Move(Move),
+    /// Concedes a two-player match.
+    Resign,
+    /// Seats a computer player, for games that have one.
+    AddBot(usize),
+    RemoveBot(usize),
+}
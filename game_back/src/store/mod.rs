@@ -0,0 +1,115 @@
+use std::io::{Error, ErrorKind, Result};
+
+use backend_commons::store::{StateBuilder, Store};
+use serde::Serialize;
+
+use crate::{
+    clock::TimeControl,
+    game::{Game, Match},
+    store::{
+        v1::{MatchEventV1, MatchRecordV1},
+        v2::{MatchEventV2, MatchRecordV2},
+    },
+};
+
+pub mod v1;
+pub mod v2;
+
+// When creating a new version, increment CURRENT_VERSION, add a module
+// converting from the previous one and point the aliases below at it.
+//
+// Games store their settings and moves inside these, and the clock is
+// stored as is, so their serialized form has to stay stable as well.
+
+pub const CURRENT_VERSION: u64 = 2;
+
+/// Snapshot of a match.
+pub type MatchRecord<Settings, Move> = MatchRecordV2<Settings, Move>;
+
+pub type MatchEvent<Move> = MatchEventV2<Move>;
+
+pub struct MatchStore<S: Store> {
+    pub store: S,
+}
+
+impl<S: Store> MatchStore<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub async fn apply_event<Move: Serialize>(&mut self, event: &MatchEvent<Move>) -> Result<()> {
+        let data = postcard::to_stdvec(event).unwrap();
+        self.store.apply_event(&data).await
+    }
+
+    pub async fn snapshot<G: Game>(&mut self, game: &Match<G>) -> Result<()> {
+        let data = postcard::to_stdvec(&game.record()).unwrap();
+        self.store.snapshot(&data).await
+    }
+}
+
+/// Rebuilds a match from its store. A store without a snapshot holds a match
+/// started with the settings and time control given here.
+pub struct MatchLoader<G: Game> {
+    game: Match<G>,
+    loaded: bool,
+}
+
+impl<G: Game> MatchLoader<G> {
+    pub fn new(settings: G::Settings, control: Option<TimeControl>) -> Self {
+        Self {
+            game: Match::new(settings, control),
+            loaded: false,
+        }
+    }
+
+    /// Whether nothing was loaded, so the match is new and its settings
+    /// still have to be stored.
+    pub fn is_new(&self) -> bool {
+        !self.loaded
+    }
+
+    pub fn get_match(self) -> Match<G> {
+        self.game
+    }
+}
+
+fn deserialize<T: serde::de::DeserializeOwned>(data: &[u8], what: &str) -> Result<T> {
+    postcard::from_bytes(data).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to deserialize {what}: {e}"),
+        )
+    })
+}
+
+fn unsupported_version(version: u64) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Unsupported version: {version}"),
+    )
+}
+
+impl<G: Game> StateBuilder for MatchLoader<G> {
+    fn load_state(&mut self, version: u64, data: Vec<u8>) -> Result<()> {
+        let record = match version {
+            1 => deserialize::<MatchRecordV1<_, _>>(&data, "match")?.into(),
+            2 => deserialize(&data, "match")?,
+            _ => return Err(unsupported_version(version)),
+        };
+        self.game = Match::from_record(record);
+        self.loaded = true;
+        Ok(())
+    }
+
+    fn load_event(&mut self, version: u64, data: Vec<u8>) -> Result<()> {
+        let event = match version {
+            1 => deserialize::<MatchEventV1<_>>(&data, "event")?.into(),
+            2 => deserialize(&data, "event")?,
+            _ => return Err(unsupported_version(version)),
+        };
+        self.game.apply_event(event);
+        self.loaded = true;
+        Ok(())
+    }
+}
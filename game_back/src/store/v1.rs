@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MatchRecordV1<Settings, Move> {
+    pub settings: Settings,
+    pub seats: Vec<Option<u64>>,
+    pub moves: Vec<(usize, Move)>,
+    pub resigned: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum MatchEventV1<Move> {
+    Sat { seat: usize, user: Option<u64> },
+    Moved { seat: usize, played: Move },
+    Resigned { seat: usize },
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::Clock,
+    store::v1::{MatchEventV1, MatchRecordV1},
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MatchRecordV2<Settings, Move> {
+    pub settings: Settings,
+    pub seats: Vec<Option<u64>>,
+    pub moves: Vec<(usize, Move)>,
+    pub resigned: Option<usize>,
+    pub flagged: Option<usize>,
+    pub clock: Option<Clock>,
+}
+
+/// Times are milliseconds since the Unix epoch, so replaying the events
+/// gives the same clock.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum MatchEventV2<Move> {
+    Sat {
+        seat: usize,
+        user: Option<u64>,
+    },
+    Moved {
+        seat: usize,
+        played: Move,
+        at: u64,
+    },
+    Resigned {
+        seat: usize,
+        at: u64,
+    },
+    /// The seat ran out of time.
+    Flagged {
+        seat: usize,
+    },
+}
+
+/// Matches before version 2 had no clock, so their times do not matter.
+impl<Settings, Move> From<MatchRecordV1<Settings, Move>> for MatchRecordV2<Settings, Move> {
+    fn from(record: MatchRecordV1<Settings, Move>) -> Self {
+        Self {
+            settings: record.settings,
+            seats: record.seats,
+            moves: record.moves,
+            resigned: record.resigned,
+            flagged: None,
+            clock: None,
+        }
+    }
+}
+
+impl<Move> From<MatchEventV1<Move>> for MatchEventV2<Move> {
+    fn from(event: MatchEventV1<Move>) -> Self {
+        match event {
+            MatchEventV1::Sat { seat, user } => Self::Sat { seat, user },
+            MatchEventV1::Moved { seat, played } => Self::Moved {
+                seat,
+                played,
+                at: 0,
+            },
+            MatchEventV1::Resigned { seat } => Self::Resigned { seat, at: 0 },
+        }
+    }
+}
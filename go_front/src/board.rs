@@ -0,0 +1,133 @@
+use frontend_commons::command::{Command, CommandConsumer};
+use game_back::go::Point;
+
+#[derive(Debug, Clone)]
+pub enum BoardCommand {
+    Point(Point),
+    Pass,
+    Sit(usize),
+    Stand,
+    Resign,
+}
+
+pub struct Board {
+    board: ts::Board,
+}
+
+impl Board {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, BoardCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            board: ts::Board::new(ts::BoardBackend::new(Box::new(consumer))),
+        }
+    }
+
+    pub fn set_size(&mut self, size: u8) {
+        self.board.set_size(size);
+    }
+
+    /// One character per point, `b`, `w` or a space, for both stones and
+    /// territory.
+    pub fn set_stones(&mut self, stones: &str, territory: &str, last: Option<Point>) {
+        let last = last.map_or(-1, i32::from);
+        self.board.set_stones(stones, territory, last);
+    }
+
+    pub fn set_seats(&mut self, black: &str, white: &str) {
+        self.board.set_seats(black, white);
+    }
+
+    pub fn set_status(&mut self, text: &str) {
+        self.board.set_status(text);
+    }
+}
+
+impl<State, Cmd> ts::BoardObserver for CommandConsumer<State, BoardCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_point(&mut self, point: u16) {
+        self.consume(BoardCommand::Point(point));
+    }
+
+    fn on_pass(&mut self) {
+        self.consume(BoardCommand::Pass);
+    }
+
+    fn on_sit(&mut self, seat: u32) {
+        self.consume(BoardCommand::Sit(seat as usize));
+    }
+
+    fn on_stand(&mut self) {
+        self.consume(BoardCommand::Stand);
+    }
+
+    fn on_resign(&mut self) {
+        self.consume(BoardCommand::Resign);
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait BoardObserver {
+        fn on_point(&mut self, point: u16);
+        fn on_pass(&mut self);
+        fn on_sit(&mut self, seat: u32);
+        fn on_stand(&mut self);
+        fn on_resign(&mut self);
+    }
+
+    #[wasm_bindgen]
+    pub struct BoardBackend {
+        observer: Box<dyn BoardObserver>,
+    }
+
+    impl BoardBackend {
+        pub fn new(observer: Box<dyn BoardObserver>) -> BoardBackend {
+            BoardBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl BoardBackend {
+        pub fn on_point(&mut self, point: u16) {
+            self.observer.on_point(point);
+        }
+        pub fn on_pass(&mut self) {
+            self.observer.on_pass();
+        }
+        pub fn on_sit(&mut self, seat: u32) {
+            self.observer.on_sit(seat);
+        }
+        pub fn on_stand(&mut self) {
+            self.observer.on_stand();
+        }
+        pub fn on_resign(&mut self) {
+            self.observer.on_resign();
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = board)]
+    unsafe extern "C" {
+        pub type Board;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: BoardBackend) -> Board;
+
+        #[wasm_bindgen(method)]
+        pub fn set_size(this: &Board, size: u8);
+
+        #[wasm_bindgen(method)]
+        pub fn set_stones(this: &Board, stones: &str, territory: &str, last: i32);
+
+        #[wasm_bindgen(method)]
+        pub fn set_seats(this: &Board, black: &str, white: &str);
+
+        #[wasm_bindgen(method)]
+        pub fn set_status(this: &Board, text: &str);
+    }
+}
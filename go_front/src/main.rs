@@ -0,0 +1,212 @@
+mod board;
+
+use std::collections::HashMap;
+
+use backend_commons::client_info::ClientListMessage;
+use frontend_commons::{
+    chat::{Chat, ChatCommand},
+    clock::Clock,
+    command::{Command, CommandConsumerGenerator},
+    socket::{Socket, SocketCommand},
+};
+use game_back::{
+    game::{GameState, Outcome},
+    go::{GoMove, GoView, Stone, point_name},
+};
+use log::{Level, debug, info};
+use web_sys::js_sys::Date;
+
+use crate::board::{Board, BoardCommand};
+
+type ToClient = game_back::ToClient<GoView>;
+type ToServer = game_back::ToServer<GoMove>;
+
+struct GoState {
+    socket: Socket<ToClient, ToServer>,
+    chat: Chat,
+    clock: Clock,
+    board: Board,
+    /// Names of the users in the room, to show who sits where.
+    names: HashMap<u64, String>,
+    game: Option<GameState<GoView>>,
+}
+
+#[derive(Debug)]
+enum GoCommand {
+    Socket(SocketCommand<ToClient>),
+    Chat(ChatCommand),
+    Board(BoardCommand),
+}
+
+fn stone_name(stone: Stone) -> &'static str {
+    match stone {
+        Stone::Black => "Black",
+        Stone::White => "White",
+    }
+}
+
+fn letters(points: &[Option<Stone>]) -> String {
+    points
+        .iter()
+        .map(|point| match point {
+            Some(Stone::Black) => 'b',
+            Some(Stone::White) => 'w',
+            None => ' ',
+        })
+        .collect()
+}
+
+fn status(game: &GameState<GoView>) -> String {
+    let view = &game.view;
+    let captures = format!(
+        "Captures: black {}, white {}",
+        view.captures[Stone::Black.seat()],
+        view.captures[Stone::White.seat()]
+    );
+    let result = match (game.outcome, &view.score) {
+        (Some(Outcome::Win(seat)), Some(score)) => format!(
+            "{} wins {} to {}",
+            stone_name(Stone::from_seat(seat)),
+            score.black.max(score.white),
+            score.black.min(score.white)
+        ),
+        (Some(Outcome::Win(seat)), None) if game.ended_on_time() => {
+            format!("{} wins on time", stone_name(Stone::from_seat(seat)))
+        }
+        (Some(Outcome::Win(seat)), None) => {
+            format!("{} wins by resignation", stone_name(Stone::from_seat(seat)))
+        }
+        (Some(Outcome::Draw), _) => "Draw".to_owned(),
+        (None, _) => {
+            let last = match view.last_move {
+                Some(GoMove::Place(point)) => format!(", last {}", point_name(view.size, point)),
+                Some(GoMove::Pass) => ", last passed".to_owned(),
+                None => String::new(),
+            };
+            format!("{} to move{}", stone_name(view.to_move), last)
+        }
+    };
+    format!("{}\n{}", result, captures)
+}
+
+impl GoState {
+    fn render(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let view = &game.view;
+        let territory = view.score.as_ref().map_or_else(
+            || " ".repeat(view.board.len()),
+            |score| letters(&score.territory),
+        );
+        let last = match view.last_move {
+            Some(GoMove::Place(point)) => Some(point),
+            _ => None,
+        };
+        let seat_name = |seat: usize| match game.seats.get(seat).copied().flatten() {
+            Some(user) => self
+                .names
+                .get(&user)
+                .cloned()
+                .unwrap_or_else(|| format!("#{user}")),
+            None => "(free)".to_owned(),
+        };
+        let (black, white) = (seat_name(0), seat_name(1));
+        self.board.set_size(view.size);
+        self.board
+            .set_stones(&letters(&view.board), &territory, last);
+        self.board.set_seats(&black, &white);
+        match &game.clock {
+            Some(clock) => self.clock.set(
+                [stone_name(Stone::Black), stone_name(Stone::White)],
+                &clock.remaining,
+                clock.running,
+                clock.delay_left,
+            ),
+            None => self.clock.hide(),
+        }
+        self.board.set_status(&status(game));
+    }
+}
+
+impl Command<GoState> for GoCommand {
+    fn apply(self, state: &mut GoState) {
+        match self {
+            GoCommand::Chat(ChatCommand::Send(text)) => {
+                state.socket.send(ToServer::Chat(text));
+            }
+            GoCommand::Board(command) => match command {
+                BoardCommand::Point(point) => {
+                    state.socket.send(ToServer::Move(GoMove::Place(point)))
+                }
+                BoardCommand::Pass => state.socket.send(ToServer::Move(GoMove::Pass)),
+                BoardCommand::Sit(seat) => state.socket.send(ToServer::Sit(seat)),
+                BoardCommand::Stand => state.socket.send(ToServer::Stand),
+                BoardCommand::Resign => state.socket.send(ToServer::Resign),
+            },
+            GoCommand::Socket(SocketCommand::Data(message)) => {
+                debug!("{:?}", message);
+                match message {
+                    ToClient::State(game) => {
+                        state.game = Some(game);
+                        state.render();
+                    }
+                    ToClient::Rejected(reason) => {
+                        state.chat.notice("go", Date::now() as u64, &reason);
+                    }
+                    ToClient::ChatListMessage(message) => state.chat.consume(message),
+                    ToClient::ClientListMessage(message) => {
+                        if let ClientListMessage::Joined(data) = message {
+                            state.names.insert(data.id, data.name);
+                            state.render();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl From<SocketCommand<ToClient>> for GoCommand {
+    fn from(value: SocketCommand<ToClient>) -> Self {
+        GoCommand::Socket(value)
+    }
+}
+
+impl From<ChatCommand> for GoCommand {
+    fn from(value: ChatCommand) -> Self {
+        GoCommand::Chat(value)
+    }
+}
+
+impl From<BoardCommand> for GoCommand {
+    fn from(value: BoardCommand) -> Self {
+        GoCommand::Board(value)
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(Level::Debug).unwrap();
+    info!("Hello, world!");
+    // Settings such as `?size=9&time=600` are passed on to the server, which
+    // uses them if the room is new.
+    let query = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+    let mut consumer_generator: CommandConsumerGenerator<_, GoCommand> =
+        CommandConsumerGenerator::new();
+    let state = GoState {
+        socket: Socket::new(
+            consumer_generator.make_consumer(),
+            &format!("socket{query}"),
+        ),
+        chat: Chat::new(consumer_generator.make_consumer()),
+        clock: Clock::new(),
+        board: Board::new(consumer_generator.make_consumer()),
+        names: HashMap::new(),
+        game: None,
+    };
+    consumer_generator.activate(state);
+}
@@ -0,0 +1,120 @@
+import JSX from "commons/createElement";
+
+export interface BoardObserver {
+    on_point(point: number): void;
+    on_pass(): void;
+    on_sit(seat: number): void;
+    on_stand(): void;
+    on_resign(): void;
+}
+
+const CELL = 32;
+const WOOD = "#dcb35c";
+const LINE = "#333";
+
+// Points are numbered row by row from the top left corner.
+export class Board {
+    private observer: BoardObserver;
+    private points: HTMLElement[] = [];
+    private stones: HTMLElement[] = [];
+    private grid: HTMLElement;
+    private status: HTMLElement;
+    private black: HTMLElement;
+    private white: HTMLElement;
+    private size = 0;
+
+    public constructor(observer: BoardObserver) {
+        this.observer = observer;
+        this.grid = <div style={`display: grid; background: ${WOOD}; padding: 8px; user-select: none; align-self: flex-start`}></div>;
+
+        this.status = <div style="margin: 8px 0; font-weight: bold"></div>;
+        this.black = <span></span>;
+        this.white = <span></span>;
+        const sitBlack = <button>Play black</button>;
+        sitBlack.addEventListener("click", _ => observer.on_sit(0));
+        const sitWhite = <button>Play white</button>;
+        sitWhite.addEventListener("click", _ => observer.on_sit(1));
+        const stand = <button>Stand up</button>;
+        stand.addEventListener("click", _ => observer.on_stand());
+        const pass = <button>Pass</button>;
+        pass.addEventListener("click", _ => observer.on_pass());
+        const resign = <button>Resign</button>;
+        resign.addEventListener("click", _ => observer.on_resign());
+
+        document.body.appendChild(<div style="display: flex; gap: 16px; padding: 16px; font-family: sans-serif; font-size: 14px">
+            {this.grid}
+            <div style="width: 280px">
+                <div>Black: {this.black}</div>
+                <div>White: {this.white}</div>
+                {this.status}
+                <div>{sitBlack} {sitWhite} {stand}</div>
+                <div style="margin-top: 8px">{pass} {resign}</div>
+            </div>
+        </div>);
+    }
+
+    // Rebuilds the grid, only when the size changes.
+    public set_size(size: number) {
+        if (this.size === size) {
+            return;
+        }
+        this.size = size;
+        this.points = [];
+        this.stones = [];
+        this.grid.style.gridTemplateColumns = `repeat(${size}, ${CELL}px)`;
+        this.grid.style.gridTemplateRows = `repeat(${size}, ${CELL}px)`;
+        const half = CELL / 2;
+        for (let point = 0; point < size * size; point++) {
+            const row = Math.floor(point / size);
+            const column = point % size;
+            // Lines stop at the middle of the edge points.
+            const left = column === 0 ? half : 0;
+            const right = column === size - 1 ? half : CELL;
+            const top = row === 0 ? half : 0;
+            const bottom = row === size - 1 ? half : CELL;
+            const element = <div style={`position: relative; cursor: pointer`}>
+                <div style={`position: absolute; left: ${left}px; width: ${right - left}px; top: ${half}px; height: 1px; background: ${LINE}`}></div>
+                <div style={`position: absolute; top: ${top}px; height: ${bottom - top}px; left: ${half}px; width: 1px; background: ${LINE}`}></div>
+            </div>;
+            const stone = <div style={`position: absolute; left: 2px; top: 2px; width: ${CELL - 4}px; height: ${CELL - 4}px; border-radius: 50%; box-sizing: border-box`}></div>;
+            element.appendChild(stone);
+            element.addEventListener("click", _ => this.observer.on_point(point));
+            this.points.push(element);
+            this.stones.push(stone);
+        }
+        this.grid.replaceChildren(...this.points);
+    }
+
+    // One character per point: 'b', 'w' or a space. Territory is drawn as
+    // small squares with the same letters.
+    public set_stones(stones: string, territory: string, last: number) {
+        for (let point = 0; point < this.stones.length; point++) {
+            const style = this.stones[point].style;
+            const stone = stones.charAt(point);
+            const owner = territory.charAt(point);
+            style.border = point === last ? "3px solid #e33" : "none";
+            if (stone !== " ") {
+                style.left = style.top = "2px";
+                style.width = style.height = `${CELL - 4}px`;
+                style.borderRadius = "50%";
+                style.background = stone === "b" ? "#111" : "#f5f5f5";
+            } else if (owner !== " ") {
+                style.left = style.top = `${CELL / 2 - 5}px`;
+                style.width = style.height = "10px";
+                style.borderRadius = "0";
+                style.background = owner === "b" ? "#111" : "#f5f5f5";
+            } else {
+                style.background = "none";
+            }
+        }
+    }
+
+    public set_seats(black: string, white: string) {
+        this.black.innerText = black;
+        this.white.innerText = white;
+    }
+
+    public set_status(text: string) {
+        this.status.innerText = text;
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Most entries sent for one leaderboard.
+pub const LEADERBOARD_SIZE: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LeaderboardEntry {
+    pub user: u64,
+    pub name: String,
+    pub rating: f64,
+    /// Rated matches played.
+    pub games: u32,
+}
+
+/// The best rated players of one game type, best first.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Leaderboard {
+    /// The game type, as in room paths, like `chess`.
+    pub kind: String,
+    pub entries: Vec<LeaderboardEntry>,
+}
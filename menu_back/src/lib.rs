@@ -1,15 +1,19 @@
+pub mod leaderboard;
 pub mod server_list;
 
 use serde::{Deserialize, Serialize};
 
-use crate::server_list::ServerListEvent;
+use crate::{leaderboard::Leaderboard, server_list::ServerListEvent};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ToClient {
     ServerListEvent(ServerListEvent),
+    Leaderboard(Leaderboard),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ToServer {
     Pong,
+    /// Asks for the leaderboard of a game type, like `chess`.
+    GetLeaderboard(String),
 }
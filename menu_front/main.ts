@@ -2,9 +2,13 @@
 
 import * as _socket from 'commons/socket'
 import * as _list from 'commons/list'
+import * as _leaderboard from 'commons/leaderboard'
 
 declare var socket;
 socket = _socket;
 
 declare var list
-list = _list
\ No newline at end of file
+list = _list
+
+declare var leaderboard;
+leaderboard = _leaderboard;
@@ -1,20 +1,26 @@
 use frontend_commons::{
     command::{Command, CommandConsumerGenerator},
+    leaderboard::{Leaderboard, LeaderboardCommand},
     list::{List, ListCommand},
     socket::{Socket, SocketCommand},
 };
 use log::{Level, debug, info};
 use menu_back::{ToClient, ToServer};
 
+/// Game types with a leaderboard, as in room paths.
+const RATED_KINDS: [&str; 4] = ["chess", "go", "connect_four", "checkers"];
+
 struct MenuState {
     socket: Socket<ToClient, ToServer>,
     list: List,
+    leaderboard: Leaderboard,
 }
 
 #[derive(Debug)]
 enum MenuCommand {
     Socket(SocketCommand<ToClient>),
     List(ListCommand),
+    Leaderboard(LeaderboardCommand),
 }
 
 impl Command<MenuState> for MenuCommand {
@@ -34,9 +40,24 @@ impl Command<MenuState> for MenuCommand {
                     todo!("Actually remove server from list")
                 }
             },
+            MenuCommand::Socket(SocketCommand::Data(ToClient::Leaderboard(leaderboard))) => {
+                let rows = leaderboard
+                    .entries
+                    .iter()
+                    .map(|entry| (entry.name.as_str(), entry.rating, entry.games));
+                state.leaderboard.set(&leaderboard.kind, rows);
+            }
+            MenuCommand::Socket(SocketCommand::Open) => {
+                state
+                    .socket
+                    .send(ToServer::GetLeaderboard(RATED_KINDS[0].to_owned()));
+            }
             MenuCommand::List(ListCommand::Click(elem)) => {
                 state.socket.send(ToServer::Pong);
             }
+            MenuCommand::Leaderboard(LeaderboardCommand::Select(kind)) => {
+                state.socket.send(ToServer::GetLeaderboard(kind));
+            }
             _ => {}
         }
     }
@@ -48,6 +69,12 @@ impl From<SocketCommand<ToClient>> for MenuCommand {
     }
 }
 
+impl From<LeaderboardCommand> for MenuCommand {
+    fn from(value: LeaderboardCommand) -> Self {
+        MenuCommand::Leaderboard(value)
+    }
+}
+
 impl From<ListCommand> for MenuCommand {
     fn from(value: ListCommand) -> Self {
         MenuCommand::List(value)
@@ -63,6 +90,7 @@ fn main() {
     let state = MenuState {
         socket: Socket::new(consumer_generator.make_consumer(), "socket"),
         list: List::new(consumer_generator.make_consumer()),
+        leaderboard: Leaderboard::new(consumer_generator.make_consumer(), &RATED_KINDS),
     };
     consumer_generator.activate(state);
 }
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Lamport timestamp of a write. Concurrent writes with the same time are
+/// ordered by the id of the writer, so every replica picks the same winner.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp {
+    pub time: u64,
+    pub writer: u64,
+}
+
+pub struct Clock {
+    time: u64,
+    writer: u64,
+}
+
+impl Clock {
+    pub fn new(writer: u64) -> Self {
+        Self { time: 0, writer }
+    }
+
+    /// Timestamp for a new local write, later than everything seen so far.
+    pub fn tick(&mut self) -> Timestamp {
+        self.time = self.time.saturating_add(1);
+        Timestamp {
+            time: self.time,
+            writer: self.writer,
+        }
+    }
+
+    pub fn observe(&mut self, timestamp: Timestamp) {
+        self.time = self.time.max(timestamp.time);
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_COMMENT_LENGTH: usize = 2000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Comment {
+    pub id: u64,
+    pub author: u64,
+    pub author_name: String,
+    pub text: String,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+/// Discussion of one board object. A thread exists as long as it has
+/// comments.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Thread {
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+/// Comments refer to objects by their global id, so only confirmed objects
+/// can be discussed.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum CommentAction {
+    Add { object: u64, text: String },
+    SetResolved { object: u64, resolved: bool },
+    Delete { object: u64, comment: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum CommentEvent {
+    Added { object: u64, comment: Comment },
+    ResolvedChanged { object: u64, resolved: bool },
+    Deleted { object: u64, comment: u64 },
+}
+
+impl CommentEvent {
+    pub fn object(&self) -> u64 {
+        match self {
+            CommentEvent::Added { object, .. }
+            | CommentEvent::ResolvedChanged { object, .. }
+            | CommentEvent::Deleted { object, .. } => *object,
+        }
+    }
+}
+
+impl Thread {
+    pub fn comment(&self, id: u64) -> Option<&Comment> {
+        self.comments.iter().find(|comment| comment.id == id)
+    }
+}
@@ -1,6 +1,19 @@
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::board::{
+    clock::Timestamp,
+    comments::Thread,
+    dice::DiceRoll,
+    roles::{Role, ShareLink},
+    seating::Seating,
+    settings::BoardSettings,
+    sheet::Slices,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum BoardAction {
@@ -10,6 +23,123 @@ pub enum BoardAction {
         local_id: u64,
         texture: Texture<ObjectIdentifier>,
     },
+    Move {
+        id: ObjectIdentifier,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetSettings(BoardSettings),
+    /// Turns an image into an empty deck with the image as its back.
+    MakeDeck {
+        image: ObjectIdentifier,
+    },
+    /// Puts an image or a card on top of a deck.
+    AddToDeck {
+        deck: u64,
+        object: ObjectIdentifier,
+    },
+    /// Takes the top card of a deck and lays it at the given position.
+    Draw {
+        deck: u64,
+        x: f64,
+        y: f64,
+    },
+    Shuffle {
+        deck: u64,
+    },
+    /// Turns a card or a deck over.
+    Flip {
+        id: u64,
+    },
+    /// Deals `count` face-down cards to each position in turn.
+    Deal {
+        deck: u64,
+        count: u32,
+        positions: Vec<(f64, f64)>,
+    },
+    /// Opens a hand zone owned by the sender.
+    NewHand {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    RemoveHand {
+        id: u64,
+    },
+    /// Places a dice object rolling the given notation, see
+    /// [`crate::board::dice::Notation`].
+    NewDice {
+        x: f64,
+        y: f64,
+        notation: String,
+    },
+    NewCounter {
+        x: f64,
+        y: f64,
+        value: i64,
+    },
+    /// Adds `delta` to whatever the counter holds when the server applies
+    /// it, so concurrent increments all count.
+    Increment {
+        id: u64,
+        delta: i64,
+    },
+    SetCounter {
+        id: u64,
+        value: i64,
+    },
+    /// Puts an image or a stack on top of another, which becomes a stack if
+    /// it was an image.
+    StackOnto {
+        target: u64,
+        object: ObjectIdentifier,
+    },
+    /// Lays the top image of a stack at the given position.
+    TakeFromStack {
+        stack: u64,
+        x: f64,
+        y: f64,
+    },
+    /// Moves the top `count` images of a stack to the given position.
+    SplitStack {
+        stack: u64,
+        count: u32,
+        x: f64,
+        y: f64,
+    },
+    /// Lays every image of a stack out separately, each one offset from the
+    /// one below it.
+    SpreadStack {
+        stack: u64,
+        dx: f64,
+        dy: f64,
+    },
+    /// Cuts an image into pieces laid where they were on it, or into a deck
+    /// whose back is the last piece.
+    SliceSheet {
+        image: ObjectIdentifier,
+        slices: Slices,
+        into_deck: bool,
+    },
+    /// Owner only: covers a region, hiding what lies in it from everyone
+    /// else.
+    NewFog {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    /// Owner only: reveals a region by removing its fog.
+    RemoveFog {
+        id: u64,
+    },
+    /// Owner only: hides an object from everyone else, or reveals it.
+    SetHidden {
+        id: u64,
+        hidden: bool,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -25,6 +155,48 @@ pub enum BoardEvent {
         global_id: u64,
         texture_id: u64,
     },
+    RejectImage {
+        local_id: u64,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    RoleChanged {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SettingsChanged(BoardSettings),
+    /// New state of an object as the receiving client may see it.
+    ObjectUpdated(BoardObject<u64>),
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(DiceRoll),
+    CounterChanged {
+        id: u64,
+        value: i64,
+    },
+    /// A texture made by the server, sent before the objects showing it.
+    NewTexture {
+        id: u64,
+        data: Vec<u8>,
+    },
+    /// Only sent to owners, the only ones who see hidden objects.
+    HiddenChanged {
+        id: u64,
+        hidden: bool,
+    },
+    SeatingChanged(Seating),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -32,14 +204,305 @@ pub enum BoardObject<Id> {
     Image(Image<Id>),
     // Todo: implement lines
     Line,
+    Deck(Deck<Id>),
+    Card(Card<Id>),
+    Hand(Hand<Id>),
+    Dice(Dice<Id>),
+    Counter(Counter<Id>),
+    Stack(Stack<Id>),
+    Fog(Fog<Id>),
 }
 
+/// Dice objects are squares of this side.
+pub const DICE_SIZE: f64 = 64.0;
+/// Counters are discs of this diameter.
+pub const COUNTER_SIZE: f64 = 64.0;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Image<Id> {
     pub id: Id,
     pub x: f64,
     pub y: f64,
     pub texture: Id,
+    pub position_timestamp: Timestamp,
+}
+
+/// A pile of cards sharing one back. The last card is on top.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Deck<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub back: Id,
+    pub cards: Vec<Id>,
+    pub face_up: bool,
+    pub position_timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Card<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub face: Id,
+    pub back: Id,
+    pub face_up: bool,
+    pub position_timestamp: Timestamp,
+}
+
+/// Area of the table private to one user. Cards whose top left corner lies
+/// in it show their face to the owner and their back to everyone else.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Hand<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub owner: u64,
+    pub position_timestamp: Timestamp,
+}
+
+/// Dice rolled together by the server, showing the outcome of their last
+/// roll.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Dice<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub notation: String,
+    pub last_roll: Option<DiceRoll>,
+    pub position_timestamp: Timestamp,
+}
+
+/// Images piled on each other, the last one on top. Unlike a deck, every
+/// image of a stack is known to everyone.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Stack<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub items: Vec<Id>,
+    pub position_timestamp: Timestamp,
+}
+
+/// A number on the table, such as hit points or a score.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Counter<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub value: i64,
+    pub position_timestamp: Timestamp,
+}
+
+/// Region hidden from everyone but the owners of the room, with every
+/// object whose top left corner lies in it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Fog<Id> {
+    pub id: Id,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub position_timestamp: Timestamp,
+}
+
+impl<Id> Hand<Id> {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && y >= self.y && x <= self.x + self.width && y <= self.y + self.height
+    }
+}
+
+impl<Id> Fog<Id> {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && y >= self.y && x <= self.x + self.width && y <= self.y + self.height
+    }
+}
+
+impl<Id: Copy> BoardObject<Id> {
+    pub fn id(&self) -> Option<Id> {
+        match self {
+            BoardObject::Image(img) => Some(img.id),
+            BoardObject::Deck(deck) => Some(deck.id),
+            BoardObject::Card(card) => Some(card.id),
+            BoardObject::Hand(hand) => Some(hand.id),
+            BoardObject::Dice(dice) => Some(dice.id),
+            BoardObject::Counter(counter) => Some(counter.id),
+            BoardObject::Stack(stack) => Some(stack.id),
+            BoardObject::Fog(fog) => Some(fog.id),
+            BoardObject::Line => None,
+        }
+    }
+
+    pub fn position(&self) -> Option<(f64, f64, Timestamp)> {
+        match self {
+            BoardObject::Image(img) => Some((img.x, img.y, img.position_timestamp)),
+            BoardObject::Deck(deck) => Some((deck.x, deck.y, deck.position_timestamp)),
+            BoardObject::Card(card) => Some((card.x, card.y, card.position_timestamp)),
+            BoardObject::Hand(hand) => Some((hand.x, hand.y, hand.position_timestamp)),
+            BoardObject::Dice(dice) => Some((dice.x, dice.y, dice.position_timestamp)),
+            BoardObject::Counter(counter) => {
+                Some((counter.x, counter.y, counter.position_timestamp))
+            }
+            BoardObject::Stack(stack) => Some((stack.x, stack.y, stack.position_timestamp)),
+            BoardObject::Fog(fog) => Some((fog.x, fog.y, fog.position_timestamp)),
+            BoardObject::Line => None,
+        }
+    }
+
+    pub fn set_position(&mut self, x: f64, y: f64, timestamp: Timestamp) {
+        let (obj_x, obj_y, obj_timestamp) = match self {
+            BoardObject::Image(img) => (&mut img.x, &mut img.y, &mut img.position_timestamp),
+            BoardObject::Deck(deck) => (&mut deck.x, &mut deck.y, &mut deck.position_timestamp),
+            BoardObject::Card(card) => (&mut card.x, &mut card.y, &mut card.position_timestamp),
+            BoardObject::Hand(hand) => (&mut hand.x, &mut hand.y, &mut hand.position_timestamp),
+            BoardObject::Dice(dice) => (&mut dice.x, &mut dice.y, &mut dice.position_timestamp),
+            BoardObject::Counter(counter) => (
+                &mut counter.x,
+                &mut counter.y,
+                &mut counter.position_timestamp,
+            ),
+            BoardObject::Stack(stack) => {
+                (&mut stack.x, &mut stack.y, &mut stack.position_timestamp)
+            }
+            BoardObject::Fog(fog) => (&mut fog.x, &mut fog.y, &mut fog.position_timestamp),
+            BoardObject::Line => return,
+        };
+        *obj_x = x;
+        *obj_y = y;
+        *obj_timestamp = timestamp;
+    }
+
+    /// Texture lying face up on the table.
+    pub fn visible_texture(&self) -> Option<Id> {
+        match self {
+            BoardObject::Image(img) => Some(img.texture),
+            BoardObject::Deck(deck) => match deck.cards.last() {
+                Some(&top) if deck.face_up => Some(top),
+                _ => Some(deck.back),
+            },
+            BoardObject::Card(card) if card.face_up => Some(card.face),
+            BoardObject::Card(card) => Some(card.back),
+            BoardObject::Stack(stack) => stack.items.last().copied(),
+            BoardObject::Hand(_)
+            | BoardObject::Dice(_)
+            | BoardObject::Counter(_)
+            | BoardObject::Fog(_)
+            | BoardObject::Line => None,
+        }
+    }
+
+    /// Every texture the object refers to, face down ones included.
+    pub fn textures(&self) -> Vec<Id> {
+        match self {
+            BoardObject::Image(img) => vec![img.texture],
+            BoardObject::Deck(deck) => {
+                let mut textures = deck.cards.clone();
+                textures.push(deck.back);
+                textures
+            }
+            BoardObject::Card(card) => vec![card.face, card.back],
+            BoardObject::Stack(stack) => stack.items.clone(),
+            BoardObject::Hand(_)
+            | BoardObject::Dice(_)
+            | BoardObject::Counter(_)
+            | BoardObject::Fog(_)
+            | BoardObject::Line => Vec::new(),
+        }
+    }
+
+    /// What everyone at the table may know of the object. Faces that are
+    /// turned down are replaced by the back, so only the number of cards in
+    /// a deck and its top card when face up are revealed.
+    pub fn public_view(&self) -> Self {
+        match self {
+            BoardObject::Deck(deck) => {
+                let hidden = deck.cards.len() - usize::from(deck.face_up && !deck.cards.is_empty());
+                let mut cards = vec![deck.back; hidden];
+                cards.extend(&deck.cards[hidden..]);
+                BoardObject::Deck(Deck {
+                    cards,
+                    ..deck.clone()
+                })
+            }
+            BoardObject::Card(card) if !card.face_up => BoardObject::Card(Card {
+                face: card.back,
+                ..card.clone()
+            }),
+            object => object.clone(),
+        }
+    }
+
+    pub fn map_ids<T>(self, f: impl Fn(Id) -> T) -> BoardObject<T> {
+        match self {
+            BoardObject::Image(img) => BoardObject::Image(Image {
+                id: f(img.id),
+                x: img.x,
+                y: img.y,
+                texture: f(img.texture),
+                position_timestamp: img.position_timestamp,
+            }),
+            BoardObject::Line => BoardObject::Line,
+            BoardObject::Deck(deck) => BoardObject::Deck(Deck {
+                id: f(deck.id),
+                x: deck.x,
+                y: deck.y,
+                back: f(deck.back),
+                cards: deck.cards.into_iter().map(&f).collect(),
+                face_up: deck.face_up,
+                position_timestamp: deck.position_timestamp,
+            }),
+            BoardObject::Card(card) => BoardObject::Card(Card {
+                id: f(card.id),
+                x: card.x,
+                y: card.y,
+                face: f(card.face),
+                back: f(card.back),
+                face_up: card.face_up,
+                position_timestamp: card.position_timestamp,
+            }),
+            BoardObject::Hand(hand) => BoardObject::Hand(Hand {
+                id: f(hand.id),
+                x: hand.x,
+                y: hand.y,
+                width: hand.width,
+                height: hand.height,
+                owner: hand.owner,
+                position_timestamp: hand.position_timestamp,
+            }),
+            BoardObject::Dice(dice) => BoardObject::Dice(Dice {
+                id: f(dice.id),
+                x: dice.x,
+                y: dice.y,
+                notation: dice.notation,
+                last_roll: dice.last_roll,
+                position_timestamp: dice.position_timestamp,
+            }),
+            BoardObject::Counter(counter) => BoardObject::Counter(Counter {
+                id: f(counter.id),
+                x: counter.x,
+                y: counter.y,
+                value: counter.value,
+                position_timestamp: counter.position_timestamp,
+            }),
+            BoardObject::Stack(stack) => BoardObject::Stack(Stack {
+                id: f(stack.id),
+                x: stack.x,
+                y: stack.y,
+                items: stack.items.into_iter().map(&f).collect(),
+                position_timestamp: stack.position_timestamp,
+            }),
+            BoardObject::Fog(fog) => BoardObject::Fog(Fog {
+                id: f(fog.id),
+                x: fog.x,
+                y: fog.y,
+                width: fog.width,
+                height: fog.height,
+                position_timestamp: fog.position_timestamp,
+            }),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, Copy)]
@@ -53,6 +516,14 @@ pub enum ObjectIdentifier {
 pub struct Board<Id> {
     pub objects: HashMap<Id, BoardObject<Id>>,
     pub textures: HashMap<Id, Vec<u8>>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: BoardSettings,
+    /// Comment threads by the id of the object they discuss.
+    pub threads: HashMap<u64, Thread>,
+    /// Objects hidden from everyone but the owners.
+    pub hidden: HashSet<u64>,
+    pub seating: Seating,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -0,0 +1,274 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_DICE: u32 = 100;
+pub const MAX_SIDES: u32 = 1000;
+pub const MAX_MODIFIER: u32 = 10000;
+pub const MAX_NOTATION_LENGTH: usize = 32;
+
+/// Which dice count towards the total, see [`Notation::keep`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// Standard dice notation: `NdS`, optionally followed by `khK` or `klK` to
+/// keep only the `K` highest or lowest dice, and by a `+M` or `-M` modifier.
+/// The count defaults to one, `k` alone means `kh` and `d%` is `d100`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Notation {
+    pub count: u32,
+    pub sides: u32,
+    pub keep: Option<Keep>,
+    pub modifier: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotationError {
+    Syntax,
+    TooManyDice,
+    TooManySides,
+    ModifierTooLarge,
+    /// Keeping none, or more dice than are rolled.
+    InvalidKeep,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::Syntax => write!(f, "expected notation such as 3d6+2"),
+            NotationError::TooManyDice => write!(f, "at most {MAX_DICE} dice can be rolled"),
+            NotationError::TooManySides => write!(f, "dice have at most {MAX_SIDES} sides"),
+            NotationError::ModifierTooLarge => {
+                write!(f, "the modifier is at most {MAX_MODIFIER}")
+            }
+            NotationError::InvalidKeep => write!(f, "cannot keep that many dice"),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+/// Splits the leading digits off `s`.
+fn take_number(s: &str) -> Result<(u32, &str), NotationError> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let number = s[..end].parse().map_err(|_| NotationError::Syntax)?;
+    Ok((number, &s[end..]))
+}
+
+fn parse_number(s: &str) -> Result<u32, NotationError> {
+    match take_number(s)? {
+        (number, "") => Ok(number),
+        _ => Err(NotationError::Syntax),
+    }
+}
+
+impl FromStr for Notation {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if s.len() > MAX_NOTATION_LENGTH {
+            return Err(NotationError::Syntax);
+        }
+        let (count, rest) = s.split_once('d').ok_or(NotationError::Syntax)?;
+        let count = if count.is_empty() {
+            1
+        } else {
+            parse_number(count)?
+        };
+        let (sides, rest) = match rest.strip_prefix('%') {
+            Some(rest) => (100, rest),
+            None => take_number(rest)?,
+        };
+        let (keep, rest) = if let Some(rest) = rest.strip_prefix("kl") {
+            let (kept, rest) = take_number(rest)?;
+            (Some(Keep::Lowest(kept)), rest)
+        } else if let Some(rest) = rest.strip_prefix("kh").or_else(|| rest.strip_prefix('k')) {
+            let (kept, rest) = take_number(rest)?;
+            (Some(Keep::Highest(kept)), rest)
+        } else {
+            (None, rest)
+        };
+        let modifier = if rest.is_empty() {
+            0
+        } else if let Some(rest) = rest.strip_prefix('+') {
+            parse_number(rest)?
+        } else if let Some(rest) = rest.strip_prefix('-') {
+            parse_number(rest)?
+        } else {
+            return Err(NotationError::Syntax);
+        };
+
+        if count == 0 || sides == 0 {
+            return Err(NotationError::Syntax);
+        }
+        if count > MAX_DICE {
+            return Err(NotationError::TooManyDice);
+        }
+        if sides > MAX_SIDES {
+            return Err(NotationError::TooManySides);
+        }
+        if modifier > MAX_MODIFIER {
+            return Err(NotationError::ModifierTooLarge);
+        }
+        if let Some(Keep::Highest(kept) | Keep::Lowest(kept)) = keep
+            && (kept == 0 || kept > count)
+        {
+            return Err(NotationError::InvalidKeep);
+        }
+        let modifier = if rest.starts_with('-') {
+            -i64::from(modifier)
+        } else {
+            i64::from(modifier)
+        };
+        Ok(Self {
+            count,
+            sides,
+            keep,
+            modifier,
+        })
+    }
+}
+
+impl Notation {
+    /// Rolls the dice in order, marking those the notation drops.
+    pub fn roll(&self, rng: &mut impl Rng) -> (Vec<Die>, i64) {
+        let mut dice: Vec<_> = (0..self.count)
+            .map(|_| Die {
+                value: rng.random_range(1..=self.sides),
+                kept: true,
+            })
+            .collect();
+        if let Some(keep) = self.keep {
+            let mut order: Vec<_> = (0..dice.len()).collect();
+            let kept = match keep {
+                Keep::Highest(kept) => {
+                    order.sort_by_key(|&i| std::cmp::Reverse(dice[i].value));
+                    kept
+                }
+                Keep::Lowest(kept) => {
+                    order.sort_by_key(|&i| dice[i].value);
+                    kept
+                }
+            };
+            for &i in &order[kept as usize..] {
+                dice[i].kept = false;
+            }
+        }
+        let sum: i64 = dice
+            .iter()
+            .filter(|die| die.kept)
+            .map(|die| i64::from(die.value))
+            .sum();
+        (dice, sum + self.modifier)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Die {
+    pub value: u32,
+    /// False for dice dropped by `kh` or `kl`.
+    pub kept: bool,
+}
+
+/// One roll of a dice object. Rolls are made by the server and journaled,
+/// so who rolled what can be checked afterwards.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiceRoll {
+    pub dice: u64,
+    pub roller: u64,
+    pub roller_name: String,
+    pub notation: String,
+    pub results: Vec<Die>,
+    pub modifier: i64,
+    pub total: i64,
+    /// Milliseconds since the Unix epoch.
+    pub rolled_at: u64,
+}
+
+impl DiceRoll {
+    /// Such as `4d6kh3: 6 5 (1) 3 = 14`, with dropped dice in parentheses.
+    pub fn describe(&self) -> String {
+        let results: Vec<_> = self
+            .results
+            .iter()
+            .map(|die| match die.kept {
+                true => die.value.to_string(),
+                false => format!("({})", die.value),
+            })
+            .collect();
+        let modifier = match self.modifier {
+            0 => String::new(),
+            modifier if modifier > 0 => format!(" + {modifier}"),
+            modifier => format!(" - {}", -modifier),
+        };
+        format!(
+            "{}: {}{} = {}",
+            self.notation,
+            results.join(" "),
+            modifier,
+            self.total
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notation(count: u32, sides: u32, keep: Option<Keep>, modifier: i64) -> Notation {
+        Notation {
+            count,
+            sides,
+            keep,
+            modifier,
+        }
+    }
+
+    #[test]
+    fn valid_notations_parse() {
+        assert_eq!("d%".parse(), Ok(notation(1, 100, None, 0)));
+        assert_eq!(
+            "4d6kh3".parse(),
+            Ok(notation(4, 6, Some(Keep::Highest(3)), 0))
+        );
+        assert_eq!(
+            "2d20kl1".parse(),
+            Ok(notation(2, 20, Some(Keep::Lowest(1)), 0))
+        );
+        assert_eq!("1d6-2".parse(), Ok(notation(1, 6, None, -2)));
+        assert_eq!(
+            " 4D6K3+1 ".parse(),
+            Ok(notation(4, 6, Some(Keep::Highest(3)), 1))
+        );
+    }
+
+    #[test]
+    fn limits_are_enforced() {
+        let parse = |s: &str| s.parse::<Notation>();
+        assert!(parse(&format!("{MAX_DICE}d{MAX_SIDES}+{MAX_MODIFIER}")).is_ok());
+        assert_eq!(parse("101d6"), Err(NotationError::TooManyDice));
+        assert_eq!(parse("1d1001"), Err(NotationError::TooManySides));
+        assert_eq!(parse("1d6+10001"), Err(NotationError::ModifierTooLarge));
+        assert_eq!(parse("1d6-10001"), Err(NotationError::ModifierTooLarge));
+        assert_eq!(parse("2d6kl0"), Err(NotationError::InvalidKeep));
+        let long = format!("1d6+{}", "0".repeat(MAX_NOTATION_LENGTH));
+        assert_eq!(parse(&long), Err(NotationError::Syntax));
+    }
+
+    #[test]
+    fn malformed_notations_are_rejected() {
+        for malformed in ["d6-", "3d", "0d6", "3d0", "d", "3x6", "3d6k", "3d6*2"] {
+            assert_eq!(
+                malformed.parse::<Notation>(),
+                Err(NotationError::Syntax),
+                "{malformed}"
+            );
+        }
+        assert_eq!("3d6k4".parse::<Notation>(), Err(NotationError::InvalidKeep));
+    }
+}
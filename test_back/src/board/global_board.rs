@@ -1,18 +1,45 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::board::common::{
-    Board, BoardAction, BoardEvent, BoardObject, Image, ObjectIdentifier, Texture,
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::board::{
+    clock::Timestamp,
+    comments::{Comment, CommentAction, CommentEvent, MAX_COMMENT_LENGTH, Thread},
+    common::{
+        Board, BoardAction, BoardEvent, BoardObject, Card, Counter, Deck, Dice, Fog, Hand, Image,
+        ObjectIdentifier, Stack, Texture,
+    },
+    dice::{DiceRoll, Notation},
+    roles::{Role, ShareLink},
+    seating::Seating,
+    settings::BoardSettings,
+    sheet::{self, Slices},
 };
 
+/// How long a page that lost its connection can come back to its session.
+pub const SESSION_TIMEOUT_SECS: u64 = 60 * 60;
+
 pub struct GlobalBoard {
     board: Board<u64>,
     rev_textures: HashMap<Vec<u8>, u64>,
     global_id_counter: u64,
+    /// Latest Lamport time of a move, which clients may run ahead of by one
+    /// write at most.
+    latest_time: u64,
     clients: HashMap<u64, Client>,
+    /// Ids assigned by pages that lost their connection, by session, with
+    /// when they left.
+    sessions: HashMap<u64, (u64, HashMap<u64, u64>)>,
+    rng: StdRng,
 }
 
 pub trait EventSender {
     fn send_event(&mut self, client_id: u64, event: BoardEvent) -> impl Future<Output = ()>;
+    fn send_comment_event(
+        &mut self,
+        client_id: u64,
+        event: CommentEvent,
+    ) -> impl Future<Output = ()>;
 }
 
 pub trait BoardObserver {
@@ -23,38 +50,500 @@ pub trait BoardObserver {
         y: f64,
         texture: Texture<u64>,
     ) -> impl Future<Output = ()>;
+    fn move_object(
+        &mut self,
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    ) -> impl Future<Output = ()>;
+    fn set_role(&mut self, user: u64, role: Option<Role>) -> impl Future<Output = ()>;
+    fn share_link_created(&mut self, id: u64, link: ShareLink) -> impl Future<Output = ()>;
+    fn share_link_revoked(&mut self, id: u64) -> impl Future<Output = ()>;
+    fn set_settings(&mut self, settings: BoardSettings) -> impl Future<Output = ()>;
+    fn comment_added(&mut self, object: u64, comment: Comment) -> impl Future<Output = ()>;
+    fn thread_resolved(&mut self, object: u64, resolved: bool) -> impl Future<Output = ()>;
+    fn comment_deleted(&mut self, object: u64, comment: u64) -> impl Future<Output = ()>;
+    /// Complete new state of an object whose contents changed.
+    fn object_set(&mut self, id: u64, object: BoardObject<u64>) -> impl Future<Output = ()>;
+    fn object_removed(&mut self, id: u64) -> impl Future<Output = ()>;
+    fn rolled(&mut self, roll: DiceRoll) -> impl Future<Output = ()>;
+    fn counter_incremented(&mut self, id: u64, delta: i64) -> impl Future<Output = ()>;
+    fn counter_set(&mut self, id: u64, value: i64) -> impl Future<Output = ()>;
+    fn texture_added(&mut self, id: u64, data: Vec<u8>) -> impl Future<Output = ()>;
+    fn hidden_changed(&mut self, id: u64, hidden: bool) -> impl Future<Output = ()>;
+    fn seating_changed(&mut self, seating: Seating) -> impl Future<Output = ()>;
+}
+
+fn max_id(board: &Board<u64>) -> u64 {
+    board
+        .objects
+        .keys()
+        .chain(board.textures.keys())
+        .max()
+        .cloned()
+        .unwrap_or(0)
+}
+
+fn latest_time(board: &Board<u64>) -> u64 {
+    board
+        .objects
+        .values()
+        .filter_map(|object| Some(object.position()?.2.time))
+        .max()
+        .unwrap_or(0)
+}
+
+fn reverse_textures(board: &Board<u64>) -> HashMap<Vec<u8>, u64> {
+    board
+        .textures
+        .iter()
+        .map(|(id, data)| (data.clone(), *id))
+        .collect()
 }
 
 impl GlobalBoard {
-    pub fn from_board(board: Board<u64>) -> Self {
-        let rev_textures = board
-            .textures
-            .iter()
-            .map(|(id, data)| (data.clone(), *id))
-            .collect();
-        let global_id_counter = board
-            .objects
-            .keys()
-            .chain(board.textures.keys())
-            .max()
-            .cloned()
-            .unwrap_or(0);
+    /// `rng_seed` drives shuffles and must not be predictable by clients.
+    pub fn from_board(board: Board<u64>, rng_seed: [u8; 32]) -> Self {
         Self {
+            rev_textures: reverse_textures(&board),
+            global_id_counter: max_id(&board),
+            latest_time: latest_time(&board),
             board,
-            rev_textures,
-            global_id_counter,
             clients: HashMap::new(),
+            sessions: HashMap::new(),
+            rng: StdRng::from_seed(rng_seed),
         }
     }
 
+    /// Replaces the board with an earlier state. Connected clients stay
+    /// registered and ids are never reused, so actions still in flight
+    /// cannot collide with objects created after the restore.
+    /// Roles, share links and seating are not part of the restored content
+    /// and stay as they are.
+    pub fn restore(&mut self, mut board: Board<u64>) {
+        board.roles = std::mem::take(&mut self.board.roles);
+        board.share_links = std::mem::take(&mut self.board.share_links);
+        board.seating = std::mem::take(&mut self.board.seating);
+        self.rev_textures = reverse_textures(&board);
+        self.global_id_counter = self.global_id_counter.max(max_id(&board));
+        self.latest_time = self.latest_time.max(latest_time(&board));
+        self.board = board;
+    }
+
     pub fn get_state(&self) -> Board<u64> {
         self.board.clone()
     }
 
-    pub fn new_client(&mut self, client_id: u64) {
+    /// The state as `client_id` may see it, see [`GlobalBoard::view_for`].
+    /// Only the textures of what it sees are included, and noted as known to
+    /// it.
+    pub fn client_state(&mut self, client_id: u64) -> Board<u64> {
+        let objects: HashMap<_, _> = self
+            .board
+            .objects
+            .iter()
+            .filter_map(|(&id, object)| Some((id, self.view_for(client_id, object)?)))
+            .collect();
+        let known: HashSet<_> = objects
+            .values()
+            .flat_map(|object| object.textures())
+            .collect();
+        let board = Board {
+            textures: known
+                .iter()
+                .filter_map(|id| Some((*id, self.board.textures.get(id)?.clone())))
+                .collect(),
+            threads: self
+                .board
+                .threads
+                .iter()
+                .filter(|(id, _)| objects.contains_key(id))
+                .map(|(&id, thread)| (id, thread.clone()))
+                .collect(),
+            hidden: match self.is_game_master(client_id) {
+                true => self.board.hidden.clone(),
+                false => HashSet::new(),
+            },
+            objects,
+            roles: self.board.roles.clone(),
+            share_links: self.board.share_links.clone(),
+            settings: self.board.settings,
+            seating: self.board.seating.clone(),
+        };
+        if let Some(client) = self.clients.get_mut(&client_id) {
+            client.known_textures = known;
+        }
+        board
+    }
+
+    fn hand_owner_at(&self, x: f64, y: f64) -> Option<u64> {
+        self.board.objects.values().find_map(|object| match object {
+            BoardObject::Hand(hand) if hand.contains(x, y) => Some(hand.owner),
+            _ => None,
+        })
+    }
+
+    /// Owners run the game and see everything, fog and hidden objects
+    /// included.
+    fn is_game_master(&self, client_id: u64) -> bool {
+        self.role(client_id).is_owner()
+    }
+
+    fn can_see(&self, client_id: u64, id: u64) -> bool {
+        if self.is_game_master(client_id) {
+            return true;
+        }
+        let Some(object) = self.board.objects.get(&id) else {
+            return false;
+        };
+        if self.board.hidden.contains(&id) {
+            return false;
+        }
+        if matches!(object, BoardObject::Fog(_)) {
+            return true;
+        }
+        let Some((x, y, _)) = object.position() else {
+            return true;
+        };
+        !self.board.objects.values().any(|object| match object {
+            BoardObject::Fog(fog) => fog.contains(x, y),
+            _ => false,
+        })
+    }
+
+    /// The object as `client_id` may see it, if at all. Cards in a hand are
+    /// shown face up to its owner and face down to everyone else, other
+    /// objects as in [`BoardObject::public_view`].
+    fn view_for(&self, client_id: u64, object: &BoardObject<u64>) -> Option<BoardObject<u64>> {
+        if !self.can_see(client_id, object.id()?) {
+            return None;
+        }
+        if let BoardObject::Card(card) = object
+            && let Some(owner) = self.hand_owner_at(card.x, card.y)
+        {
+            let visible = owner == client_id;
+            return Some(BoardObject::Card(Card {
+                face: if visible { card.face } else { card.back },
+                face_up: visible,
+                ..card.clone()
+            }));
+        }
+        Some(object.public_view())
+    }
+
+    /// Registers a connection at `now`, in seconds. One it replaces is
+    /// forgotten as by [`GlobalBoard::remove_client`], so its session can
+    /// still be resumed.
+    pub fn new_client(&mut self, client_id: u64, now: u64) {
+        self.remove_client(client_id, now);
         self.clients.insert(client_id, Client::new(client_id));
     }
 
+    /// Forgets a connection but keeps the ids it assigned, so they can be
+    /// picked up again by [`GlobalBoard::resume`] after a reconnect within
+    /// [`SESSION_TIMEOUT_SECS`] of `now`.
+    pub fn remove_client(&mut self, client_id: u64, now: u64) {
+        let Some(client) = self.clients.remove(&client_id) else {
+            return;
+        };
+        if let Some(session) = client.session {
+            self.sessions.insert(session, (now, client.ids_map));
+        }
+    }
+
+    /// Drops the ids of sessions that left more than
+    /// [`SESSION_TIMEOUT_SECS`] before `now`.
+    pub fn prune_sessions(&mut self, now: u64) {
+        self.sessions
+            .retain(|_, (left_at, _)| now.saturating_sub(*left_at) <= SESSION_TIMEOUT_SECS);
+    }
+
+    /// Binds a connection to the board session of its page. Actions replayed
+    /// after a reconnect then resolve the same local ids as before.
+    pub fn resume(&mut self, client_id: u64, session: u64) {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        client.session = Some(session);
+        if let Some((_, ids_map)) = self.sessions.remove(&session) {
+            client.ids_map.extend(ids_map);
+        }
+    }
+
+    pub fn role(&self, user: u64) -> Role {
+        self.board.roles.get(&user).copied().unwrap_or_default()
+    }
+
+    /// Grants `role` to `user`, or takes their role away with `None`.
+    pub async fn set_role(
+        &mut self,
+        user: u64,
+        role: Option<Role>,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        match role {
+            Some(role) => self.board.roles.insert(user, role),
+            None => self.board.roles.remove(&user),
+        };
+        observer.set_role(user, role).await;
+        for client in self.clients.values() {
+            event_sender
+                .send_event(client.id, BoardEvent::RoleChanged { user, role })
+                .await;
+        }
+    }
+
+    /// Makes `user` the owner of a room that has none, such as a new one.
+    pub async fn claim_ownership(
+        &mut self,
+        user: u64,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        if self.board.roles.values().any(|role| role.is_owner()) {
+            return;
+        }
+        self.set_role(user, Some(Role::Owner), event_sender, observer)
+            .await;
+    }
+
+    pub fn seating(&self) -> &Seating {
+        &self.board.seating
+    }
+
+    /// Replaces the seating. The room decides who may change it.
+    pub async fn set_seating(
+        &mut self,
+        seating: Seating,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        if seating == self.board.seating {
+            return;
+        }
+        self.board.seating = seating.clone();
+        observer.seating_changed(seating.clone()).await;
+        for client in self.clients.values() {
+            event_sender
+                .send_event(client.id, BoardEvent::SeatingChanged(seating.clone()))
+                .await;
+        }
+    }
+
+    pub fn share_link(&self, id: u64) -> Option<ShareLink> {
+        self.board.share_links.get(&id).copied()
+    }
+
+    pub async fn create_share_link(
+        &mut self,
+        id: u64,
+        link: ShareLink,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        self.board.share_links.insert(id, link);
+        observer.share_link_created(id, link).await;
+        for client in self.clients.values() {
+            event_sender
+                .send_event(client.id, BoardEvent::ShareLinkCreated { id, link })
+                .await;
+        }
+    }
+
+    pub async fn revoke_share_link(
+        &mut self,
+        id: u64,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        if self.board.share_links.remove(&id).is_none() {
+            return;
+        }
+        observer.share_link_revoked(id).await;
+        for client in self.clients.values() {
+            event_sender
+                .send_event(client.id, BoardEvent::ShareLinkRevoked { id })
+                .await;
+        }
+    }
+
+    pub fn thread(&self, object: u64) -> Option<&Thread> {
+        self.board.threads.get(&object)
+    }
+
+    /// Applies a comment action of `author`, whose permission has already
+    /// been checked. `now` is the time new comments are stamped with.
+    pub async fn apply_comment(
+        &mut self,
+        author: u64,
+        author_name: &str,
+        now: u64,
+        action: CommentAction,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        let event = match action {
+            CommentAction::Add { object, text } => {
+                let text = text.trim();
+                let visible = self
+                    .board
+                    .objects
+                    .get(&object)
+                    .and_then(|target| self.view_for(author, target))
+                    .is_some();
+                if text.is_empty() || text.chars().count() > MAX_COMMENT_LENGTH || !visible {
+                    return;
+                }
+                let comment = Comment {
+                    id: self.next_global_id(),
+                    author,
+                    author_name: author_name.to_owned(),
+                    text: text.to_owned(),
+                    created_at: now,
+                };
+                self.board
+                    .threads
+                    .entry(object)
+                    .or_default()
+                    .comments
+                    .push(comment.clone());
+                observer.comment_added(object, comment.clone()).await;
+                CommentEvent::Added { object, comment }
+            }
+            CommentAction::SetResolved { object, resolved } => {
+                let Some(thread) = self.board.threads.get_mut(&object) else {
+                    return;
+                };
+                if thread.resolved == resolved {
+                    return;
+                }
+                thread.resolved = resolved;
+                observer.thread_resolved(object, resolved).await;
+                CommentEvent::ResolvedChanged { object, resolved }
+            }
+            CommentAction::Delete { object, comment } => {
+                let Some(thread) = self.board.threads.get_mut(&object) else {
+                    return;
+                };
+                let Some(index) = thread.comments.iter().position(|c| c.id == comment) else {
+                    return;
+                };
+                thread.comments.remove(index);
+                if thread.comments.is_empty() {
+                    self.board.threads.remove(&object);
+                }
+                observer.comment_deleted(object, comment).await;
+                CommentEvent::Deleted { object, comment }
+            }
+        };
+        for client in self.clients.values() {
+            if self.can_see(client.id, event.object()) {
+                event_sender
+                    .send_comment_event(client.id, event.clone())
+                    .await;
+            }
+        }
+    }
+
+    /// Rolls a dice object for `roller`, whose permission has already been
+    /// checked. The outcome is journaled with the roller's name and shown to
+    /// everyone who can see the dice.
+    pub async fn roll(
+        &mut self,
+        roller: u64,
+        roller_name: &str,
+        now: u64,
+        dice: u64,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        if !self.can_see(roller, dice) {
+            return;
+        }
+        let Some(BoardObject::Dice(object)) = self.board.objects.get_mut(&dice) else {
+            return;
+        };
+        let Ok(notation) = object.notation.parse::<Notation>() else {
+            return;
+        };
+        let (results, total) = notation.roll(&mut self.rng);
+        let roll = DiceRoll {
+            dice,
+            roller,
+            roller_name: roller_name.to_owned(),
+            notation: object.notation.clone(),
+            results,
+            modifier: notation.modifier,
+            total,
+            rolled_at: now,
+        };
+        object.last_roll = Some(roll.clone());
+        observer.rolled(roll.clone()).await;
+        self.send_to_viewers(dice, BoardEvent::Rolled(roll), event_sender)
+            .await;
+    }
+
+    /// Answers an action that is not allowed, so the client rolls back its
+    /// optimistic change.
+    pub async fn reject(
+        &mut self,
+        client_id: u64,
+        board_action: BoardAction,
+        event_sender: &mut impl EventSender,
+    ) {
+        let event = match board_action {
+            BoardAction::NewImage { local_id, .. } => BoardEvent::RejectImage { local_id },
+            BoardAction::Move { id, .. } => {
+                let Some(global_id) = self
+                    .clients
+                    .get(&client_id)
+                    .and_then(|client| client.get_global_id(id))
+                else {
+                    return;
+                };
+                let Some((x, y, timestamp)) = self
+                    .board
+                    .objects
+                    .get(&global_id)
+                    .and_then(|object| object.position())
+                else {
+                    return;
+                };
+                BoardEvent::Move {
+                    id: global_id,
+                    x,
+                    y,
+                    timestamp,
+                }
+            }
+            BoardAction::SetSettings(_) => BoardEvent::SettingsChanged(self.board.settings),
+            // Deck actions wait for the server, there is nothing to roll back.
+            BoardAction::MakeDeck { .. }
+            | BoardAction::AddToDeck { .. }
+            | BoardAction::Draw { .. }
+            | BoardAction::Shuffle { .. }
+            | BoardAction::Flip { .. }
+            | BoardAction::Deal { .. }
+            | BoardAction::NewHand { .. }
+            | BoardAction::RemoveHand { .. }
+            | BoardAction::NewDice { .. }
+            | BoardAction::NewCounter { .. }
+            | BoardAction::Increment { .. }
+            | BoardAction::SetCounter { .. }
+            | BoardAction::StackOnto { .. }
+            | BoardAction::TakeFromStack { .. }
+            | BoardAction::SplitStack { .. }
+            | BoardAction::SpreadStack { .. }
+            | BoardAction::SliceSheet { .. }
+            | BoardAction::NewFog { .. }
+            | BoardAction::RemoveFog { .. }
+            | BoardAction::SetHidden { .. } => return,
+        };
+        event_sender.send_event(client_id, event).await;
+    }
+
     fn get_global_texture(
         &mut self,
         client_id: u64,
@@ -73,11 +562,17 @@ impl GlobalBoard {
                     data: data.to_owned(),
                 })
             }
-            Texture::Existing { id } => self.clients.get(&client_id).and_then(|client| {
+            // Texture ids are easy to guess, and one the client has not been
+            // sent may be the face of a card in someone's hand or of a hidden
+            // object.
+            Texture::Existing { id } => {
+                let client = self.clients.get(&client_id)?;
                 client
                     .get_global_id(*id)
+                    .filter(|global_id| client.known_textures.contains(global_id))
+                    .filter(|global_id| self.board.textures.contains_key(global_id))
                     .map(|global_id| Texture::Existing { id: global_id })
-            }),
+            }
         }
     }
 
@@ -86,6 +581,311 @@ impl GlobalBoard {
         self.global_id_counter
     }
 
+    fn get_global_id(&self, client_id: u64, id: ObjectIdentifier) -> Option<u64> {
+        self.clients
+            .get(&client_id)
+            .and_then(|client| client.get_global_id(id))
+    }
+
+    /// Sends the textures of `object` that `client_id` does not have yet.
+    async fn send_textures(
+        &mut self,
+        client_id: u64,
+        object: &BoardObject<u64>,
+        event_sender: &mut impl EventSender,
+    ) {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        for id in object.textures() {
+            if !client.known_textures.insert(id) {
+                continue;
+            }
+            let Some(data) = self.board.textures.get(&id) else {
+                continue;
+            };
+            let event = BoardEvent::NewTexture {
+                id,
+                data: data.clone(),
+            };
+            event_sender.send_event(client_id, event).await;
+        }
+    }
+
+    /// Shows `client_id` its own view of an object, or takes the object
+    /// away if it may not see it.
+    async fn send_view(&mut self, client_id: u64, id: u64, event_sender: &mut impl EventSender) {
+        let view = self
+            .board
+            .objects
+            .get(&id)
+            .and_then(|object| self.view_for(client_id, object));
+        let event = match view {
+            Some(view) => {
+                self.send_textures(client_id, &view, event_sender).await;
+                BoardEvent::ObjectUpdated(view)
+            }
+            None => BoardEvent::ObjectRemoved { id },
+        };
+        event_sender.send_event(client_id, event).await;
+    }
+
+    /// Shows every client its own view of the given objects.
+    async fn send_views(&mut self, ids: &[u64], event_sender: &mut impl EventSender) {
+        let clients: Vec<_> = self.clients.keys().copied().collect();
+        for &id in ids {
+            for &client_id in &clients {
+                self.send_view(client_id, id, event_sender).await;
+            }
+        }
+    }
+
+    /// Sends an event about an object to those who can see it.
+    async fn send_to_viewers(
+        &self,
+        id: u64,
+        event: BoardEvent,
+        event_sender: &mut impl EventSender,
+    ) {
+        for client in self.clients.values() {
+            if self.can_see(client.id, id) {
+                event_sender.send_event(client.id, event.clone()).await;
+            }
+        }
+    }
+
+    fn object_ids(&self) -> Vec<u64> {
+        self.board.objects.keys().copied().collect()
+    }
+
+    fn card_ids(&self) -> Vec<u64> {
+        self.board
+            .objects
+            .iter()
+            .filter(|(_, object)| matches!(object, BoardObject::Card(_)))
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Persists the new state of an object and shows it to everyone.
+    async fn update_object(
+        &mut self,
+        id: u64,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        let Some(object) = self.board.objects.get(&id) else {
+            return;
+        };
+        observer.object_set(id, object.clone()).await;
+        self.send_views(&[id], event_sender).await;
+    }
+
+    async fn remove_object(
+        &mut self,
+        id: u64,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        if self.board.objects.remove(&id).is_none() {
+            return;
+        }
+        self.board.threads.remove(&id);
+        self.board.hidden.remove(&id);
+        observer.object_removed(id).await;
+        for client in self.clients.values() {
+            event_sender
+                .send_event(client.id, BoardEvent::ObjectRemoved { id })
+                .await;
+        }
+    }
+
+    /// Id of a texture with the given contents, made if there is none yet.
+    /// Clients get it along with the first object they see showing it.
+    async fn add_texture(&mut self, data: Vec<u8>, observer: &mut impl BoardObserver) -> u64 {
+        if let Some(&id) = self.rev_textures.get(&data) {
+            return id;
+        }
+        let id = self.next_global_id();
+        self.board.textures.insert(id, data.clone());
+        self.rev_textures.insert(data.clone(), id);
+        observer.texture_added(id, data).await;
+        id
+    }
+
+    /// Cuts an image into pieces, see [`BoardAction::SliceSheet`].
+    async fn slice_sheet(
+        &mut self,
+        id: u64,
+        slices: &Slices,
+        into_deck: bool,
+        event_sender: &mut impl EventSender,
+        observer: &mut impl BoardObserver,
+    ) {
+        let Some(BoardObject::Image(sheet)) = self.board.objects.get(&id).cloned() else {
+            return;
+        };
+        let Some(pieces) = self
+            .board
+            .textures
+            .get(&sheet.texture)
+            .and_then(|data| sheet::slice(data, slices))
+        else {
+            return;
+        };
+        if into_deck && pieces.len() < 2 {
+            return;
+        }
+        let mut placed = Vec::new();
+        for (rect, data) in pieces {
+            let texture = self.add_texture(data, observer).await;
+            placed.push((rect, texture));
+        }
+        if into_deck {
+            let (_, back) = placed.pop().unwrap();
+            // The first piece ends up on top.
+            let cards = placed
+                .into_iter()
+                .rev()
+                .map(|(_, texture)| texture)
+                .collect();
+            let deck = Deck {
+                id,
+                x: sheet.x,
+                y: sheet.y,
+                back,
+                cards,
+                face_up: false,
+                position_timestamp: sheet.position_timestamp,
+            };
+            self.board.objects.insert(id, BoardObject::Deck(deck));
+            self.update_object(id, event_sender, observer).await;
+            return;
+        }
+        self.remove_object(id, event_sender, observer).await;
+        for (rect, texture) in placed {
+            let piece = self.next_global_id();
+            let image = Image {
+                id: piece,
+                x: sheet.x + rect.x as f64,
+                y: sheet.y + rect.y as f64,
+                texture,
+                position_timestamp: Timestamp::default(),
+            };
+            self.board.objects.insert(piece, BoardObject::Image(image));
+            self.update_object(piece, event_sender, observer).await;
+        }
+    }
+
+    /// Lays images at the given position, as a stack if there are several.
+    fn new_pile(&mut self, mut items: Vec<u64>, x: f64, y: f64) -> u64 {
+        let id = self.next_global_id();
+        let object = if items.len() == 1 {
+            BoardObject::Image(Image {
+                id,
+                x,
+                y,
+                texture: items.remove(0),
+                position_timestamp: Timestamp::default(),
+            })
+        } else {
+            BoardObject::Stack(Stack {
+                id,
+                x,
+                y,
+                items,
+                position_timestamp: Timestamp::default(),
+            })
+        };
+        self.board.objects.insert(id, object);
+        id
+    }
+
+    /// Takes the top `count` images of a stack, which must keep at least one.
+    /// A stack left with a single image becomes that image.
+    fn take_from_stack(&mut self, id: u64, count: u32) -> Option<Vec<u64>> {
+        let Some(BoardObject::Stack(stack)) = self.board.objects.get_mut(&id) else {
+            return None;
+        };
+        let count = count as usize;
+        if count == 0 || count >= stack.items.len() {
+            return None;
+        }
+        let taken = stack.items.split_off(stack.items.len() - count);
+        if let [texture] = stack.items[..] {
+            let image = Image {
+                id,
+                x: stack.x,
+                y: stack.y,
+                texture,
+                position_timestamp: stack.position_timestamp,
+            };
+            self.board.objects.insert(id, BoardObject::Image(image));
+        }
+        Some(taken)
+    }
+
+    /// Takes the top card of a deck as a new card object.
+    fn draw_card(&mut self, deck: u64, x: f64, y: f64, face_up: Option<bool>) -> Option<u64> {
+        let Some(BoardObject::Deck(deck)) = self.board.objects.get_mut(&deck) else {
+            return None;
+        };
+        let face = deck.cards.pop()?;
+        let back = deck.back;
+        let face_up = face_up.unwrap_or(deck.face_up);
+        let id = self.next_global_id();
+        self.board.objects.insert(
+            id,
+            BoardObject::Card(Card {
+                id,
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp: Timestamp::default(),
+            }),
+        );
+        Some(id)
+    }
+
+    /// The existing objects an action of `client_id` acts on.
+    fn targets(&self, client_id: u64, board_action: &BoardAction) -> Vec<u64> {
+        let ids = match *board_action {
+            BoardAction::Move { id, .. }
+            | BoardAction::MakeDeck { image: id }
+            | BoardAction::SliceSheet { image: id, .. } => vec![self.get_global_id(client_id, id)],
+            BoardAction::AddToDeck { deck, object } => {
+                vec![Some(deck), self.get_global_id(client_id, object)]
+            }
+            BoardAction::StackOnto { target, object } => {
+                vec![Some(target), self.get_global_id(client_id, object)]
+            }
+            BoardAction::Draw { deck: id, .. }
+            | BoardAction::Shuffle { deck: id }
+            | BoardAction::Flip { id }
+            | BoardAction::Deal { deck: id, .. }
+            | BoardAction::RemoveHand { id }
+            | BoardAction::Increment { id, .. }
+            | BoardAction::SetCounter { id, .. }
+            | BoardAction::TakeFromStack { stack: id, .. }
+            | BoardAction::SplitStack { stack: id, .. }
+            | BoardAction::SpreadStack { stack: id, .. }
+            | BoardAction::RemoveFog { id }
+            | BoardAction::SetHidden { id, .. } => vec![Some(id)],
+            BoardAction::NewImage { .. }
+            | BoardAction::SetSettings(_)
+            | BoardAction::NewHand { .. }
+            | BoardAction::NewDice { .. }
+            | BoardAction::NewCounter { .. }
+            | BoardAction::NewFog { .. } => Vec::new(),
+        };
+        ids.into_iter()
+            .flatten()
+            .filter(|id| self.board.objects.contains_key(id))
+            .collect()
+    }
+
     pub async fn apply(
         &mut self,
         client_id: u64,
@@ -93,6 +893,19 @@ impl GlobalBoard {
         event_sender: &mut impl EventSender,
         observer: &mut impl BoardObserver,
     ) {
+        // Clients may only act on what they can see. One that does not know
+        // an object is hidden from it yet learns now.
+        let hidden: Vec<_> = self
+            .targets(client_id, &board_action)
+            .into_iter()
+            .filter(|&id| !self.can_see(client_id, id))
+            .collect();
+        if !hidden.is_empty() {
+            for id in hidden {
+                self.send_view(client_id, id, event_sender).await;
+            }
+            return;
+        }
         match board_action {
             BoardAction::NewImage {
                 x,
@@ -100,17 +913,49 @@ impl GlobalBoard {
                 local_id,
                 texture,
             } => {
-                let global_id = self.next_global_id();
+                let Some(client) = self.clients.get(&client_id) else {
+                    return;
+                };
+                if let Some(global_id) = client.ids_map.get(&local_id).cloned() {
+                    // A replay of an action that was applied before the
+                    // client lost its connection.
+                    let Some(BoardObject::Image(img)) = self.board.objects.get(&global_id) else {
+                        return;
+                    };
+                    event_sender
+                        .send_event(
+                            client_id,
+                            BoardEvent::ConfirmImage {
+                                local_id,
+                                global_id,
+                                texture_id: img.texture,
+                            },
+                        )
+                        .await;
+                    return;
+                }
                 let Some(texture_global) = self.get_global_texture(client_id, &texture) else {
+                    event_sender
+                        .send_event(client_id, BoardEvent::RejectImage { local_id })
+                        .await;
+                    return;
+                };
+                let texture_id = texture_global.get_id();
+                let Some(texture_data) = self.board.textures.get(&texture_id).cloned() else {
+                    event_sender
+                        .send_event(client_id, BoardEvent::RejectImage { local_id })
+                        .await;
                     return;
                 };
+                let global_id = self.next_global_id();
                 self.board.objects.insert(
                     global_id,
                     BoardObject::Image(Image {
                         id: global_id,
                         x,
                         y,
-                        texture: texture_global.get_id(),
+                        texture: texture_id,
+                        position_timestamp: Timestamp::default(),
                     }),
                 );
 
@@ -118,51 +963,505 @@ impl GlobalBoard {
                     .new_image(global_id, x, y, texture_global.clone())
                     .await;
 
-                for client in self.clients.values_mut() {
-                    if client.id == client_id {
+                let clients: Vec<_> = self.clients.keys().copied().collect();
+                for other in clients {
+                    let visible = self.can_see(other, global_id);
+                    let Some(client) = self.clients.get_mut(&other) else {
+                        continue;
+                    };
+                    if other == client_id {
                         client.set_global_id(local_id, global_id);
                         if let ObjectIdentifier::Local(local_texture_id) = texture.get_id() {
-                            client.set_global_id(local_texture_id, texture_global.get_id());
+                            client.set_global_id(local_texture_id, texture_id);
                         }
+                        client.known_textures.insert(texture_id);
                         event_sender
                             .send_event(
                                 client_id,
                                 BoardEvent::ConfirmImage {
                                     local_id,
                                     global_id,
-                                    texture_id: texture_global.get_id(),
+                                    texture_id,
                                 },
                             )
                             .await;
-                    } else {
+                    } else if visible {
+                        let texture = match client.known_textures.insert(texture_id) {
+                            true => Texture::New {
+                                id: texture_id,
+                                data: texture_data.clone(),
+                            },
+                            false => Texture::Existing { id: texture_id },
+                        };
                         event_sender
                             .send_event(
-                                client.id,
+                                other,
                                 BoardEvent::NewImage {
                                     id: global_id,
                                     x,
                                     y,
-                                    texture: texture_global.clone(),
+                                    texture,
                                 },
                             )
                             .await;
                     }
                 }
             }
+            BoardAction::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => {
+                let Some(global_id) = self
+                    .clients
+                    .get(&client_id)
+                    .and_then(|client| client.get_global_id(id))
+                else {
+                    return;
+                };
+                // A forged timestamp far ahead would pin the object out of
+                // everyone else's reach, so it is pulled back to the next
+                // write.
+                let stamped = Timestamp {
+                    time: timestamp.time.min(self.latest_time.saturating_add(1)),
+                    ..timestamp
+                };
+                self.latest_time = self.latest_time.max(stamped.time);
+                let Some(object) = self.board.objects.get(&global_id) else {
+                    return;
+                };
+                // Moving fog uncovers what it hid, which is up to game masters.
+                if matches!(object, BoardObject::Fog(_)) && !self.is_game_master(client_id) {
+                    self.reject(client_id, board_action, event_sender).await;
+                    return;
+                }
+                let Some((old_x, old_y, old_timestamp)) = object.position() else {
+                    return;
+                };
+                if stamped <= old_timestamp {
+                    // A newer write has already won, so the writer has to roll
+                    // back its optimistic move.
+                    let event = BoardEvent::Move {
+                        id: global_id,
+                        x: old_x,
+                        y: old_y,
+                        timestamp: old_timestamp,
+                    };
+                    event_sender.send_event(client_id, event).await;
+                    return;
+                }
+                let seen_before: Vec<_> = self
+                    .clients
+                    .keys()
+                    .filter(|&&other| other != client_id)
+                    .map(|&other| (other, self.can_see(other, global_id)))
+                    .collect();
+                let Some(object) = self.board.objects.get_mut(&global_id) else {
+                    return;
+                };
+                object.set_position(x, y, stamped);
+                let kind = object.clone();
+
+                observer.move_object(global_id, x, y, stamped).await;
+                if stamped != timestamp {
+                    let event = BoardEvent::Move {
+                        id: global_id,
+                        x,
+                        y,
+                        timestamp: stamped,
+                    };
+                    event_sender.send_event(client_id, event).await;
+                }
+
+                match kind {
+                    // The card may have entered or left a hand, which changes
+                    // what everyone sees of it, the mover included.
+                    BoardObject::Card(_) => {
+                        self.send_views(&[global_id], event_sender).await;
+                        return;
+                    }
+                    // Cards stay where they are, so the hand may now cover
+                    // others.
+                    BoardObject::Hand(_) => {
+                        self.send_views(&self.card_ids(), event_sender).await;
+                    }
+                    // Whatever the fog uncovers appears and whatever it now
+                    // covers disappears.
+                    BoardObject::Fog(_) => {
+                        self.send_views(&self.object_ids(), event_sender).await;
+                        return;
+                    }
+                    _ => {}
+                }
+
+                // The object may have entered or left fog.
+                for (other, seen) in seen_before {
+                    match (seen, self.can_see(other, global_id)) {
+                        (true, true) => {
+                            let event = BoardEvent::Move {
+                                id: global_id,
+                                x,
+                                y,
+                                timestamp: stamped,
+                            };
+                            event_sender.send_event(other, event).await;
+                        }
+                        (false, true) | (true, false) => {
+                            self.send_view(other, global_id, event_sender).await;
+                        }
+                        (false, false) => {}
+                    }
+                }
+            }
+            BoardAction::SetSettings(settings) => {
+                if !settings.is_valid() {
+                    self.reject(client_id, board_action, event_sender).await;
+                    return;
+                }
+                self.board.settings = settings;
+                observer.set_settings(settings).await;
+                for client in self.clients.values() {
+                    event_sender
+                        .send_event(client.id, BoardEvent::SettingsChanged(settings))
+                        .await;
+                }
+            }
+            BoardAction::MakeDeck { image } => {
+                let Some(id) = self.get_global_id(client_id, image) else {
+                    return;
+                };
+                let Some(BoardObject::Image(img)) = self.board.objects.get(&id) else {
+                    return;
+                };
+                let deck = Deck {
+                    id,
+                    x: img.x,
+                    y: img.y,
+                    back: img.texture,
+                    cards: Vec::new(),
+                    face_up: false,
+                    position_timestamp: img.position_timestamp,
+                };
+                self.board.objects.insert(id, BoardObject::Deck(deck));
+                self.update_object(id, event_sender, observer).await;
+            }
+            BoardAction::AddToDeck { deck, object } => {
+                let Some(object) = self.get_global_id(client_id, object) else {
+                    return;
+                };
+                if !matches!(self.board.objects.get(&deck), Some(BoardObject::Deck(_))) {
+                    return;
+                }
+                let face = match self.board.objects.get(&object) {
+                    Some(BoardObject::Image(img)) => img.texture,
+                    Some(BoardObject::Card(card)) => card.face,
+                    _ => return,
+                };
+                self.remove_object(object, event_sender, observer).await;
+                if let Some(BoardObject::Deck(deck)) = self.board.objects.get_mut(&deck) {
+                    deck.cards.push(face);
+                }
+                self.update_object(deck, event_sender, observer).await;
+            }
+            BoardAction::Draw { deck, x, y } => {
+                let Some(card) = self.draw_card(deck, x, y, None) else {
+                    return;
+                };
+                self.update_object(deck, event_sender, observer).await;
+                self.update_object(card, event_sender, observer).await;
+            }
+            BoardAction::Shuffle { deck: id } => {
+                let Some(BoardObject::Deck(deck)) = self.board.objects.get_mut(&id) else {
+                    return;
+                };
+                deck.cards.shuffle(&mut self.rng);
+                self.update_object(id, event_sender, observer).await;
+            }
+            BoardAction::Flip { id } => {
+                match self.board.objects.get_mut(&id) {
+                    Some(BoardObject::Deck(deck)) => {
+                        deck.cards.reverse();
+                        deck.face_up = !deck.face_up;
+                    }
+                    Some(BoardObject::Card(card)) => card.face_up = !card.face_up,
+                    _ => return,
+                }
+                self.update_object(id, event_sender, observer).await;
+            }
+            BoardAction::Deal {
+                deck,
+                count,
+                positions,
+            } => {
+                let mut dealt = Vec::new();
+                'deal: for _ in 0..count {
+                    for &(x, y) in &positions {
+                        let Some(card) = self.draw_card(deck, x, y, Some(false)) else {
+                            break 'deal;
+                        };
+                        dealt.push(card);
+                    }
+                }
+                if dealt.is_empty() {
+                    return;
+                }
+                self.update_object(deck, event_sender, observer).await;
+                for card in dealt {
+                    self.update_object(card, event_sender, observer).await;
+                }
+            }
+            BoardAction::NewHand {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+                    return;
+                }
+                let id = self.next_global_id();
+                let hand = Hand {
+                    id,
+                    x,
+                    y,
+                    width,
+                    height,
+                    owner: client_id,
+                    position_timestamp: Timestamp::default(),
+                };
+                self.board.objects.insert(id, BoardObject::Hand(hand));
+                self.update_object(id, event_sender, observer).await;
+                self.send_views(&self.card_ids(), event_sender).await;
+            }
+            BoardAction::RemoveHand { id } => {
+                let Some(BoardObject::Hand(hand)) = self.board.objects.get(&id) else {
+                    return;
+                };
+                if hand.owner != client_id && !self.role(client_id).is_owner() {
+                    return;
+                }
+                self.remove_object(id, event_sender, observer).await;
+                self.send_views(&self.card_ids(), event_sender).await;
+            }
+            BoardAction::NewDice { x, y, notation } => {
+                let notation = notation.trim();
+                if notation.parse::<Notation>().is_err() {
+                    return;
+                }
+                let id = self.next_global_id();
+                let dice = Dice {
+                    id,
+                    x,
+                    y,
+                    notation: notation.to_owned(),
+                    last_roll: None,
+                    position_timestamp: Timestamp::default(),
+                };
+                self.board.objects.insert(id, BoardObject::Dice(dice));
+                self.update_object(id, event_sender, observer).await;
+            }
+            BoardAction::NewCounter { x, y, value } => {
+                let id = self.next_global_id();
+                let counter = Counter {
+                    id,
+                    x,
+                    y,
+                    value,
+                    position_timestamp: Timestamp::default(),
+                };
+                self.board.objects.insert(id, BoardObject::Counter(counter));
+                self.update_object(id, event_sender, observer).await;
+            }
+            BoardAction::Increment { id, delta } => {
+                let Some(BoardObject::Counter(counter)) = self.board.objects.get_mut(&id) else {
+                    return;
+                };
+                counter.value = counter.value.saturating_add(delta);
+                let value = counter.value;
+                observer.counter_incremented(id, delta).await;
+                self.send_to_viewers(id, BoardEvent::CounterChanged { id, value }, event_sender)
+                    .await;
+            }
+            BoardAction::SetCounter { id, value } => {
+                let Some(BoardObject::Counter(counter)) = self.board.objects.get_mut(&id) else {
+                    return;
+                };
+                counter.value = value;
+                observer.counter_set(id, value).await;
+                self.send_to_viewers(id, BoardEvent::CounterChanged { id, value }, event_sender)
+                    .await;
+            }
+            BoardAction::StackOnto { target, object } => {
+                let Some(object) = self.get_global_id(client_id, object) else {
+                    return;
+                };
+                if object == target {
+                    return;
+                }
+                let items = match self.board.objects.get(&object) {
+                    Some(BoardObject::Image(img)) => vec![img.texture],
+                    Some(BoardObject::Stack(stack)) => stack.items.clone(),
+                    _ => return,
+                };
+                let mut stack = match self.board.objects.get(&target) {
+                    Some(BoardObject::Image(img)) => Stack {
+                        id: target,
+                        x: img.x,
+                        y: img.y,
+                        items: vec![img.texture],
+                        position_timestamp: img.position_timestamp,
+                    },
+                    Some(BoardObject::Stack(stack)) => stack.clone(),
+                    _ => return,
+                };
+                self.remove_object(object, event_sender, observer).await;
+                stack.items.extend(items);
+                self.board.objects.insert(target, BoardObject::Stack(stack));
+                self.update_object(target, event_sender, observer).await;
+            }
+            BoardAction::TakeFromStack { stack, x, y } => {
+                let Some(taken) = self.take_from_stack(stack, 1) else {
+                    return;
+                };
+                let image = self.new_pile(taken, x, y);
+                self.update_object(stack, event_sender, observer).await;
+                self.update_object(image, event_sender, observer).await;
+            }
+            BoardAction::SplitStack { stack, count, x, y } => {
+                let Some(taken) = self.take_from_stack(stack, count) else {
+                    return;
+                };
+                let pile = self.new_pile(taken, x, y);
+                self.update_object(stack, event_sender, observer).await;
+                self.update_object(pile, event_sender, observer).await;
+            }
+            BoardAction::SpreadStack { stack: id, dx, dy } => {
+                if !(dx.is_finite() && dy.is_finite()) {
+                    return;
+                }
+                let Some(BoardObject::Stack(stack)) = self.board.objects.get(&id) else {
+                    return;
+                };
+                let Stack {
+                    x,
+                    y,
+                    items,
+                    position_timestamp,
+                    ..
+                } = stack.clone();
+                let mut spread = Vec::new();
+                for (i, texture) in items.into_iter().enumerate() {
+                    // The bottom image keeps the stack's id, and with it its
+                    // comments.
+                    let (image_id, position_timestamp) = match i {
+                        0 => (id, position_timestamp),
+                        _ => (self.next_global_id(), Timestamp::default()),
+                    };
+                    let image = Image {
+                        id: image_id,
+                        x: x + i as f64 * dx,
+                        y: y + i as f64 * dy,
+                        texture,
+                        position_timestamp,
+                    };
+                    self.board
+                        .objects
+                        .insert(image_id, BoardObject::Image(image));
+                    spread.push(image_id);
+                }
+                for image in spread {
+                    self.update_object(image, event_sender, observer).await;
+                }
+            }
+            BoardAction::SliceSheet {
+                image,
+                slices,
+                into_deck,
+            } => {
+                let Some(id) = self.get_global_id(client_id, image) else {
+                    return;
+                };
+                self.slice_sheet(id, &slices, into_deck, event_sender, observer)
+                    .await;
+            }
+            BoardAction::NewFog {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                if !self.is_game_master(client_id) {
+                    return;
+                }
+                if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+                    return;
+                }
+                let id = self.next_global_id();
+                let fog = Fog {
+                    id,
+                    x,
+                    y,
+                    width,
+                    height,
+                    position_timestamp: Timestamp::default(),
+                };
+                self.board.objects.insert(id, BoardObject::Fog(fog));
+                observer
+                    .object_set(id, self.board.objects[&id].clone())
+                    .await;
+                self.send_views(&self.object_ids(), event_sender).await;
+            }
+            BoardAction::RemoveFog { id } => {
+                if !self.is_game_master(client_id)
+                    || !matches!(self.board.objects.get(&id), Some(BoardObject::Fog(_)))
+                {
+                    return;
+                }
+                self.remove_object(id, event_sender, observer).await;
+                self.send_views(&self.object_ids(), event_sender).await;
+            }
+            BoardAction::SetHidden { id, hidden } => {
+                if !self.is_game_master(client_id)
+                    || !self.board.objects.contains_key(&id)
+                    || self.board.hidden.contains(&id) == hidden
+                {
+                    return;
+                }
+                match hidden {
+                    true => self.board.hidden.insert(id),
+                    false => self.board.hidden.remove(&id),
+                };
+                observer.hidden_changed(id, hidden).await;
+                let clients: Vec<_> = self.clients.keys().copied().collect();
+                for other in clients {
+                    if self.is_game_master(other) {
+                        let event = BoardEvent::HiddenChanged { id, hidden };
+                        event_sender.send_event(other, event).await;
+                    } else {
+                        self.send_view(other, id, event_sender).await;
+                    }
+                }
+            }
         }
     }
 }
 
 struct Client {
     id: u64,
+    session: Option<u64>,
     ids_map: HashMap<u64, u64>,
+    /// Textures the client has been sent since its last full board.
+    known_textures: HashSet<u64>,
 }
 
 impl Client {
     pub fn new(id: u64) -> Self {
         Self {
             id,
+            session: None,
             ids_map: HashMap::new(),
+            known_textures: HashSet::new(),
         }
     }
 
@@ -177,3 +1476,209 @@ impl Client {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+
+    const OWNER: u64 = 1;
+    const PLAYER: u64 = 2;
+    const IMAGE: u64 = 10;
+    const FOG: u64 = 11;
+
+    /// Runs a board future, which never waits with the senders below.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("board future waited"),
+        }
+    }
+
+    #[derive(Default)]
+    struct Events(Vec<(u64, BoardEvent)>);
+
+    impl EventSender for Events {
+        async fn send_event(&mut self, client_id: u64, event: BoardEvent) {
+            self.0.push((client_id, event));
+        }
+
+        async fn send_comment_event(&mut self, _: u64, _: CommentEvent) {}
+    }
+
+    struct Ignore;
+
+    impl BoardObserver for Ignore {
+        async fn new_image(&mut self, _: u64, _: f64, _: f64, _: Texture<u64>) {}
+        async fn move_object(&mut self, _: u64, _: f64, _: f64, _: Timestamp) {}
+        async fn set_role(&mut self, _: u64, _: Option<Role>) {}
+        async fn share_link_created(&mut self, _: u64, _: ShareLink) {}
+        async fn share_link_revoked(&mut self, _: u64) {}
+        async fn set_settings(&mut self, _: BoardSettings) {}
+        async fn comment_added(&mut self, _: u64, _: Comment) {}
+        async fn thread_resolved(&mut self, _: u64, _: bool) {}
+        async fn comment_deleted(&mut self, _: u64, _: u64) {}
+        async fn object_set(&mut self, _: u64, _: BoardObject<u64>) {}
+        async fn object_removed(&mut self, _: u64) {}
+        async fn rolled(&mut self, _: DiceRoll) {}
+        async fn counter_incremented(&mut self, _: u64, _: i64) {}
+        async fn counter_set(&mut self, _: u64, _: i64) {}
+        async fn texture_added(&mut self, _: u64, _: Vec<u8>) {}
+        async fn hidden_changed(&mut self, _: u64, _: bool) {}
+        async fn seating_changed(&mut self, _: Seating) {}
+    }
+
+    /// An owner and a player, with an image under fog.
+    fn fogged_board() -> GlobalBoard {
+        let image = Image {
+            id: IMAGE,
+            x: 5.0,
+            y: 5.0,
+            texture: 3,
+            position_timestamp: Timestamp::default(),
+        };
+        let fog = Fog {
+            id: FOG,
+            x: 0.0,
+            y: 0.0,
+            width: 10.0,
+            height: 10.0,
+            position_timestamp: Timestamp::default(),
+        };
+        let board = Board {
+            objects: HashMap::from([
+                (IMAGE, BoardObject::Image(image)),
+                (FOG, BoardObject::Fog(fog)),
+            ]),
+            textures: HashMap::from([(3, vec![0])]),
+            roles: HashMap::from([(OWNER, Role::Owner), (PLAYER, Role::Editor)]),
+            share_links: HashMap::new(),
+            settings: BoardSettings::default(),
+            threads: HashMap::new(),
+            hidden: HashSet::new(),
+            seating: Seating::default(),
+        };
+        let mut board = GlobalBoard::from_board(board, [0; 32]);
+        board.new_client(OWNER, 0);
+        board.new_client(PLAYER, 0);
+        board
+    }
+
+    fn position(board: &GlobalBoard, id: u64) -> (f64, f64) {
+        let (x, y, _) = board.board.objects[&id].position().unwrap();
+        (x, y)
+    }
+
+    fn move_at(id: u64, x: f64, y: f64, time: u64, writer: u64) -> BoardAction {
+        BoardAction::Move {
+            id: ObjectIdentifier::Global(id),
+            x,
+            y,
+            timestamp: Timestamp { time, writer },
+        }
+    }
+
+    fn move_to(id: u64, x: f64, y: f64) -> BoardAction {
+        move_at(id, x, y, 1, PLAYER)
+    }
+
+    #[test]
+    fn fogged_objects_cannot_be_moved_out_of_fog() {
+        let mut board = fogged_board();
+        let mut events = Events::default();
+        block_on(board.apply(PLAYER, move_to(IMAGE, 50.0, 50.0), &mut events, &mut Ignore));
+        assert_eq!(position(&board, IMAGE), (5.0, 5.0));
+        assert!(matches!(
+            events.0[..],
+            [(PLAYER, BoardEvent::ObjectRemoved { id: IMAGE })]
+        ));
+
+        block_on(board.apply(OWNER, move_to(IMAGE, 50.0, 50.0), &mut events, &mut Ignore));
+        assert_eq!(position(&board, IMAGE), (50.0, 50.0));
+    }
+
+    #[test]
+    fn only_game_masters_move_fog() {
+        let mut board = fogged_board();
+        let mut events = Events::default();
+        block_on(board.apply(PLAYER, move_to(FOG, 50.0, 50.0), &mut events, &mut Ignore));
+        assert_eq!(position(&board, FOG), (0.0, 0.0));
+        assert!(!board.can_see(PLAYER, IMAGE));
+    }
+
+    #[test]
+    fn forged_timestamps_cannot_pin_objects() {
+        let mut board = fogged_board();
+        let mut events = Events::default();
+        let forged = move_at(FOG, 20.0, 20.0, u64::MAX, OWNER);
+        block_on(board.apply(OWNER, forged, &mut events, &mut Ignore));
+        let (_, _, timestamp) = board.board.objects[&FOG].position().unwrap();
+        assert_eq!(timestamp.time, 1);
+
+        block_on(board.apply(
+            OWNER,
+            move_at(FOG, 30.0, 30.0, 2, OWNER),
+            &mut events,
+            &mut Ignore,
+        ));
+        assert_eq!(position(&board, FOG), (30.0, 30.0));
+    }
+
+    #[test]
+    fn replayed_images_are_created_once() {
+        let mut board = fogged_board();
+        let mut events = Events::default();
+        let new_image = BoardAction::NewImage {
+            x: 50.0,
+            y: 50.0,
+            local_id: 1,
+            texture: Texture::New {
+                id: ObjectIdentifier::Local(2),
+                data: vec![1],
+            },
+        };
+        board.resume(PLAYER, 7);
+        block_on(board.apply(PLAYER, new_image.clone(), &mut events, &mut Ignore));
+        // The page reconnects and sends its queue and pending actions again.
+        board.remove_client(PLAYER, 0);
+        board.new_client(PLAYER, 0);
+        board.resume(PLAYER, 7);
+        block_on(board.apply(PLAYER, new_image.clone(), &mut events, &mut Ignore));
+        block_on(board.apply(PLAYER, new_image, &mut events, &mut Ignore));
+        assert_eq!(board.board.objects.len(), 3);
+        let confirmed: Vec<_> = events
+            .0
+            .iter()
+            .filter_map(|event| match event {
+                (PLAYER, BoardEvent::ConfirmImage { global_id, .. }) => Some(*global_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(confirmed.len(), 3);
+        assert!(confirmed.iter().all(|&id| id == confirmed[0]));
+    }
+
+    #[test]
+    fn unknown_textures_cannot_be_reused() {
+        let mut board = fogged_board();
+        let mut events = Events::default();
+        let new_image = BoardAction::NewImage {
+            x: 50.0,
+            y: 50.0,
+            local_id: 1,
+            texture: Texture::Existing {
+                id: ObjectIdentifier::Global(3),
+            },
+        };
+        block_on(board.apply(PLAYER, new_image, &mut events, &mut Ignore));
+        assert_eq!(board.board.objects.len(), 2);
+        assert!(matches!(
+            events.0[..],
+            [(PLAYER, BoardEvent::RejectImage { local_id: 1 })]
+        ));
+    }
+}
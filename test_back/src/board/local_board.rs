@@ -1,12 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::board::common::{
-    Board, BoardAction, BoardEvent, BoardObject, Image, ObjectIdentifier, Texture,
+use crate::board::{
+    clock::{Clock, Timestamp},
+    comments::{CommentEvent, Thread},
+    common::{
+        Board, BoardAction, BoardEvent, BoardObject, COUNTER_SIZE, DICE_SIZE, Image,
+        ObjectIdentifier, Texture,
+    },
+    roles::Role,
+    seating::Seating,
+    settings::BoardSettings,
 };
 
 pub trait BoardObserver {
     fn create_texture(&mut self, data: Vec<u8>) -> Option<u64>;
+    fn texture_size(&self, texture_id: u64) -> (f64, f64);
     fn new_image(&mut self, x: f64, y: f64, texture_id: u64) -> u64;
+    fn new_zone(&mut self, x: f64, y: f64, width: f64, height: f64) -> u64;
+    /// Draws dice showing the total of their last roll, if any.
+    fn new_dice(&mut self, x: f64, y: f64, total: Option<i64>) -> u64;
+    fn new_counter(&mut self, x: f64, y: f64, value: i64) -> u64;
+    /// Shades a region, either fog or an object hidden from players.
+    fn new_fog(&mut self, x: f64, y: f64, width: f64, height: f64) -> u64;
+    fn clear(&mut self);
+    fn settings_changed(&mut self, settings: &BoardSettings);
 }
 
 pub struct LocalBoard {
@@ -15,23 +32,102 @@ pub struct LocalBoard {
     texture_internal_ids_reverse: HashMap<u64, ObjectIdentifier>,
     image_internal_ids: HashMap<ObjectIdentifier, u64>,
     local_id_counter: u64,
+    pending_actions: BTreeMap<u64, BoardAction>,
+    clock: Clock,
+}
+
+/// Images are drawn in creation order, with unconfirmed ones on top.
+fn draw_order(id: &ObjectIdentifier) -> (bool, u64) {
+    match *id {
+        ObjectIdentifier::Global(id) => (false, id),
+        ObjectIdentifier::Local(id) => (true, id),
+    }
+}
+
+/// Hand zones lie below everything else and fog above it.
+fn layer(object: &BoardObject<ObjectIdentifier>) -> (u8, Option<(bool, u64)>) {
+    let layer = match object {
+        BoardObject::Hand(_) => 0,
+        BoardObject::Fog(_) => 2,
+        _ => 1,
+    };
+    (layer, object.id().map(|id| draw_order(&id)))
+}
+
+/// Fog is only picked where nothing else is, so objects the game master sees
+/// through it stay within reach.
+fn pick_layer(object: &BoardObject<ObjectIdentifier>) -> (u8, Option<(bool, u64)>) {
+    let layer = match object {
+        BoardObject::Fog(_) => 0,
+        BoardObject::Hand(_) => 1,
+        _ => 2,
+    };
+    (layer, object.id().map(|id| draw_order(&id)))
 }
 
 impl LocalBoard {
-    pub fn new() -> Self {
+    /// `session` identifies this board in timestamps of its writes.
+    pub fn new(session: u64) -> Self {
         Self {
             board: Board {
                 objects: HashMap::new(),
                 textures: HashMap::new(),
+                roles: HashMap::new(),
+                share_links: HashMap::new(),
+                settings: BoardSettings::default(),
+                threads: HashMap::new(),
+                hidden: HashSet::new(),
+                seating: Seating::default(),
             },
             texture_internal_ids: HashMap::new(),
             image_internal_ids: HashMap::new(),
             local_id_counter: 0,
             texture_internal_ids_reverse: HashMap::new(),
+            pending_actions: BTreeMap::new(),
+            clock: Clock::new(session),
         }
     }
 
-    pub fn load(&mut self, board: Board<u64>, observer: &mut impl BoardObserver) {
+    /// Actions the server has not answered yet, in the order they were made.
+    /// They are resent after a reconnect since the last ones may have been
+    /// lost with the connection; the server ignores those it already applied.
+    pub fn pending_actions(&self) -> Vec<BoardAction> {
+        self.pending_actions.values().cloned().collect()
+    }
+
+    /// Replaces the board with the server's state. Objects the server has not
+    /// confirmed yet are kept, as their confirmation may still arrive, and so
+    /// are local moves newer than the server's. Those moves are returned to be
+    /// sent again, as they may have been lost with the connection; the server
+    /// ignores those it already applied.
+    pub fn load(
+        &mut self,
+        board: Board<u64>,
+        observer: &mut impl BoardObserver,
+    ) -> Vec<BoardAction> {
+        let is_local = |id: &ObjectIdentifier| matches!(id, ObjectIdentifier::Local(_));
+        let local_positions: HashMap<_, _> = self
+            .board
+            .objects
+            .values()
+            .filter_map(|object| Some((object.id()?, object.position()?)))
+            .collect();
+        self.board.objects.retain(|id, _| is_local(id));
+        let mut moves: Vec<_> = local_positions
+            .iter()
+            .filter(|&(id, &(_, _, timestamp))| is_local(id) && timestamp != Timestamp::default())
+            .map(|(&id, &position)| (id, position))
+            .collect();
+        self.board.roles = board.roles;
+        self.board.share_links = board.share_links;
+        self.board.settings = board.settings;
+        self.board.threads = board.threads;
+        self.board.hidden = board.hidden;
+        observer.settings_changed(&board.settings);
+        self.texture_internal_ids.retain(|id, _| is_local(id));
+        self.texture_internal_ids_reverse
+            .retain(|_, id| is_local(id));
+
         for (texture_global_id, data) in board.textures {
             let internal_id = observer
                 .create_texture(data)
@@ -41,36 +137,91 @@ impl LocalBoard {
         }
 
         for object in board.objects.into_values() {
-            match object {
-                BoardObject::Image(Image {
-                    id: obj_global_id,
-                    x,
-                    y,
-                    texture: texture_global_id,
-                }) => {
-                    let texture_id = ObjectIdentifier::Global(texture_global_id);
-                    let img_id = ObjectIdentifier::Global(obj_global_id);
-
-                    let texture_internal_id = *self
-                        .texture_internal_ids
-                        .get(&texture_id)
-                        .expect("Texture must exist");
-
-                    let img_internal_id = observer.new_image(x, y, texture_internal_id);
-                    self.init_image_id(img_id, img_internal_id);
-
-                    self.board.objects.insert(
-                        img_id,
-                        BoardObject::Image(Image {
-                            id: img_id,
-                            x,
-                            y,
-                            texture: texture_id,
-                        }),
-                    );
-                }
-                BoardObject::Line => todo!(),
+            let mut object = object.map_ids(ObjectIdentifier::Global);
+            let (Some(id), Some((_, _, position_timestamp))) = (object.id(), object.position())
+            else {
+                continue;
+            };
+            self.clock.observe(position_timestamp);
+            if let Some(&(x, y, local_timestamp)) = local_positions.get(&id)
+                && local_timestamp > position_timestamp
+            {
+                object.set_position(x, y, local_timestamp);
+                moves.push((id, (x, y, local_timestamp)));
             }
+            self.board.objects.insert(id, object);
+        }
+
+        self.redraw(observer);
+        // In the order they were made, as the server lets clocks run ahead of
+        // it by one write only.
+        moves.sort_by_key(|&(_, (_, _, timestamp))| timestamp);
+        moves
+            .into_iter()
+            .map(|(id, (x, y, timestamp))| BoardAction::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            })
+            .collect()
+    }
+
+    fn redraw(&mut self, observer: &mut impl BoardObserver) {
+        observer.clear();
+        self.image_internal_ids.clear();
+        let mut objects: Vec<_> = self.board.objects.values().collect();
+        objects.sort_by_key(|object| layer(object));
+        for object in objects {
+            if let BoardObject::Hand(hand) = object {
+                let zone_internal_id = observer.new_zone(hand.x, hand.y, hand.width, hand.height);
+                self.image_internal_ids.insert(hand.id, zone_internal_id);
+                continue;
+            }
+            if let BoardObject::Dice(dice) = object {
+                let total = dice.last_roll.as_ref().map(|roll| roll.total);
+                let dice_internal_id = observer.new_dice(dice.x, dice.y, total);
+                self.image_internal_ids.insert(dice.id, dice_internal_id);
+                continue;
+            }
+            if let BoardObject::Counter(counter) = object {
+                let counter_internal_id = observer.new_counter(counter.x, counter.y, counter.value);
+                self.image_internal_ids
+                    .insert(counter.id, counter_internal_id);
+                continue;
+            }
+            if let BoardObject::Fog(fog) = object {
+                let fog_internal_id = observer.new_fog(fog.x, fog.y, fog.width, fog.height);
+                self.image_internal_ids.insert(fog.id, fog_internal_id);
+                continue;
+            }
+            let (Some(id), Some(texture), Some((x, y, _))) =
+                (object.id(), object.visible_texture(), object.position())
+            else {
+                continue;
+            };
+            let texture_internal_id = *self
+                .texture_internal_ids
+                .get(&texture)
+                .expect("Texture must exist");
+            let img_internal_id = observer.new_image(x, y, texture_internal_id);
+            self.image_internal_ids.insert(id, img_internal_id);
+        }
+        // Only the game master is sent hidden objects, shaded to tell them
+        // apart from what players see.
+        let hidden: Vec<_> = self
+            .board
+            .hidden
+            .iter()
+            .filter_map(|&id| {
+                let id = ObjectIdentifier::Global(id);
+                let (x, y) = self.position(id)?;
+                let (width, height) = self.size(id, observer)?;
+                Some((x, y, width, height))
+            })
+            .collect();
+        for (x, y, width, height) in hidden {
+            observer.new_fog(x, y, width, height);
         }
     }
 
@@ -98,14 +249,36 @@ impl LocalBoard {
     }
 
     fn update_texture_id(&mut self, old_id: ObjectIdentifier, new_id: ObjectIdentifier) {
-        let internal_id = self
-            .texture_internal_ids
-            .remove(&old_id)
-            .expect("Old texture ID must exist");
+        if old_id == new_id {
+            return;
+        }
+        // Several pending images may share one local texture, so the first
+        // confirmation re-keys it for all of them.
+        if let Some(internal_id) = self.texture_internal_ids.remove(&old_id) {
+            self.texture_internal_ids.insert(new_id, internal_id);
+            self.texture_internal_ids_reverse
+                .insert(internal_id, new_id);
+        }
+        for object in self.board.objects.values_mut() {
+            if let BoardObject::Image(img) = object
+                && img.texture == old_id
+            {
+                img.texture = new_id;
+            }
+        }
+    }
 
-        self.texture_internal_ids.insert(new_id, internal_id);
-        self.texture_internal_ids_reverse
-            .insert(internal_id, new_id);
+    fn forget_texture_if_unused(&mut self, id: ObjectIdentifier) {
+        let used = self.board.objects.values().any(|object| match object {
+            BoardObject::Image(img) => img.texture == id,
+            _ => false,
+        });
+        if used || !matches!(id, ObjectIdentifier::Local(_)) {
+            return;
+        }
+        if let Some(internal_id) = self.texture_internal_ids.remove(&id) {
+            self.texture_internal_ids_reverse.remove(&internal_id);
+        }
     }
 
     fn create_or_get_texture_id(
@@ -148,6 +321,7 @@ impl LocalBoard {
                         x,
                         y,
                         texture: texture_id,
+                        position_timestamp: Timestamp::default(),
                     }),
                 );
             }
@@ -159,22 +333,26 @@ impl LocalBoard {
                 let img_old_id = ObjectIdentifier::Local(local_id);
                 let img_new_id = ObjectIdentifier::Global(global_id);
                 let texture_new_id = ObjectIdentifier::Global(texture_id);
+                self.pending_actions.remove(&local_id);
 
-                let img = self
-                    .board
-                    .objects
-                    .remove(&img_old_id)
-                    .expect("Object must exist");
+                let Some(img) = self.board.objects.remove(&img_old_id) else {
+                    return;
+                };
 
                 let BoardObject::Image(img) = img else {
                     panic!("Object must be an image");
                 };
 
-                let texture_old_id = img.texture;
+                self.update_texture_id(img.texture, texture_new_id);
 
-                self.update_image_id(img_old_id, img_new_id);
-                self.update_texture_id(texture_old_id, texture_new_id);
+                // The image may already be known if the server applied it
+                // before a reconnect and sent it back in the new board.
+                if self.board.objects.contains_key(&img_new_id) {
+                    self.redraw(observer);
+                    return;
+                }
 
+                self.update_image_id(img_old_id, img_new_id);
                 self.board.objects.insert(
                     img_new_id,
                     BoardObject::Image(Image {
@@ -184,9 +362,285 @@ impl LocalBoard {
                     }),
                 );
             }
+            BoardEvent::RejectImage { local_id } => {
+                self.pending_actions.remove(&local_id);
+                let img_id = ObjectIdentifier::Local(local_id);
+                let Some(BoardObject::Image(img)) = self.board.objects.remove(&img_id) else {
+                    return;
+                };
+                self.forget_texture_if_unused(img.texture);
+                self.redraw(observer);
+            }
+            BoardEvent::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => {
+                self.clock.observe(timestamp);
+                let Some(object) = self.board.objects.get_mut(&ObjectIdentifier::Global(id)) else {
+                    return;
+                };
+                let Some((local_x, local_y, local_timestamp)) = object.position() else {
+                    return;
+                };
+                // A local write with a later timestamp is still on its way to
+                // the server and will win there as well, unless the server
+                // stamped it earlier because it ran ahead of its clock.
+                let restamped =
+                    timestamp.writer == local_timestamp.writer && (x, y) == (local_x, local_y);
+                if timestamp <= local_timestamp && !restamped {
+                    return;
+                }
+                object.set_position(x, y, timestamp);
+                self.redraw(observer);
+            }
+            BoardEvent::RoleChanged { user, role } => match role {
+                Some(role) => {
+                    self.board.roles.insert(user, role);
+                }
+                None => {
+                    self.board.roles.remove(&user);
+                }
+            },
+            BoardEvent::ShareLinkCreated { id, link } => {
+                self.board.share_links.insert(id, link);
+            }
+            BoardEvent::ShareLinkRevoked { id } => {
+                self.board.share_links.remove(&id);
+            }
+            BoardEvent::SettingsChanged(settings) => {
+                self.board.settings = settings;
+                observer.settings_changed(&settings);
+            }
+            BoardEvent::ObjectUpdated(object) => {
+                let mut object = object.map_ids(ObjectIdentifier::Global);
+                let Some(id) = object.id() else {
+                    return;
+                };
+                if let (Some((x, y, local_timestamp)), Some((_, _, timestamp))) = (
+                    self.board.objects.get(&id).and_then(|old| old.position()),
+                    object.position(),
+                ) && local_timestamp > timestamp
+                {
+                    object.set_position(x, y, local_timestamp);
+                }
+                self.board.objects.insert(id, object);
+                self.redraw(observer);
+            }
+            BoardEvent::ObjectRemoved { id } => {
+                self.board.objects.remove(&ObjectIdentifier::Global(id));
+                self.board.threads.remove(&id);
+                self.board.hidden.remove(&id);
+                self.redraw(observer);
+            }
+            BoardEvent::Rolled(roll) => {
+                let id = ObjectIdentifier::Global(roll.dice);
+                if let Some(BoardObject::Dice(dice)) = self.board.objects.get_mut(&id) {
+                    dice.last_roll = Some(roll);
+                    self.redraw(observer);
+                }
+            }
+            BoardEvent::NewTexture { id, data } => {
+                self.create_or_get_texture_id(Texture::New { id, data }, observer);
+            }
+            BoardEvent::CounterChanged { id, value } => {
+                let id = ObjectIdentifier::Global(id);
+                if let Some(BoardObject::Counter(counter)) = self.board.objects.get_mut(&id) {
+                    counter.value = value;
+                    self.redraw(observer);
+                }
+            }
+            BoardEvent::HiddenChanged { id, hidden } => {
+                match hidden {
+                    true => self.board.hidden.insert(id),
+                    false => self.board.hidden.remove(&id),
+                };
+                self.redraw(observer);
+            }
+            BoardEvent::SeatingChanged(seating) => self.board.seating = seating,
+        }
+    }
+
+    pub fn apply_comment_event(&mut self, event: CommentEvent) {
+        match event {
+            CommentEvent::Added { object, comment } => {
+                self.board
+                    .threads
+                    .entry(object)
+                    .or_default()
+                    .comments
+                    .push(comment);
+            }
+            CommentEvent::ResolvedChanged { object, resolved } => {
+                if let Some(thread) = self.board.threads.get_mut(&object) {
+                    thread.resolved = resolved;
+                }
+            }
+            CommentEvent::Deleted { object, comment } => {
+                if let Some(thread) = self.board.threads.get_mut(&object) {
+                    thread.comments.retain(|c| c.id != comment);
+                    if thread.comments.is_empty() {
+                        self.board.threads.remove(&object);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Objects that have not been confirmed yet have no thread.
+    pub fn thread(&self, id: ObjectIdentifier) -> Option<&Thread> {
+        match id {
+            ObjectIdentifier::Global(id) => self.board.threads.get(&id),
+            ObjectIdentifier::Local(_) => None,
+        }
+    }
+
+    pub fn settings(&self) -> BoardSettings {
+        self.board.settings
+    }
+
+    pub fn set_settings(
+        &mut self,
+        settings: BoardSettings,
+        observer: &mut impl BoardObserver,
+    ) -> BoardAction {
+        self.board.settings = settings;
+        observer.settings_changed(&settings);
+        BoardAction::SetSettings(settings)
+    }
+
+    pub fn role(&self, user: u64) -> Role {
+        self.board.roles.get(&user).copied().unwrap_or_default()
+    }
+
+    pub fn seating(&self) -> &Seating {
+        &self.board.seating
+    }
+
+    pub fn object(&self, id: ObjectIdentifier) -> Option<&BoardObject<ObjectIdentifier>> {
+        self.board.objects.get(&id)
+    }
+
+    pub fn size(&self, id: ObjectIdentifier, observer: &impl BoardObserver) -> Option<(f64, f64)> {
+        let object = self.board.objects.get(&id)?;
+        match object {
+            BoardObject::Hand(hand) => return Some((hand.width, hand.height)),
+            BoardObject::Dice(_) => return Some((DICE_SIZE, DICE_SIZE)),
+            BoardObject::Counter(_) => return Some((COUNTER_SIZE, COUNTER_SIZE)),
+            BoardObject::Fog(fog) => return Some((fog.width, fog.height)),
+            _ => {}
+        }
+        let texture = object.visible_texture()?;
+        let texture_internal_id = self.texture_internal_ids.get(&texture)?;
+        Some(observer.texture_size(*texture_internal_id))
+    }
+
+    /// Topmost object covering the given point that passes `filter`.
+    fn find_at(
+        &self,
+        x: f64,
+        y: f64,
+        observer: &impl BoardObserver,
+        filter: impl Fn(&BoardObject<ObjectIdentifier>) -> bool,
+    ) -> Option<ObjectIdentifier> {
+        self.board
+            .objects
+            .values()
+            .filter(|object| filter(object))
+            .filter(|object| {
+                let (Some(id), Some((obj_x, obj_y, _))) = (object.id(), object.position()) else {
+                    return false;
+                };
+                self.size(id, observer).is_some_and(|(width, height)| {
+                    x >= obj_x && y >= obj_y && x <= obj_x + width && y <= obj_y + height
+                })
+            })
+            .max_by_key(|object| pick_layer(object))?
+            .id()
+    }
+
+    /// Topmost object covering the given point.
+    pub fn object_at(
+        &self,
+        x: f64,
+        y: f64,
+        observer: &impl BoardObserver,
+    ) -> Option<ObjectIdentifier> {
+        self.find_at(x, y, observer, |_| true)
+    }
+
+    /// Topmost deck covering the given point, such as the one a card is
+    /// dropped onto.
+    pub fn deck_at(&self, x: f64, y: f64, observer: &impl BoardObserver) -> Option<u64> {
+        match self.find_at(x, y, observer, |object| {
+            matches!(object, BoardObject::Deck(_))
+        })? {
+            ObjectIdentifier::Global(id) => Some(id),
+            ObjectIdentifier::Local(_) => None,
+        }
+    }
+
+    /// Topmost image or stack covering the given point other than `dragged`,
+    /// such as the one `dragged` is dropped onto to stack them.
+    pub fn stack_target_at(
+        &self,
+        x: f64,
+        y: f64,
+        dragged: ObjectIdentifier,
+        observer: &impl BoardObserver,
+    ) -> Option<u64> {
+        match self.find_at(x, y, observer, |object| {
+            matches!(object, BoardObject::Image(_) | BoardObject::Stack(_))
+                && object.id() != Some(dragged)
+        })? {
+            ObjectIdentifier::Global(id) => Some(id),
+            ObjectIdentifier::Local(_) => None,
+        }
+    }
+
+    /// Topmost fog covering the given point.
+    pub fn fog_at(&self, x: f64, y: f64, observer: &impl BoardObserver) -> Option<u64> {
+        match self.find_at(x, y, observer, |object| {
+            matches!(object, BoardObject::Fog(_))
+        })? {
+            ObjectIdentifier::Global(id) => Some(id),
+            ObjectIdentifier::Local(_) => None,
         }
     }
 
+    /// Whether the object is hidden from players. Only the game master is told.
+    pub fn is_hidden(&self, id: u64) -> bool {
+        self.board.hidden.contains(&id)
+    }
+
+    pub fn position(&self, id: ObjectIdentifier) -> Option<(f64, f64)> {
+        let (x, y, _) = self.board.objects.get(&id)?.position()?;
+        Some((x, y))
+    }
+
+    pub fn move_object(
+        &mut self,
+        id: ObjectIdentifier,
+        x: f64,
+        y: f64,
+        observer: &mut impl BoardObserver,
+    ) -> Option<BoardAction> {
+        let (width, height) = self.size(id, observer)?;
+        let (x, y) = self.board.settings.snap(x, y, width, height);
+        let timestamp = self.clock.tick();
+        let object = self.board.objects.get_mut(&id)?;
+        object.position()?;
+        object.set_position(x, y, timestamp);
+        self.redraw(observer);
+        Some(BoardAction::Move {
+            id,
+            x,
+            y,
+            timestamp,
+        })
+    }
+
     pub fn new_image(
         &mut self,
         x: f64,
@@ -197,6 +651,8 @@ impl LocalBoard {
         let img_local_id = self.next_local_id();
         let img_id = ObjectIdentifier::Local(img_local_id);
         let texture_internal_id = observer.create_texture(data.clone())?;
+        let (width, height) = observer.texture_size(texture_internal_id);
+        let (x, y) = self.board.settings.snap(x, y, width, height);
         let texture_id = self
             .texture_internal_ids_reverse
             .get(&texture_internal_id)
@@ -224,13 +680,16 @@ impl LocalBoard {
                 x,
                 y,
                 texture: texture.get_id(),
+                position_timestamp: Timestamp::default(),
             }),
         );
-        Some(BoardAction::NewImage {
+        let action = BoardAction::NewImage {
             x,
             y,
             local_id: img_local_id,
             texture,
-        })
+        };
+        self.pending_actions.insert(img_local_id, action.clone());
+        Some(action)
     }
 }
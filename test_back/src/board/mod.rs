@@ -1,3 +1,10 @@
+pub mod clock;
+pub mod comments;
 pub mod common;
+pub mod dice;
 pub mod global_board;
 pub mod local_board;
+pub mod roles;
+pub mod seating;
+pub mod settings;
+pub mod sheet;
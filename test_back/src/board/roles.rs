@@ -0,0 +1,17 @@
+pub use backend_commons::client_info::Role;
+use serde::{Deserialize, Serialize};
+
+/// An outstanding share link of a room. Tokens of links that were revoked or
+/// have expired no longer admit anyone.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ShareLink {
+    pub role: Role,
+    /// Seconds since the Unix epoch.
+    pub expires_at: u64,
+}
+
+impl ShareLink {
+    pub fn is_valid(&self, now: u64) -> bool {
+        now < self.expires_at
+    }
+}
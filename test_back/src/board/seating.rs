@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// Most seats a room can have.
+pub const MAX_SEATS: usize = 16;
+
+/// Who plays in which seat, and whose turn it is. Seats take turns in
+/// order, skipping empty ones. A room without seats lets every editor act
+/// at any time.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Seating {
+    /// The user in each seat.
+    pub seats: Vec<Option<u64>>,
+    /// The seat whose turn it is, which is only empty while every seat is.
+    pub turn: usize,
+}
+
+impl Seating {
+    pub fn is_enabled(&self) -> bool {
+        !self.seats.is_empty()
+    }
+
+    pub fn seat_of(&self, user: u64) -> Option<usize> {
+        self.seats.iter().position(|&seated| seated == Some(user))
+    }
+
+    /// The user whose turn it is.
+    pub fn to_move(&self) -> Option<u64> {
+        self.seats.get(self.turn).copied().flatten()
+    }
+
+    /// Whether seating lets `user` act on the board now.
+    pub fn may_act(&self, user: u64) -> bool {
+        !self.is_enabled() || self.to_move() == Some(user)
+    }
+
+    /// Adds or removes seats at the end. Users in removed seats become
+    /// spectators.
+    pub fn resize(&mut self, count: usize) {
+        self.seats.resize(count.min(MAX_SEATS), None);
+        if self.turn >= self.seats.len() {
+            self.turn = 0;
+        }
+        self.skip_empty();
+    }
+
+    /// Seats `user` in a free seat, moving them if they already had one.
+    pub fn sit(&mut self, user: u64, seat: usize) -> bool {
+        if self.seats.get(seat) != Some(&None) {
+            return false;
+        }
+        self.stand(user);
+        self.seats[seat] = Some(user);
+        self.skip_empty();
+        true
+    }
+
+    pub fn stand(&mut self, user: u64) -> bool {
+        let Some(seat) = self.seat_of(user) else {
+            return false;
+        };
+        self.seats[seat] = None;
+        self.skip_empty();
+        true
+    }
+
+    /// Passes the turn to the next occupied seat.
+    pub fn end_turn(&mut self) {
+        let count = self.seats.len();
+        if let Some(next) = (1..=count)
+            .map(|step| (self.turn + step) % count)
+            .find(|&seat| self.seats[seat].is_some())
+        {
+            self.turn = next;
+        }
+    }
+
+    /// Passes the turn on from an empty seat, if anyone is seated.
+    fn skip_empty(&mut self) {
+        if self.seats.get(self.turn) == Some(&None) {
+            self.end_turn();
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+const SQRT_3: f64 = 1.732_050_807_568_877_2;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GridKind {
+    #[default]
+    None,
+    Square,
+    /// Pointy-top hexagons, `grid_size` apart horizontally.
+    Hex,
+}
+
+/// Appearance of the table surface shared by everyone in the room.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BoardSettings {
+    /// `0xRRGGBB`
+    pub background: u32,
+    pub grid: GridKind,
+    pub grid_size: f64,
+    pub snap_to_grid: bool,
+}
+
+impl Default for BoardSettings {
+    fn default() -> Self {
+        Self {
+            background: 0xffffff,
+            grid: GridKind::None,
+            grid_size: 64.0,
+            snap_to_grid: false,
+        }
+    }
+}
+
+impl BoardSettings {
+    pub fn is_valid(&self) -> bool {
+        self.background <= 0xffffff && self.grid_size.is_finite() && self.grid_size >= 1.0
+    }
+
+    /// Where an object of the given size placed at `(x, y)` ends up. Square
+    /// grids catch the top left corner on an intersection, hex grids put the
+    /// object's centre on the centre of a cell.
+    pub fn snap(&self, x: f64, y: f64, width: f64, height: f64) -> (f64, f64) {
+        if !self.snap_to_grid {
+            return (x, y);
+        }
+        let size = self.grid_size;
+        match self.grid {
+            GridKind::None => (x, y),
+            GridKind::Square => ((x / size).round() * size, (y / size).round() * size),
+            GridKind::Hex => {
+                let (center_x, center_y) = hex_center(x + width / 2.0, y + height / 2.0, size);
+                (center_x - width / 2.0, center_y - height / 2.0)
+            }
+        }
+    }
+}
+
+/// Hex centres form two rectangular lattices offset by half a cell, the
+/// nearest centre is the closer of the nearest points of both.
+fn hex_center(x: f64, y: f64, size: f64) -> (f64, f64) {
+    let (width, height) = (size, size * SQRT_3);
+    let even = ((x / width).round() * width, (y / height).round() * height);
+    let odd = (
+        ((x / width - 0.5).round() + 0.5) * width,
+        ((y / height - 0.5).round() + 0.5) * height,
+    );
+    let distance = |(cx, cy): (f64, f64)| (cx - x).powi(2) + (cy - y).powi(2);
+    if distance(even) <= distance(odd) {
+        even
+    } else {
+        odd
+    }
+}
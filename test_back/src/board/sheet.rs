@@ -0,0 +1,93 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_SLICES: usize = 500;
+
+/// Part of a sheet, in pixels of its texture.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// How a sheet of cards or tokens is cut into pieces.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum Slices {
+    /// Equal cells read row by row. Pixels left over when the sheet does not
+    /// divide evenly are dropped from the right and bottom edges.
+    Grid {
+        columns: u32,
+        rows: u32,
+    },
+    Rects(Vec<Rect>),
+}
+
+impl Slices {
+    /// The pieces of a sheet of the given size, or `None` if they do not fit
+    /// on it.
+    pub fn rects(&self, width: u32, height: u32) -> Option<Vec<Rect>> {
+        let rects = match self {
+            Slices::Grid { columns, rows } => {
+                let (columns, rows) = (*columns, *rows);
+                if columns == 0 || rows == 0 || columns > width || rows > height {
+                    return None;
+                }
+                // Checked before making any, as a grid can ask for billions.
+                let count = columns.checked_mul(rows)?;
+                if usize::try_from(count).ok()? > MAX_SLICES {
+                    return None;
+                }
+                let (cell_width, cell_height) = (width / columns, height / rows);
+                (0..rows)
+                    .flat_map(|row| {
+                        (0..columns).map(move |column| Rect {
+                            x: column * cell_width,
+                            y: row * cell_height,
+                            width: cell_width,
+                            height: cell_height,
+                        })
+                    })
+                    .collect()
+            }
+            Slices::Rects(rects) => rects.clone(),
+        };
+        let fits = |rect: &Rect| {
+            rect.width > 0
+                && rect.height > 0
+                && rect
+                    .x
+                    .checked_add(rect.width)
+                    .is_some_and(|right| right <= width)
+                && rect
+                    .y
+                    .checked_add(rect.height)
+                    .is_some_and(|bottom| bottom <= height)
+        };
+        (!rects.is_empty() && rects.len() <= MAX_SLICES && rects.iter().all(fits)).then_some(rects)
+    }
+}
+
+/// Cuts an encoded image into pieces, each encoded as PNG.
+pub fn slice(data: &[u8], slices: &Slices) -> Option<Vec<(Rect, Vec<u8>)>> {
+    let sheet = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .decode()
+        .ok()?;
+    slices
+        .rects(sheet.width(), sheet.height())?
+        .into_iter()
+        .map(|rect| {
+            let piece = sheet.crop_imm(rect.x, rect.y, rect.width, rect.height);
+            let mut data = Vec::new();
+            piece
+                .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+                .ok()?;
+            Some((rect, data))
+        })
+        .collect()
+}
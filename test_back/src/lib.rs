@@ -1,19 +1,64 @@
 pub mod board;
 pub mod store;
 
-use backend_commons::client_info::ClientListMessage;
+use backend_commons::{
+    chat::ChatListMessage,
+    checkpoint::{CheckpointAction, CheckpointListMessage},
+    client_info::ClientListMessage,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::board::common::{Board, BoardAction, BoardEvent};
+use crate::board::{
+    comments::{CommentAction, CommentEvent},
+    common::{Board, BoardAction, BoardEvent},
+    roles::Role,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ToClient {
     ClientListMessage(ClientListMessage),
-    NewBoard(Board<u64>),
+    ChatListMessage(ChatListMessage),
+    NewBoard(Box<Board<u64>>),
     BoardEvent(BoardEvent),
+    CommentEvent(CommentEvent),
+    CheckpointListMessage(CheckpointListMessage),
+    /// Answer to [`ToServer::CreateShareLink`], only sent to its owner.
+    ShareLink {
+        id: u64,
+        path: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ToServer {
+    /// Sent first on every connection with an id chosen once per page load.
+    Session(u64),
+    Chat(String),
     BoardAction(BoardAction),
+    /// Anyone may comment. Resolving needs an editor, deleting the author or
+    /// an owner.
+    CommentAction(CommentAction),
+    /// Editors only: rolls the dice object with the given id.
+    Roll(u64),
+    CheckpointAction(CheckpointAction),
+    /// Owner only: grants a role to a user, or revokes it with `None`.
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    /// Owner only: creates a link admitting anyone who opens it with `role`.
+    CreateShareLink {
+        role: Role,
+        valid_for_secs: u64,
+    },
+    /// Owner only.
+    RevokeShareLink(u64),
+    /// Owner only: sets how many seats the room has, `0` turning seating
+    /// off.
+    SetSeats(usize),
+    /// Editors only: takes a free seat.
+    Sit(usize),
+    Stand,
+    /// Passes the turn on. Only the player to move or an owner may.
+    EndTurn,
 }
@@ -1,15 +1,40 @@
 use backend_commons::store::Store;
 
 use crate::{
-    board::global_board::BoardObserver,
+    board::{
+        clock::Timestamp,
+        comments::Comment,
+        common::BoardObject,
+        dice::DiceRoll,
+        global_board::BoardObserver,
+        roles::{Role, ShareLink},
+        seating::Seating,
+        settings::BoardSettings,
+    },
     store::{
-        serialization::{BoardStore, convert_board},
-        v1::EventV1,
+        serialization::{
+            BoardStore, EventLatest, convert_board, convert_comment, convert_object, convert_role,
+            convert_roll, convert_seating, convert_settings, convert_share_link, convert_timestamp,
+        },
+        v14::Texture,
     },
 };
 
 pub mod serialization;
 pub mod v1;
+pub mod v10;
+pub mod v11;
+pub mod v12;
+pub mod v13;
+pub mod v14;
+pub mod v2;
+pub mod v3;
+pub mod v4;
+pub mod v5;
+pub mod v6;
+pub mod v7;
+pub mod v8;
+pub mod v9;
 
 pub struct StoringObserver<S: Store> {
     store: BoardStore<S>,
@@ -41,12 +66,109 @@ impl<S: Store> BoardObserver for StoringObserver<S> {
         texture: crate::board::common::Texture<u64>,
     ) {
         let texture = match texture {
-            crate::board::common::Texture::New { id, data } => v1::Texture::New { id, data },
+            crate::board::common::Texture::New { id, data } => Texture::New { id, data },
             crate::board::common::Texture::Existing { id: tex_id } => {
-                v1::Texture::Existing { id: tex_id }
+                Texture::Existing { id: tex_id }
             }
         };
-        let event = EventV1::NewImage { id, x, y, texture };
+        let event = EventLatest::NewImage { id, x, y, texture };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn move_object(&mut self, id: u64, x: f64, y: f64, timestamp: Timestamp) {
+        let event = EventLatest::Move {
+            id,
+            x,
+            y,
+            timestamp: convert_timestamp(timestamp),
+        };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn set_role(&mut self, user: u64, role: Option<Role>) {
+        let event = EventLatest::SetRole {
+            user,
+            role: role.map(convert_role),
+        };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn share_link_created(&mut self, id: u64, link: ShareLink) {
+        let event = EventLatest::ShareLinkCreated {
+            id,
+            link: convert_share_link(link),
+        };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn share_link_revoked(&mut self, id: u64) {
+        let event = EventLatest::ShareLinkRevoked { id };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn set_settings(&mut self, settings: BoardSettings) {
+        let event = EventLatest::SetSettings(convert_settings(settings));
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn comment_added(&mut self, object: u64, comment: Comment) {
+        let event = EventLatest::CommentAdded {
+            object,
+            comment: convert_comment(comment),
+        };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn thread_resolved(&mut self, object: u64, resolved: bool) {
+        let event = EventLatest::ThreadResolved { object, resolved };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn comment_deleted(&mut self, object: u64, comment: u64) {
+        let event = EventLatest::CommentDeleted { object, comment };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn object_set(&mut self, id: u64, object: BoardObject<u64>) {
+        let event = EventLatest::ObjectSet {
+            id,
+            object: convert_object(object),
+        };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn object_removed(&mut self, id: u64) {
+        let event = EventLatest::ObjectRemoved { id };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn rolled(&mut self, roll: DiceRoll) {
+        let event = EventLatest::Rolled(convert_roll(roll));
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn counter_incremented(&mut self, id: u64, delta: i64) {
+        let event = EventLatest::CounterIncremented { id, delta };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn counter_set(&mut self, id: u64, value: i64) {
+        let event = EventLatest::CounterSet { id, value };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn texture_added(&mut self, id: u64, data: Vec<u8>) {
+        let event = EventLatest::TextureAdded { id, data };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn hidden_changed(&mut self, id: u64, hidden: bool) {
+        let event = EventLatest::SetHidden { id, hidden };
+        self.store.apply_event(event).await.unwrap();
+    }
+
+    async fn seating_changed(&mut self, seating: Seating) {
+        let event = EventLatest::SetSeating(convert_seating(seating));
         self.store.apply_event(event).await.unwrap();
     }
 }
@@ -1,34 +1,81 @@
 use std::io::Result;
 
 use backend_commons::store::{StateBuilder, Store};
-use serde::de;
 
 use crate::{
-    board,
+    board::{
+        self,
+        clock::Timestamp,
+        comments::{Comment, Thread},
+        common::{BoardObject, Card, Counter, Deck, Dice, Fog, Hand, Image, Stack},
+        dice::{DiceRoll, Die},
+        roles::{Role, ShareLink},
+        seating::Seating,
+        settings::{BoardSettings, GridKind},
+    },
     store::{
         self,
         v1::{BoardV1, EventV1},
+        v2::{BoardV2, EventV2},
+        v3::{BoardV3, EventV3},
+        v4::{BoardV4, EventV4},
+        v5::{BoardV5, EventV5},
+        v6::{BoardV6, EventV6},
+        v7::{BoardV7, EventV7},
+        v8::{BoardV8, EventV8},
+        v9::{BoardV9, EventV9},
+        v10::{BoardV10, EventV10},
+        v11::{BoardV11, EventV11},
+        v12::{BoardV12, EventV12},
+        v13::{BoardV13, EventV13},
+        v14::{BoardV14, EventV14},
     },
 };
 
 // When creating a new version, increment CURRENT_VERSION and add a new variant to the Event and Board enums.
 
-pub const CURRENT_VERSION: u64 = 1;
-pub type EventLatest = EventV1;
-pub type BoardLatest = BoardV1;
+pub const CURRENT_VERSION: u64 = 14;
+pub type EventLatest = EventV14;
+pub type BoardLatest = BoardV14;
 
 #[derive(Debug)]
 enum Event {
     V1(EventV1),
+    V2(EventV2),
+    V3(EventV3),
+    V4(EventV4),
+    V5(EventV5),
+    V6(EventV6),
+    V7(EventV7),
+    V8(EventV8),
+    V9(EventV9),
+    V10(EventV10),
+    V11(EventV11),
+    V12(EventV12),
+    V13(EventV13),
+    V14(EventV14),
 }
 
 #[derive(Debug)]
 enum Board {
     V1(BoardV1),
+    V2(BoardV2),
+    V3(BoardV3),
+    V4(BoardV4),
+    V5(BoardV5),
+    V6(BoardV6),
+    V7(BoardV7),
+    V8(BoardV8),
+    V9(BoardV9),
+    V10(BoardV10),
+    V11(BoardV11),
+    V12(BoardV12),
+    V13(BoardV13),
+    V14(BoardV14),
 }
 
 pub struct BoardLoader {
-    board: BoardV1,
+    board: BoardLatest,
 }
 
 pub struct BoardStore<S: Store> {
@@ -51,37 +98,383 @@ impl<S: Store> BoardStore<S> {
     }
 }
 
+pub fn convert_timestamp(timestamp: Timestamp) -> store::v3::Timestamp {
+    store::v3::Timestamp {
+        time: timestamp.time,
+        writer: timestamp.writer,
+    }
+}
+
+fn load_timestamp(timestamp: store::v3::Timestamp) -> Timestamp {
+    Timestamp {
+        time: timestamp.time,
+        writer: timestamp.writer,
+    }
+}
+
+pub fn convert_role(role: Role) -> store::v3::Role {
+    match role {
+        Role::Viewer => store::v3::Role::Viewer,
+        Role::Editor => store::v3::Role::Editor,
+        Role::Owner => store::v3::Role::Owner,
+    }
+}
+
+fn load_role(role: store::v3::Role) -> Role {
+    match role {
+        store::v3::Role::Viewer => Role::Viewer,
+        store::v3::Role::Editor => Role::Editor,
+        store::v3::Role::Owner => Role::Owner,
+    }
+}
+
+pub fn convert_share_link(link: ShareLink) -> store::v4::ShareLink {
+    store::v4::ShareLink {
+        role: convert_role(link.role),
+        expires_at: link.expires_at,
+    }
+}
+
+fn load_share_link(link: store::v4::ShareLink) -> ShareLink {
+    ShareLink {
+        role: load_role(link.role),
+        expires_at: link.expires_at,
+    }
+}
+
+pub fn convert_settings(settings: BoardSettings) -> store::v5::Settings {
+    store::v5::Settings {
+        background: settings.background,
+        grid: match settings.grid {
+            GridKind::None => store::v5::GridKind::None,
+            GridKind::Square => store::v5::GridKind::Square,
+            GridKind::Hex => store::v5::GridKind::Hex,
+        },
+        grid_size: settings.grid_size,
+        snap_to_grid: settings.snap_to_grid,
+    }
+}
+
+fn load_settings(settings: store::v5::Settings) -> BoardSettings {
+    BoardSettings {
+        background: settings.background,
+        grid: match settings.grid {
+            store::v5::GridKind::None => GridKind::None,
+            store::v5::GridKind::Square => GridKind::Square,
+            store::v5::GridKind::Hex => GridKind::Hex,
+        },
+        grid_size: settings.grid_size,
+        snap_to_grid: settings.snap_to_grid,
+    }
+}
+
+pub fn convert_comment(comment: Comment) -> store::v6::Comment {
+    store::v6::Comment {
+        id: comment.id,
+        author: comment.author,
+        author_name: comment.author_name,
+        text: comment.text,
+        created_at: comment.created_at,
+    }
+}
+
+fn load_comment(comment: store::v6::Comment) -> Comment {
+    Comment {
+        id: comment.id,
+        author: comment.author,
+        author_name: comment.author_name,
+        text: comment.text,
+        created_at: comment.created_at,
+    }
+}
+
+fn convert_thread(thread: Thread) -> store::v6::Thread {
+    store::v6::Thread {
+        comments: thread.comments.into_iter().map(convert_comment).collect(),
+        resolved: thread.resolved,
+    }
+}
+
+fn load_thread(thread: store::v6::Thread) -> Thread {
+    Thread {
+        comments: thread.comments.into_iter().map(load_comment).collect(),
+        resolved: thread.resolved,
+    }
+}
+
+pub fn convert_roll(roll: DiceRoll) -> store::v9::Roll {
+    store::v9::Roll {
+        dice: roll.dice,
+        roller: roll.roller,
+        roller_name: roll.roller_name,
+        notation: roll.notation,
+        results: roll
+            .results
+            .into_iter()
+            .map(|die| store::v9::Die {
+                value: die.value,
+                kept: die.kept,
+            })
+            .collect(),
+        modifier: roll.modifier,
+        total: roll.total,
+        rolled_at: roll.rolled_at,
+    }
+}
+
+fn load_roll(roll: store::v9::Roll) -> DiceRoll {
+    DiceRoll {
+        dice: roll.dice,
+        roller: roll.roller,
+        roller_name: roll.roller_name,
+        notation: roll.notation,
+        results: roll
+            .results
+            .into_iter()
+            .map(|die| Die {
+                value: die.value,
+                kept: die.kept,
+            })
+            .collect(),
+        modifier: roll.modifier,
+        total: roll.total,
+        rolled_at: roll.rolled_at,
+    }
+}
+
+pub fn convert_seating(seating: Seating) -> store::v14::Seating {
+    store::v14::Seating {
+        seats: seating.seats,
+        turn: seating.turn,
+    }
+}
+
+fn load_seating(seating: store::v14::Seating) -> Seating {
+    Seating {
+        seats: seating.seats,
+        turn: seating.turn,
+    }
+}
+
+pub fn convert_object(object: BoardObject<u64>) -> store::v14::Object {
+    match object {
+        BoardObject::Image(image) => store::v14::Object::Image {
+            x: image.x,
+            y: image.y,
+            texture_id: image.texture,
+            position_timestamp: convert_timestamp(image.position_timestamp),
+        },
+        BoardObject::Line => store::v14::Object::Line,
+        BoardObject::Deck(deck) => store::v14::Object::Deck {
+            x: deck.x,
+            y: deck.y,
+            back: deck.back,
+            cards: deck.cards,
+            face_up: deck.face_up,
+            position_timestamp: convert_timestamp(deck.position_timestamp),
+        },
+        BoardObject::Card(card) => store::v14::Object::Card {
+            x: card.x,
+            y: card.y,
+            face: card.face,
+            back: card.back,
+            face_up: card.face_up,
+            position_timestamp: convert_timestamp(card.position_timestamp),
+        },
+        BoardObject::Hand(hand) => store::v14::Object::Hand {
+            x: hand.x,
+            y: hand.y,
+            width: hand.width,
+            height: hand.height,
+            owner: hand.owner,
+            position_timestamp: convert_timestamp(hand.position_timestamp),
+        },
+        BoardObject::Dice(dice) => store::v14::Object::Dice {
+            x: dice.x,
+            y: dice.y,
+            notation: dice.notation,
+            last_roll: dice.last_roll.map(convert_roll),
+            position_timestamp: convert_timestamp(dice.position_timestamp),
+        },
+        BoardObject::Counter(counter) => store::v14::Object::Counter {
+            x: counter.x,
+            y: counter.y,
+            value: counter.value,
+            position_timestamp: convert_timestamp(counter.position_timestamp),
+        },
+        BoardObject::Stack(stack) => store::v14::Object::Stack {
+            x: stack.x,
+            y: stack.y,
+            items: stack.items,
+            position_timestamp: convert_timestamp(stack.position_timestamp),
+        },
+        BoardObject::Fog(fog) => store::v14::Object::Fog {
+            x: fog.x,
+            y: fog.y,
+            width: fog.width,
+            height: fog.height,
+            position_timestamp: convert_timestamp(fog.position_timestamp),
+        },
+    }
+}
+
+fn load_object(id: u64, object: store::v14::Object) -> BoardObject<u64> {
+    match object {
+        store::v14::Object::Image {
+            x,
+            y,
+            texture_id,
+            position_timestamp,
+        } => BoardObject::Image(Image {
+            id,
+            x,
+            y,
+            texture: texture_id,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Line => BoardObject::Line,
+        store::v14::Object::Deck {
+            x,
+            y,
+            back,
+            cards,
+            face_up,
+            position_timestamp,
+        } => BoardObject::Deck(Deck {
+            id,
+            x,
+            y,
+            back,
+            cards,
+            face_up,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Card {
+            x,
+            y,
+            face,
+            back,
+            face_up,
+            position_timestamp,
+        } => BoardObject::Card(Card {
+            id,
+            x,
+            y,
+            face,
+            back,
+            face_up,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Hand {
+            x,
+            y,
+            width,
+            height,
+            owner,
+            position_timestamp,
+        } => BoardObject::Hand(Hand {
+            id,
+            x,
+            y,
+            width,
+            height,
+            owner,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Dice {
+            x,
+            y,
+            notation,
+            last_roll,
+            position_timestamp,
+        } => BoardObject::Dice(Dice {
+            id,
+            x,
+            y,
+            notation,
+            last_roll: last_roll.map(load_roll),
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Counter {
+            x,
+            y,
+            value,
+            position_timestamp,
+        } => BoardObject::Counter(Counter {
+            id,
+            x,
+            y,
+            value,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Stack {
+            x,
+            y,
+            items,
+            position_timestamp,
+        } => BoardObject::Stack(Stack {
+            id,
+            x,
+            y,
+            items,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+        store::v14::Object::Fog {
+            x,
+            y,
+            width,
+            height,
+            position_timestamp,
+        } => BoardObject::Fog(Fog {
+            id,
+            x,
+            y,
+            width,
+            height,
+            position_timestamp: load_timestamp(position_timestamp),
+        }),
+    }
+}
+
 pub fn convert_board(board: crate::board::common::Board<u64>) -> BoardLatest {
     BoardLatest {
         objects: board
             .objects
             .into_iter()
-            .map(|(id, obj)| {
-                (
-                    id,
-                    match obj {
-                        board::common::BoardObject::Image(image) => store::v1::Object::Image {
-                            x: image.x,
-                            y: image.y,
-                            texture_id: image.texture,
-                        },
-                        board::common::BoardObject::Line => store::v1::Object::Line,
-                    },
-                )
-            })
+            .map(|(id, object)| (id, convert_object(object)))
             .collect(),
-        textures: board
-            .textures
+        textures: board.textures,
+        roles: board
+            .roles
             .into_iter()
-            .map(|(id, data)| (id, data))
+            .map(|(user, role)| (user, convert_role(role)))
             .collect(),
+        share_links: board
+            .share_links
+            .into_iter()
+            .map(|(id, link)| (id, convert_share_link(link)))
+            .collect(),
+        settings: convert_settings(board.settings),
+        threads: board
+            .threads
+            .into_iter()
+            .map(|(object, thread)| (object, convert_thread(thread)))
+            .collect(),
+        hidden: board.hidden,
+        seating: convert_seating(board.seating),
+    }
+}
+
+impl Default for BoardLoader {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl BoardLoader {
     pub fn new() -> Self {
         Self {
-            board: BoardV1::new(),
+            board: BoardLatest::new(),
         }
     }
 
@@ -90,79 +483,141 @@ impl BoardLoader {
             .board
             .objects
             .into_iter()
-            .map(|(id, obj)| {
-                (
-                    id,
-                    match obj {
-                        super::v1::Object::Image { x, y, texture_id } => {
-                            board::common::BoardObject::Image(board::common::Image {
-                                id,
-                                x,
-                                y,
-                                texture: texture_id,
-                            })
-                        }
-                        super::v1::Object::Line => todo!(),
-                    },
-                )
-            })
+            .map(|(id, object)| (id, load_object(id, object)))
             .collect();
 
         board::common::Board {
             textures: self.board.textures,
             objects,
+            roles: self
+                .board
+                .roles
+                .into_iter()
+                .map(|(user, role)| (user, load_role(role)))
+                .collect(),
+            share_links: self
+                .board
+                .share_links
+                .into_iter()
+                .map(|(id, link)| (id, load_share_link(link)))
+                .collect(),
+            settings: load_settings(self.board.settings),
+            threads: self
+                .board
+                .threads
+                .into_iter()
+                .map(|(object, thread)| (object, load_thread(thread)))
+                .collect(),
+            hidden: self.board.hidden,
+            seating: load_seating(self.board.seating),
         }
     }
 
     fn load_board(&mut self, board: Board) {
-        match board {
-            Board::V1(b) => self.board = b,
-        }
+        let board = match board {
+            Board::V1(b) => Board::V2(b.into()),
+            Board::V2(b) => Board::V3(b.into()),
+            Board::V3(b) => Board::V4(b.into()),
+            Board::V4(b) => Board::V5(b.into()),
+            Board::V5(b) => Board::V6(b.into()),
+            Board::V6(b) => Board::V7(b.into()),
+            Board::V7(b) => Board::V8(b.into()),
+            Board::V8(b) => Board::V9(b.into()),
+            Board::V9(b) => Board::V10(b.into()),
+            Board::V10(b) => Board::V11(b.into()),
+            Board::V11(b) => Board::V12(b.into()),
+            Board::V12(b) => Board::V13(b.into()),
+            Board::V13(b) => Board::V14(b.into()),
+            Board::V14(b) => {
+                self.board = b;
+                return;
+            }
+        };
+        self.load_board(board);
     }
 
     fn load_board_event(&mut self, event: Event) {
-        match event {
-            Event::V1(e) => self.board.apply_event(e),
-        }
+        let event = match event {
+            Event::V1(e) => Event::V2(e.into()),
+            Event::V2(e) => Event::V3(e.into()),
+            Event::V3(e) => Event::V4(e.into()),
+            Event::V4(e) => Event::V5(e.into()),
+            Event::V5(e) => Event::V6(e.into()),
+            Event::V6(e) => Event::V7(e.into()),
+            Event::V7(e) => Event::V8(e.into()),
+            Event::V8(e) => Event::V9(e.into()),
+            Event::V9(e) => Event::V10(e.into()),
+            Event::V10(e) => Event::V11(e.into()),
+            Event::V11(e) => Event::V12(e.into()),
+            Event::V12(e) => Event::V13(e.into()),
+            Event::V13(e) => Event::V14(e.into()),
+            Event::V14(e) => {
+                self.board.apply_event(e);
+                return;
+            }
+        };
+        self.load_board_event(event);
     }
 }
 
+fn deserialize<T: serde::de::DeserializeOwned>(data: &[u8], what: &str) -> Result<T> {
+    postcard::from_bytes(data).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Failed to deserialize {what}: {e}"),
+        )
+    })
+}
+
+fn unsupported_version(version: u64) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Unsupported version: {version}"),
+    )
+}
+
 impl StateBuilder for BoardLoader {
     fn load_state(&mut self, version: u64, data: Vec<u8>) -> std::io::Result<()> {
-        match version {
-            1 => {
-                let board: BoardV1 = postcard::from_bytes(&data).map_err(|e| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        format!("Failed to deserialize board: {e}"),
-                    )
-                })?;
-                self.load_board(Board::V1(board));
-                Ok(())
-            }
-            _ => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Unsupported version: {version}"),
-            )),
-        }
+        let board = match version {
+            1 => Board::V1(deserialize(&data, "board")?),
+            2 => Board::V2(deserialize(&data, "board")?),
+            3 => Board::V3(deserialize(&data, "board")?),
+            4 => Board::V4(deserialize(&data, "board")?),
+            5 => Board::V5(deserialize(&data, "board")?),
+            6 => Board::V6(deserialize(&data, "board")?),
+            7 => Board::V7(deserialize(&data, "board")?),
+            8 => Board::V8(deserialize(&data, "board")?),
+            9 => Board::V9(deserialize(&data, "board")?),
+            10 => Board::V10(deserialize(&data, "board")?),
+            11 => Board::V11(deserialize(&data, "board")?),
+            12 => Board::V12(deserialize(&data, "board")?),
+            13 => Board::V13(deserialize(&data, "board")?),
+            14 => Board::V14(deserialize(&data, "board")?),
+            _ => return Err(unsupported_version(version)),
+        };
+        self.load_board(board);
+        Ok(())
     }
 
     fn load_event(&mut self, version: u64, data: Vec<u8>) -> std::io::Result<()> {
-        match version {
-            1 => {
-                let event: EventV1 = postcard::from_bytes(&data).map_err(|e| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        format!("Failed to deserialize event: {e}"),
-                    )
-                })?;
-                self.load_board_event(Event::V1(event));
-                Ok(())
-            }
-            _ => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Unsupported version: {version}"),
-            )),
-        }
+        let event = match version {
+            1 => Event::V1(deserialize(&data, "event")?),
+            2 => Event::V2(deserialize(&data, "event")?),
+            3 => Event::V3(deserialize(&data, "event")?),
+            4 => Event::V4(deserialize(&data, "event")?),
+            5 => Event::V5(deserialize(&data, "event")?),
+            6 => Event::V6(deserialize(&data, "event")?),
+            7 => Event::V7(deserialize(&data, "event")?),
+            8 => Event::V8(deserialize(&data, "event")?),
+            9 => Event::V9(deserialize(&data, "event")?),
+            10 => Event::V10(deserialize(&data, "event")?),
+            11 => Event::V11(deserialize(&data, "event")?),
+            12 => Event::V12(deserialize(&data, "event")?),
+            13 => Event::V13(deserialize(&data, "event")?),
+            14 => Event::V14(deserialize(&data, "event")?),
+            _ => return Err(unsupported_version(version)),
+        };
+        self.load_board_event(event);
+        Ok(())
     }
 }
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v9::{self, BoardV9, EventV9};
+
+pub use crate::store::v9::{
+    Comment, Die, GridKind, Role, Roll, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+    Deck {
+        x: f64,
+        y: f64,
+        back: u64,
+        cards: Vec<u64>,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Card {
+        x: f64,
+        y: f64,
+        face: u64,
+        back: u64,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Hand {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        owner: u64,
+        position_timestamp: Timestamp,
+    },
+    Dice {
+        x: f64,
+        y: f64,
+        notation: String,
+        last_roll: Option<Roll>,
+        position_timestamp: Timestamp,
+    },
+    Counter {
+        x: f64,
+        y: f64,
+        value: i64,
+        position_timestamp: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV10 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV10 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(Roll),
+    CounterIncremented {
+        id: u64,
+        delta: i64,
+    },
+    CounterSet {
+        id: u64,
+        value: i64,
+    },
+}
+
+impl BoardV10 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV10) {
+        match event {
+            EventV10::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV10::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => match self.objects.get_mut(&id) {
+                Some(
+                    Object::Image {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Deck {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Card {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Hand {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Dice {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Counter {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    },
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+                Some(Object::Line) | None => {}
+            },
+            EventV10::SetRole { user, role } => {
+                self.apply_v9_event(EventV9::SetRole { user, role })
+            }
+            EventV10::ShareLinkCreated { id, link } => {
+                self.apply_v9_event(EventV9::ShareLinkCreated { id, link })
+            }
+            EventV10::ShareLinkRevoked { id } => {
+                self.apply_v9_event(EventV9::ShareLinkRevoked { id })
+            }
+            EventV10::SetSettings(settings) => self.apply_v9_event(EventV9::SetSettings(settings)),
+            EventV10::CommentAdded { object, comment } => {
+                self.apply_v9_event(EventV9::CommentAdded { object, comment })
+            }
+            EventV10::ThreadResolved { object, resolved } => {
+                self.apply_v9_event(EventV9::ThreadResolved { object, resolved })
+            }
+            EventV10::CommentDeleted { object, comment } => {
+                self.apply_v9_event(EventV9::CommentDeleted { object, comment })
+            }
+            EventV10::ObjectSet { id, object } => {
+                self.objects.insert(id, object);
+            }
+            EventV10::ObjectRemoved { id } => {
+                self.objects.remove(&id);
+                self.threads.remove(&id);
+            }
+            EventV10::Rolled(roll) => {
+                if let Some(Object::Dice { last_roll, .. }) = self.objects.get_mut(&roll.dice) {
+                    *last_roll = Some(roll);
+                }
+            }
+            EventV10::CounterIncremented { id, delta } => {
+                if let Some(Object::Counter { value, .. }) = self.objects.get_mut(&id) {
+                    *value = value.saturating_add(delta);
+                }
+            }
+            EventV10::CounterSet {
+                id,
+                value: new_value,
+            } => {
+                if let Some(Object::Counter { value, .. }) = self.objects.get_mut(&id) {
+                    *value = new_value;
+                }
+            }
+        }
+    }
+
+    /// Only for events that leave objects and textures alone, as their
+    /// representation changed in this version.
+    fn apply_v9_event(&mut self, event: EventV9) {
+        let mut board = BoardV9 {
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<v9::Object> for Object {
+    fn from(object: v9::Object) -> Self {
+        match object {
+            v9::Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            } => Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            },
+            v9::Object::Line => Object::Line,
+            v9::Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            } => Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            },
+            v9::Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            } => Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            },
+            v9::Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            } => Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            },
+            v9::Object::Dice {
+                x,
+                y,
+                notation,
+                last_roll,
+                position_timestamp,
+            } => Object::Dice {
+                x,
+                y,
+                notation,
+                last_roll,
+                position_timestamp,
+            },
+        }
+    }
+}
+
+impl From<BoardV9> for BoardV10 {
+    fn from(board: BoardV9) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board
+                .objects
+                .into_iter()
+                .map(|(id, object)| (id, object.into()))
+                .collect(),
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+        }
+    }
+}
+
+impl From<EventV9> for EventV10 {
+    fn from(event: EventV9) -> Self {
+        match event {
+            EventV9::NewImage { id, x, y, texture } => EventV10::NewImage { id, x, y, texture },
+            EventV9::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV10::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV9::SetRole { user, role } => EventV10::SetRole { user, role },
+            EventV9::ShareLinkCreated { id, link } => EventV10::ShareLinkCreated { id, link },
+            EventV9::ShareLinkRevoked { id } => EventV10::ShareLinkRevoked { id },
+            EventV9::SetSettings(settings) => EventV10::SetSettings(settings),
+            EventV9::CommentAdded { object, comment } => EventV10::CommentAdded { object, comment },
+            EventV9::ThreadResolved { object, resolved } => {
+                EventV10::ThreadResolved { object, resolved }
+            }
+            EventV9::CommentDeleted { object, comment } => {
+                EventV10::CommentDeleted { object, comment }
+            }
+            EventV9::ObjectSet { id, object } => EventV10::ObjectSet {
+                id,
+                object: object.into(),
+            },
+            EventV9::ObjectRemoved { id } => EventV10::ObjectRemoved { id },
+            EventV9::Rolled(roll) => EventV10::Rolled(roll),
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v10::{self, BoardV10, EventV10};
+
+pub use crate::store::v10::{
+    Comment, Die, GridKind, Role, Roll, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+    Deck {
+        x: f64,
+        y: f64,
+        back: u64,
+        cards: Vec<u64>,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Card {
+        x: f64,
+        y: f64,
+        face: u64,
+        back: u64,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Hand {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        owner: u64,
+        position_timestamp: Timestamp,
+    },
+    Dice {
+        x: f64,
+        y: f64,
+        notation: String,
+        last_roll: Option<Roll>,
+        position_timestamp: Timestamp,
+    },
+    Counter {
+        x: f64,
+        y: f64,
+        value: i64,
+        position_timestamp: Timestamp,
+    },
+    Stack {
+        x: f64,
+        y: f64,
+        items: Vec<u64>,
+        position_timestamp: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV11 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV11 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(Roll),
+    CounterIncremented {
+        id: u64,
+        delta: i64,
+    },
+    CounterSet {
+        id: u64,
+        value: i64,
+    },
+}
+
+impl BoardV11 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV11) {
+        match event {
+            EventV11::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV11::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => match self.objects.get_mut(&id) {
+                Some(
+                    Object::Image {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Deck {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Card {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Hand {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Dice {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Counter {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Stack {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    },
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+                Some(Object::Line) | None => {}
+            },
+            EventV11::SetRole { user, role } => {
+                self.apply_v10_event(EventV10::SetRole { user, role })
+            }
+            EventV11::ShareLinkCreated { id, link } => {
+                self.apply_v10_event(EventV10::ShareLinkCreated { id, link })
+            }
+            EventV11::ShareLinkRevoked { id } => {
+                self.apply_v10_event(EventV10::ShareLinkRevoked { id })
+            }
+            EventV11::SetSettings(settings) => {
+                self.apply_v10_event(EventV10::SetSettings(settings))
+            }
+            EventV11::CommentAdded { object, comment } => {
+                self.apply_v10_event(EventV10::CommentAdded { object, comment })
+            }
+            EventV11::ThreadResolved { object, resolved } => {
+                self.apply_v10_event(EventV10::ThreadResolved { object, resolved })
+            }
+            EventV11::CommentDeleted { object, comment } => {
+                self.apply_v10_event(EventV10::CommentDeleted { object, comment })
+            }
+            EventV11::ObjectSet { id, object } => {
+                self.objects.insert(id, object);
+            }
+            EventV11::ObjectRemoved { id } => {
+                self.objects.remove(&id);
+                self.threads.remove(&id);
+            }
+            EventV11::Rolled(roll) => {
+                if let Some(Object::Dice { last_roll, .. }) = self.objects.get_mut(&roll.dice) {
+                    *last_roll = Some(roll);
+                }
+            }
+            EventV11::CounterIncremented { id, delta } => {
+                if let Some(Object::Counter { value, .. }) = self.objects.get_mut(&id) {
+                    *value = value.saturating_add(delta);
+                }
+            }
+            EventV11::CounterSet {
+                id,
+                value: new_value,
+            } => {
+                if let Some(Object::Counter { value, .. }) = self.objects.get_mut(&id) {
+                    *value = new_value;
+                }
+            }
+        }
+    }
+
+    /// Only for events that leave objects and textures alone, as their
+    /// representation changed in this version.
+    fn apply_v10_event(&mut self, event: EventV10) {
+        let mut board = BoardV10 {
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<v10::Object> for Object {
+    fn from(object: v10::Object) -> Self {
+        match object {
+            v10::Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            } => Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            },
+            v10::Object::Line => Object::Line,
+            v10::Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            } => Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            },
+            v10::Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            } => Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            },
+            v10::Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            } => Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            },
+            v10::Object::Dice {
+                x,
+                y,
+                notation,
+                last_roll,
+                position_timestamp,
+            } => Object::Dice {
+                x,
+                y,
+                notation,
+                last_roll,
+                position_timestamp,
+            },
+            v10::Object::Counter {
+                x,
+                y,
+                value,
+                position_timestamp,
+            } => Object::Counter {
+                x,
+                y,
+                value,
+                position_timestamp,
+            },
+        }
+    }
+}
+
+impl From<BoardV10> for BoardV11 {
+    fn from(board: BoardV10) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board
+                .objects
+                .into_iter()
+                .map(|(id, object)| (id, object.into()))
+                .collect(),
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+        }
+    }
+}
+
+impl From<EventV10> for EventV11 {
+    fn from(event: EventV10) -> Self {
+        match event {
+            EventV10::NewImage { id, x, y, texture } => EventV11::NewImage { id, x, y, texture },
+            EventV10::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV11::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV10::SetRole { user, role } => EventV11::SetRole { user, role },
+            EventV10::ShareLinkCreated { id, link } => EventV11::ShareLinkCreated { id, link },
+            EventV10::ShareLinkRevoked { id } => EventV11::ShareLinkRevoked { id },
+            EventV10::SetSettings(settings) => EventV11::SetSettings(settings),
+            EventV10::CommentAdded { object, comment } => {
+                EventV11::CommentAdded { object, comment }
+            }
+            EventV10::ThreadResolved { object, resolved } => {
+                EventV11::ThreadResolved { object, resolved }
+            }
+            EventV10::CommentDeleted { object, comment } => {
+                EventV11::CommentDeleted { object, comment }
+            }
+            EventV10::ObjectSet { id, object } => EventV11::ObjectSet {
+                id,
+                object: object.into(),
+            },
+            EventV10::ObjectRemoved { id } => EventV11::ObjectRemoved { id },
+            EventV10::Rolled(roll) => EventV11::Rolled(roll),
+            EventV10::CounterIncremented { id, delta } => {
+                EventV11::CounterIncremented { id, delta }
+            }
+            EventV10::CounterSet { id, value } => EventV11::CounterSet { id, value },
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v11::{BoardV11, EventV11};
+
+pub use crate::store::v11::{
+    Comment, Die, GridKind, Object, Role, Roll, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV12 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV12 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(Roll),
+    CounterIncremented {
+        id: u64,
+        delta: i64,
+    },
+    CounterSet {
+        id: u64,
+        value: i64,
+    },
+    TextureAdded {
+        id: u64,
+        data: Vec<u8>,
+    },
+}
+
+impl BoardV12 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV12) {
+        match event {
+            EventV12::NewImage { id, x, y, texture } => {
+                self.apply_v11_event(EventV11::NewImage { id, x, y, texture })
+            }
+            EventV12::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => self.apply_v11_event(EventV11::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            }),
+            EventV12::SetRole { user, role } => {
+                self.apply_v11_event(EventV11::SetRole { user, role })
+            }
+            EventV12::ShareLinkCreated { id, link } => {
+                self.apply_v11_event(EventV11::ShareLinkCreated { id, link })
+            }
+            EventV12::ShareLinkRevoked { id } => {
+                self.apply_v11_event(EventV11::ShareLinkRevoked { id })
+            }
+            EventV12::SetSettings(settings) => {
+                self.apply_v11_event(EventV11::SetSettings(settings))
+            }
+            EventV12::CommentAdded { object, comment } => {
+                self.apply_v11_event(EventV11::CommentAdded { object, comment })
+            }
+            EventV12::ThreadResolved { object, resolved } => {
+                self.apply_v11_event(EventV11::ThreadResolved { object, resolved })
+            }
+            EventV12::CommentDeleted { object, comment } => {
+                self.apply_v11_event(EventV11::CommentDeleted { object, comment })
+            }
+            EventV12::ObjectSet { id, object } => {
+                self.apply_v11_event(EventV11::ObjectSet { id, object })
+            }
+            EventV12::ObjectRemoved { id } => self.apply_v11_event(EventV11::ObjectRemoved { id }),
+            EventV12::Rolled(roll) => self.apply_v11_event(EventV11::Rolled(roll)),
+            EventV12::CounterIncremented { id, delta } => {
+                self.apply_v11_event(EventV11::CounterIncremented { id, delta })
+            }
+            EventV12::CounterSet { id, value } => {
+                self.apply_v11_event(EventV11::CounterSet { id, value })
+            }
+            EventV12::TextureAdded { id, data } => {
+                self.textures.insert(id, data);
+            }
+        }
+    }
+
+    fn apply_v11_event(&mut self, event: EventV11) {
+        let mut board = BoardV11 {
+            textures: std::mem::take(&mut self.textures),
+            objects: std::mem::take(&mut self.objects),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.textures = board.textures;
+        self.objects = board.objects;
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<BoardV11> for BoardV12 {
+    fn from(board: BoardV11) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board.objects,
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+        }
+    }
+}
+
+impl From<EventV11> for EventV12 {
+    fn from(event: EventV11) -> Self {
+        match event {
+            EventV11::NewImage { id, x, y, texture } => EventV12::NewImage { id, x, y, texture },
+            EventV11::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV12::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV11::SetRole { user, role } => EventV12::SetRole { user, role },
+            EventV11::ShareLinkCreated { id, link } => EventV12::ShareLinkCreated { id, link },
+            EventV11::ShareLinkRevoked { id } => EventV12::ShareLinkRevoked { id },
+            EventV11::SetSettings(settings) => EventV12::SetSettings(settings),
+            EventV11::CommentAdded { object, comment } => {
+                EventV12::CommentAdded { object, comment }
+            }
+            EventV11::ThreadResolved { object, resolved } => {
+                EventV12::ThreadResolved { object, resolved }
+            }
+            EventV11::CommentDeleted { object, comment } => {
+                EventV12::CommentDeleted { object, comment }
+            }
+            EventV11::ObjectSet { id, object } => EventV12::ObjectSet { id, object },
+            EventV11::ObjectRemoved { id } => EventV12::ObjectRemoved { id },
+            EventV11::Rolled(roll) => EventV12::Rolled(roll),
+            EventV11::CounterIncremented { id, delta } => {
+                EventV12::CounterIncremented { id, delta }
+            }
+            EventV11::CounterSet { id, value } => EventV12::CounterSet { id, value },
+        }
+    }
+}
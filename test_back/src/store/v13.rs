@@ -0,0 +1,473 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v12::{self, BoardV12, EventV12};
+
+pub use crate::store::v12::{
+    Comment, Die, GridKind, Role, Roll, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+    Deck {
+        x: f64,
+        y: f64,
+        back: u64,
+        cards: Vec<u64>,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Card {
+        x: f64,
+        y: f64,
+        face: u64,
+        back: u64,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Hand {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        owner: u64,
+        position_timestamp: Timestamp,
+    },
+    Dice {
+        x: f64,
+        y: f64,
+        notation: String,
+        last_roll: Option<Roll>,
+        position_timestamp: Timestamp,
+    },
+    Counter {
+        x: f64,
+        y: f64,
+        value: i64,
+        position_timestamp: Timestamp,
+    },
+    Stack {
+        x: f64,
+        y: f64,
+        items: Vec<u64>,
+        position_timestamp: Timestamp,
+    },
+    Fog {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        position_timestamp: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV13 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+    pub hidden: HashSet<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV13 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(Roll),
+    CounterIncremented {
+        id: u64,
+        delta: i64,
+    },
+    CounterSet {
+        id: u64,
+        value: i64,
+    },
+    TextureAdded {
+        id: u64,
+        data: Vec<u8>,
+    },
+    SetHidden {
+        id: u64,
+        hidden: bool,
+    },
+}
+
+impl BoardV13 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV13) {
+        match event {
+            EventV13::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV13::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => match self.objects.get_mut(&id) {
+                Some(
+                    Object::Image {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Deck {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Card {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Hand {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Dice {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Counter {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Stack {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Fog {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    },
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+                Some(Object::Line) | None => {}
+            },
+            EventV13::SetRole { user, role } => {
+                self.apply_v12_event(EventV12::SetRole { user, role })
+            }
+            EventV13::ShareLinkCreated { id, link } => {
+                self.apply_v12_event(EventV12::ShareLinkCreated { id, link })
+            }
+            EventV13::ShareLinkRevoked { id } => {
+                self.apply_v12_event(EventV12::ShareLinkRevoked { id })
+            }
+            EventV13::SetSettings(settings) => {
+                self.apply_v12_event(EventV12::SetSettings(settings))
+            }
+            EventV13::CommentAdded { object, comment } => {
+                self.apply_v12_event(EventV12::CommentAdded { object, comment })
+            }
+            EventV13::ThreadResolved { object, resolved } => {
+                self.apply_v12_event(EventV12::ThreadResolved { object, resolved })
+            }
+            EventV13::CommentDeleted { object, comment } => {
+                self.apply_v12_event(EventV12::CommentDeleted { object, comment })
+            }
+            EventV13::ObjectSet { id, object } => {
+                self.objects.insert(id, object);
+            }
+            EventV13::ObjectRemoved { id } => {
+                self.objects.remove(&id);
+                self.threads.remove(&id);
+                self.hidden.remove(&id);
+            }
+            EventV13::Rolled(roll) => {
+                if let Some(Object::Dice { last_roll, .. }) = self.objects.get_mut(&roll.dice) {
+                    *last_roll = Some(roll);
+                }
+            }
+            EventV13::CounterIncremented { id, delta } => {
+                if let Some(Object::Counter { value, .. }) = self.objects.get_mut(&id) {
+                    *value = value.saturating_add(delta);
+                }
+            }
+            EventV13::CounterSet {
+                id,
+                value: new_value,
+            } => {
+                if let Some(Object::Counter { value, .. }) = self.objects.get_mut(&id) {
+                    *value = new_value;
+                }
+            }
+            EventV13::TextureAdded { id, data } => {
+                self.textures.insert(id, data);
+            }
+            EventV13::SetHidden { id, hidden } => {
+                if hidden {
+                    self.hidden.insert(id);
+                } else {
+                    self.hidden.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Only for events that leave objects and textures alone, as their
+    /// representation changed in this version.
+    fn apply_v12_event(&mut self, event: EventV12) {
+        let mut board = BoardV12 {
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<v12::Object> for Object {
+    fn from(object: v12::Object) -> Self {
+        match object {
+            v12::Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            } => Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            },
+            v12::Object::Line => Object::Line,
+            v12::Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            } => Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            },
+            v12::Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            } => Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            },
+            v12::Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            } => Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            },
+            v12::Object::Dice {
+                x,
+                y,
+                notation,
+                last_roll,
+                position_timestamp,
+            } => Object::Dice {
+                x,
+                y,
+                notation,
+                last_roll,
+                position_timestamp,
+            },
+            v12::Object::Counter {
+                x,
+                y,
+                value,
+                position_timestamp,
+            } => Object::Counter {
+                x,
+                y,
+                value,
+                position_timestamp,
+            },
+            v12::Object::Stack {
+                x,
+                y,
+                items,
+                position_timestamp,
+            } => Object::Stack {
+                x,
+                y,
+                items,
+                position_timestamp,
+            },
+        }
+    }
+}
+
+impl From<BoardV12> for BoardV13 {
+    fn from(board: BoardV12) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board
+                .objects
+                .into_iter()
+                .map(|(id, object)| (id, object.into()))
+                .collect(),
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+            hidden: HashSet::new(),
+        }
+    }
+}
+
+impl From<EventV12> for EventV13 {
+    fn from(event: EventV12) -> Self {
+        match event {
+            EventV12::NewImage { id, x, y, texture } => EventV13::NewImage { id, x, y, texture },
+            EventV12::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV13::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV12::SetRole { user, role } => EventV13::SetRole { user, role },
+            EventV12::ShareLinkCreated { id, link } => EventV13::ShareLinkCreated { id, link },
+            EventV12::ShareLinkRevoked { id } => EventV13::ShareLinkRevoked { id },
+            EventV12::SetSettings(settings) => EventV13::SetSettings(settings),
+            EventV12::CommentAdded { object, comment } => {
+                EventV13::CommentAdded { object, comment }
+            }
+            EventV12::ThreadResolved { object, resolved } => {
+                EventV13::ThreadResolved { object, resolved }
+            }
+            EventV12::CommentDeleted { object, comment } => {
+                EventV13::CommentDeleted { object, comment }
+            }
+            EventV12::ObjectSet { id, object } => EventV13::ObjectSet {
+                id,
+                object: object.into(),
+            },
+            EventV12::ObjectRemoved { id } => EventV13::ObjectRemoved { id },
+            EventV12::Rolled(roll) => EventV13::Rolled(roll),
+            EventV12::CounterIncremented { id, delta } => {
+                EventV13::CounterIncremented { id, delta }
+            }
+            EventV12::CounterSet { id, value } => EventV13::CounterSet { id, value },
+            EventV12::TextureAdded { id, data } => EventV13::TextureAdded { id, data },
+        }
+    }
+}
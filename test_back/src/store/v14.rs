@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v13::{BoardV13, EventV13};
+
+pub use crate::store::v13::{
+    Comment, Die, GridKind, Object, Role, Roll, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Seating {
+    pub seats: Vec<Option<u64>>,
+    pub turn: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV14 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+    pub hidden: HashSet<u64>,
+    pub seating: Seating,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV14 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(Roll),
+    CounterIncremented {
+        id: u64,
+        delta: i64,
+    },
+    CounterSet {
+        id: u64,
+        value: i64,
+    },
+    TextureAdded {
+        id: u64,
+        data: Vec<u8>,
+    },
+    SetHidden {
+        id: u64,
+        hidden: bool,
+    },
+    SetSeating(Seating),
+}
+
+impl BoardV14 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV14) {
+        match event {
+            EventV14::NewImage { id, x, y, texture } => {
+                self.apply_v13_event(EventV13::NewImage { id, x, y, texture })
+            }
+            EventV14::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => self.apply_v13_event(EventV13::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            }),
+            EventV14::SetRole { user, role } => {
+                self.apply_v13_event(EventV13::SetRole { user, role })
+            }
+            EventV14::ShareLinkCreated { id, link } => {
+                self.apply_v13_event(EventV13::ShareLinkCreated { id, link })
+            }
+            EventV14::ShareLinkRevoked { id } => {
+                self.apply_v13_event(EventV13::ShareLinkRevoked { id })
+            }
+            EventV14::SetSettings(settings) => {
+                self.apply_v13_event(EventV13::SetSettings(settings))
+            }
+            EventV14::CommentAdded { object, comment } => {
+                self.apply_v13_event(EventV13::CommentAdded { object, comment })
+            }
+            EventV14::ThreadResolved { object, resolved } => {
+                self.apply_v13_event(EventV13::ThreadResolved { object, resolved })
+            }
+            EventV14::CommentDeleted { object, comment } => {
+                self.apply_v13_event(EventV13::CommentDeleted { object, comment })
+            }
+            EventV14::ObjectSet { id, object } => {
+                self.apply_v13_event(EventV13::ObjectSet { id, object })
+            }
+            EventV14::ObjectRemoved { id } => self.apply_v13_event(EventV13::ObjectRemoved { id }),
+            EventV14::Rolled(roll) => self.apply_v13_event(EventV13::Rolled(roll)),
+            EventV14::CounterIncremented { id, delta } => {
+                self.apply_v13_event(EventV13::CounterIncremented { id, delta })
+            }
+            EventV14::CounterSet { id, value } => {
+                self.apply_v13_event(EventV13::CounterSet { id, value })
+            }
+            EventV14::TextureAdded { id, data } => {
+                self.apply_v13_event(EventV13::TextureAdded { id, data })
+            }
+            EventV14::SetHidden { id, hidden } => {
+                self.apply_v13_event(EventV13::SetHidden { id, hidden })
+            }
+            EventV14::SetSeating(seating) => self.seating = seating,
+        }
+    }
+
+    fn apply_v13_event(&mut self, event: EventV13) {
+        let mut board = BoardV13 {
+            textures: std::mem::take(&mut self.textures),
+            objects: std::mem::take(&mut self.objects),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+            hidden: std::mem::take(&mut self.hidden),
+        };
+        board.apply_event(event);
+        self.textures = board.textures;
+        self.objects = board.objects;
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+        self.hidden = board.hidden;
+    }
+}
+
+impl From<BoardV13> for BoardV14 {
+    fn from(board: BoardV13) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board.objects,
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+            hidden: board.hidden,
+            seating: Seating::default(),
+        }
+    }
+}
+
+impl From<EventV13> for EventV14 {
+    fn from(event: EventV13) -> Self {
+        match event {
+            EventV13::NewImage { id, x, y, texture } => EventV14::NewImage { id, x, y, texture },
+            EventV13::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV14::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV13::SetRole { user, role } => EventV14::SetRole { user, role },
+            EventV13::ShareLinkCreated { id, link } => EventV14::ShareLinkCreated { id, link },
+            EventV13::ShareLinkRevoked { id } => EventV14::ShareLinkRevoked { id },
+            EventV13::SetSettings(settings) => EventV14::SetSettings(settings),
+            EventV13::CommentAdded { object, comment } => {
+                EventV14::CommentAdded { object, comment }
+            }
+            EventV13::ThreadResolved { object, resolved } => {
+                EventV14::ThreadResolved { object, resolved }
+            }
+            EventV13::CommentDeleted { object, comment } => {
+                EventV14::CommentDeleted { object, comment }
+            }
+            EventV13::ObjectSet { id, object } => EventV14::ObjectSet { id, object },
+            EventV13::ObjectRemoved { id } => EventV14::ObjectRemoved { id },
+            EventV13::Rolled(roll) => EventV14::Rolled(roll),
+            EventV13::CounterIncremented { id, delta } => {
+                EventV14::CounterIncremented { id, delta }
+            }
+            EventV13::CounterSet { id, value } => EventV14::CounterSet { id, value },
+            EventV13::TextureAdded { id, data } => EventV14::TextureAdded { id, data },
+            EventV13::SetHidden { id, hidden } => EventV14::SetHidden { id, hidden },
+        }
+    }
+}
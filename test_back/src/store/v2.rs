@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v1::{self, BoardV1, EventV1};
+
+pub use crate::store::v1::Texture;
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct Timestamp {
+    pub time: u64,
+    pub writer: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV2 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV2 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+}
+
+impl BoardV2 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV2) {
+        match event {
+            EventV2::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV2::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => {
+                if let Some(Object::Image {
+                    x,
+                    y,
+                    position_timestamp,
+                    ..
+                }) = self.objects.get_mut(&id)
+                {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+            }
+        }
+    }
+}
+
+impl From<BoardV1> for BoardV2 {
+    fn from(board: BoardV1) -> Self {
+        let objects = board
+            .objects
+            .into_iter()
+            .map(|(id, object)| {
+                let object = match object {
+                    v1::Object::Image { x, y, texture_id } => Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                    v1::Object::Line => Object::Line,
+                };
+                (id, object)
+            })
+            .collect();
+        Self {
+            textures: board.textures,
+            objects,
+        }
+    }
+}
+
+impl From<EventV1> for EventV2 {
+    fn from(event: EventV1) -> Self {
+        match event {
+            EventV1::NewImage { id, x, y, texture } => EventV2::NewImage { id, x, y, texture },
+        }
+    }
+}
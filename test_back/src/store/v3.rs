@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v2::{BoardV2, EventV2};
+
+pub use crate::store::v2::{Object, Texture, Timestamp};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum Role {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV3 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV3 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+}
+
+impl BoardV3 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV3) {
+        match event {
+            EventV3::NewImage { id, x, y, texture } => {
+                self.apply_v2_event(EventV2::NewImage { id, x, y, texture })
+            }
+            EventV3::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => self.apply_v2_event(EventV2::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            }),
+            EventV3::SetRole { user, role } => match role {
+                Some(role) => {
+                    self.roles.insert(user, role);
+                }
+                None => {
+                    self.roles.remove(&user);
+                }
+            },
+        }
+    }
+
+    fn apply_v2_event(&mut self, event: EventV2) {
+        let mut board = BoardV2 {
+            textures: std::mem::take(&mut self.textures),
+            objects: std::mem::take(&mut self.objects),
+        };
+        board.apply_event(event);
+        self.textures = board.textures;
+        self.objects = board.objects;
+    }
+}
+
+impl From<BoardV2> for BoardV3 {
+    fn from(board: BoardV2) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board.objects,
+            roles: HashMap::new(),
+        }
+    }
+}
+
+impl From<EventV2> for EventV3 {
+    fn from(event: EventV2) -> Self {
+        match event {
+            EventV2::NewImage { id, x, y, texture } => EventV3::NewImage { id, x, y, texture },
+            EventV2::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV3::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+        }
+    }
+}
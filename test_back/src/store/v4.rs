@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v3::{BoardV3, EventV3};
+
+pub use crate::store::v3::{Object, Role, Texture, Timestamp};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ShareLink {
+    pub role: Role,
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV4 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV4 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+}
+
+impl BoardV4 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV4) {
+        match event {
+            EventV4::NewImage { id, x, y, texture } => {
+                self.apply_v3_event(EventV3::NewImage { id, x, y, texture })
+            }
+            EventV4::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => self.apply_v3_event(EventV3::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            }),
+            EventV4::SetRole { user, role } => self.apply_v3_event(EventV3::SetRole { user, role }),
+            EventV4::ShareLinkCreated { id, link } => {
+                self.share_links.insert(id, link);
+            }
+            EventV4::ShareLinkRevoked { id } => {
+                self.share_links.remove(&id);
+            }
+        }
+    }
+
+    fn apply_v3_event(&mut self, event: EventV3) {
+        let mut board = BoardV3 {
+            textures: std::mem::take(&mut self.textures),
+            objects: std::mem::take(&mut self.objects),
+            roles: std::mem::take(&mut self.roles),
+        };
+        board.apply_event(event);
+        self.textures = board.textures;
+        self.objects = board.objects;
+        self.roles = board.roles;
+    }
+}
+
+impl From<BoardV3> for BoardV4 {
+    fn from(board: BoardV3) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board.objects,
+            roles: board.roles,
+            share_links: HashMap::new(),
+        }
+    }
+}
+
+impl From<EventV3> for EventV4 {
+    fn from(event: EventV3) -> Self {
+        match event {
+            EventV3::NewImage { id, x, y, texture } => EventV4::NewImage { id, x, y, texture },
+            EventV3::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV4::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV3::SetRole { user, role } => EventV4::SetRole { user, role },
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v4::{BoardV4, EventV4};
+
+pub use crate::store::v4::{Object, Role, ShareLink, Texture, Timestamp};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub enum GridKind {
+    #[default]
+    None,
+    Square,
+    Hex,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Settings {
+    pub background: u32,
+    pub grid: GridKind,
+    pub grid_size: f64,
+    pub snap_to_grid: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            background: 0xffffff,
+            grid: GridKind::None,
+            grid_size: 64.0,
+            snap_to_grid: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV5 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV5 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+}
+
+impl BoardV5 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV5) {
+        match event {
+            EventV5::NewImage { id, x, y, texture } => {
+                self.apply_v4_event(EventV4::NewImage { id, x, y, texture })
+            }
+            EventV5::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => self.apply_v4_event(EventV4::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            }),
+            EventV5::SetRole { user, role } => self.apply_v4_event(EventV4::SetRole { user, role }),
+            EventV5::ShareLinkCreated { id, link } => {
+                self.apply_v4_event(EventV4::ShareLinkCreated { id, link })
+            }
+            EventV5::ShareLinkRevoked { id } => {
+                self.apply_v4_event(EventV4::ShareLinkRevoked { id })
+            }
+            EventV5::SetSettings(settings) => self.settings = settings,
+        }
+    }
+
+    fn apply_v4_event(&mut self, event: EventV4) {
+        let mut board = BoardV4 {
+            textures: std::mem::take(&mut self.textures),
+            objects: std::mem::take(&mut self.objects),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+        };
+        board.apply_event(event);
+        self.textures = board.textures;
+        self.objects = board.objects;
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+    }
+}
+
+impl From<BoardV4> for BoardV5 {
+    fn from(board: BoardV4) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board.objects,
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: Settings::default(),
+        }
+    }
+}
+
+impl From<EventV4> for EventV5 {
+    fn from(event: EventV4) -> Self {
+        match event {
+            EventV4::NewImage { id, x, y, texture } => EventV5::NewImage { id, x, y, texture },
+            EventV4::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV5::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV4::SetRole { user, role } => EventV5::SetRole { user, role },
+            EventV4::ShareLinkCreated { id, link } => EventV5::ShareLinkCreated { id, link },
+            EventV4::ShareLinkRevoked { id } => EventV5::ShareLinkRevoked { id },
+        }
+    }
+}
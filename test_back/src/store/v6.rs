@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v5::{BoardV5, EventV5};
+
+pub use crate::store::v5::{GridKind, Object, Role, Settings, ShareLink, Texture, Timestamp};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Comment {
+    pub id: u64,
+    pub author: u64,
+    pub author_name: String,
+    pub text: String,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Thread {
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV6 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV6 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+}
+
+impl BoardV6 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV6) {
+        match event {
+            EventV6::NewImage { id, x, y, texture } => {
+                self.apply_v5_event(EventV5::NewImage { id, x, y, texture })
+            }
+            EventV6::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => self.apply_v5_event(EventV5::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            }),
+            EventV6::SetRole { user, role } => self.apply_v5_event(EventV5::SetRole { user, role }),
+            EventV6::ShareLinkCreated { id, link } => {
+                self.apply_v5_event(EventV5::ShareLinkCreated { id, link })
+            }
+            EventV6::ShareLinkRevoked { id } => {
+                self.apply_v5_event(EventV5::ShareLinkRevoked { id })
+            }
+            EventV6::SetSettings(settings) => self.apply_v5_event(EventV5::SetSettings(settings)),
+            EventV6::CommentAdded { object, comment } => {
+                self.threads
+                    .entry(object)
+                    .or_default()
+                    .comments
+                    .push(comment);
+            }
+            EventV6::ThreadResolved { object, resolved } => {
+                if let Some(thread) = self.threads.get_mut(&object) {
+                    thread.resolved = resolved;
+                }
+            }
+            EventV6::CommentDeleted { object, comment } => {
+                if let Some(thread) = self.threads.get_mut(&object) {
+                    thread.comments.retain(|c| c.id != comment);
+                    if thread.comments.is_empty() {
+                        self.threads.remove(&object);
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_v5_event(&mut self, event: EventV5) {
+        let mut board = BoardV5 {
+            textures: std::mem::take(&mut self.textures),
+            objects: std::mem::take(&mut self.objects),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+        };
+        board.apply_event(event);
+        self.textures = board.textures;
+        self.objects = board.objects;
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+    }
+}
+
+impl From<BoardV5> for BoardV6 {
+    fn from(board: BoardV5) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board.objects,
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: HashMap::new(),
+        }
+    }
+}
+
+impl From<EventV5> for EventV6 {
+    fn from(event: EventV5) -> Self {
+        match event {
+            EventV5::NewImage { id, x, y, texture } => EventV6::NewImage { id, x, y, texture },
+            EventV5::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV6::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV5::SetRole { user, role } => EventV6::SetRole { user, role },
+            EventV5::ShareLinkCreated { id, link } => EventV6::ShareLinkCreated { id, link },
+            EventV5::ShareLinkRevoked { id } => EventV6::ShareLinkRevoked { id },
+            EventV5::SetSettings(settings) => EventV6::SetSettings(settings),
+        }
+    }
+}
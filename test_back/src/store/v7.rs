@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v6::{self, BoardV6, EventV6};
+
+pub use crate::store::v6::{
+    Comment, GridKind, Role, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+    Deck {
+        x: f64,
+        y: f64,
+        back: u64,
+        cards: Vec<u64>,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Card {
+        x: f64,
+        y: f64,
+        face: u64,
+        back: u64,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV7 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV7 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+}
+
+impl BoardV7 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV7) {
+        match event {
+            EventV7::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV7::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => match self.objects.get_mut(&id) {
+                Some(
+                    Object::Image {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Deck {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Card {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    },
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+                Some(Object::Line) | None => {}
+            },
+            EventV7::SetRole { user, role } => self.apply_v6_event(EventV6::SetRole { user, role }),
+            EventV7::ShareLinkCreated { id, link } => {
+                self.apply_v6_event(EventV6::ShareLinkCreated { id, link })
+            }
+            EventV7::ShareLinkRevoked { id } => {
+                self.apply_v6_event(EventV6::ShareLinkRevoked { id })
+            }
+            EventV7::SetSettings(settings) => self.apply_v6_event(EventV6::SetSettings(settings)),
+            EventV7::CommentAdded { object, comment } => {
+                self.apply_v6_event(EventV6::CommentAdded { object, comment })
+            }
+            EventV7::ThreadResolved { object, resolved } => {
+                self.apply_v6_event(EventV6::ThreadResolved { object, resolved })
+            }
+            EventV7::CommentDeleted { object, comment } => {
+                self.apply_v6_event(EventV6::CommentDeleted { object, comment })
+            }
+            EventV7::ObjectSet { id, object } => {
+                self.objects.insert(id, object);
+            }
+            EventV7::ObjectRemoved { id } => {
+                self.objects.remove(&id);
+                self.threads.remove(&id);
+            }
+        }
+    }
+
+    /// Only for events that leave objects and textures alone, as their
+    /// representation changed in this version.
+    fn apply_v6_event(&mut self, event: EventV6) {
+        let mut board = BoardV6 {
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<v6::Object> for Object {
+    fn from(object: v6::Object) -> Self {
+        match object {
+            v6::Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            } => Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            },
+            v6::Object::Line => Object::Line,
+        }
+    }
+}
+
+impl From<BoardV6> for BoardV7 {
+    fn from(board: BoardV6) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board
+                .objects
+                .into_iter()
+                .map(|(id, object)| (id, object.into()))
+                .collect(),
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+        }
+    }
+}
+
+impl From<EventV6> for EventV7 {
+    fn from(event: EventV6) -> Self {
+        match event {
+            EventV6::NewImage { id, x, y, texture } => EventV7::NewImage { id, x, y, texture },
+            EventV6::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV7::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV6::SetRole { user, role } => EventV7::SetRole { user, role },
+            EventV6::ShareLinkCreated { id, link } => EventV7::ShareLinkCreated { id, link },
+            EventV6::ShareLinkRevoked { id } => EventV7::ShareLinkRevoked { id },
+            EventV6::SetSettings(settings) => EventV7::SetSettings(settings),
+            EventV6::CommentAdded { object, comment } => EventV7::CommentAdded { object, comment },
+            EventV6::ThreadResolved { object, resolved } => {
+                EventV7::ThreadResolved { object, resolved }
+            }
+            EventV6::CommentDeleted { object, comment } => {
+                EventV7::CommentDeleted { object, comment }
+            }
+        }
+    }
+}
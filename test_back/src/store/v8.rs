@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v7::{self, BoardV7, EventV7};
+
+pub use crate::store::v7::{
+    Comment, GridKind, Role, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+    Deck {
+        x: f64,
+        y: f64,
+        back: u64,
+        cards: Vec<u64>,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Card {
+        x: f64,
+        y: f64,
+        face: u64,
+        back: u64,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Hand {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        owner: u64,
+        position_timestamp: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV8 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV8 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+}
+
+impl BoardV8 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV8) {
+        match event {
+            EventV8::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV8::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => match self.objects.get_mut(&id) {
+                Some(
+                    Object::Image {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Deck {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Card {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Hand {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    },
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+                Some(Object::Line) | None => {}
+            },
+            EventV8::SetRole { user, role } => self.apply_v7_event(EventV7::SetRole { user, role }),
+            EventV8::ShareLinkCreated { id, link } => {
+                self.apply_v7_event(EventV7::ShareLinkCreated { id, link })
+            }
+            EventV8::ShareLinkRevoked { id } => {
+                self.apply_v7_event(EventV7::ShareLinkRevoked { id })
+            }
+            EventV8::SetSettings(settings) => self.apply_v7_event(EventV7::SetSettings(settings)),
+            EventV8::CommentAdded { object, comment } => {
+                self.apply_v7_event(EventV7::CommentAdded { object, comment })
+            }
+            EventV8::ThreadResolved { object, resolved } => {
+                self.apply_v7_event(EventV7::ThreadResolved { object, resolved })
+            }
+            EventV8::CommentDeleted { object, comment } => {
+                self.apply_v7_event(EventV7::CommentDeleted { object, comment })
+            }
+            EventV8::ObjectSet { id, object } => {
+                self.objects.insert(id, object);
+            }
+            EventV8::ObjectRemoved { id } => {
+                self.objects.remove(&id);
+                self.threads.remove(&id);
+            }
+        }
+    }
+
+    /// Only for events that leave objects and textures alone, as their
+    /// representation changed in this version.
+    fn apply_v7_event(&mut self, event: EventV7) {
+        let mut board = BoardV7 {
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<v7::Object> for Object {
+    fn from(object: v7::Object) -> Self {
+        match object {
+            v7::Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            } => Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            },
+            v7::Object::Line => Object::Line,
+            v7::Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            } => Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            },
+            v7::Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            } => Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            },
+        }
+    }
+}
+
+impl From<BoardV7> for BoardV8 {
+    fn from(board: BoardV7) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board
+                .objects
+                .into_iter()
+                .map(|(id, object)| (id, object.into()))
+                .collect(),
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+        }
+    }
+}
+
+impl From<EventV7> for EventV8 {
+    fn from(event: EventV7) -> Self {
+        match event {
+            EventV7::NewImage { id, x, y, texture } => EventV8::NewImage { id, x, y, texture },
+            EventV7::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV8::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV7::SetRole { user, role } => EventV8::SetRole { user, role },
+            EventV7::ShareLinkCreated { id, link } => EventV8::ShareLinkCreated { id, link },
+            EventV7::ShareLinkRevoked { id } => EventV8::ShareLinkRevoked { id },
+            EventV7::SetSettings(settings) => EventV8::SetSettings(settings),
+            EventV7::CommentAdded { object, comment } => EventV8::CommentAdded { object, comment },
+            EventV7::ThreadResolved { object, resolved } => {
+                EventV8::ThreadResolved { object, resolved }
+            }
+            EventV7::CommentDeleted { object, comment } => {
+                EventV8::CommentDeleted { object, comment }
+            }
+            EventV7::ObjectSet { id, object } => EventV8::ObjectSet {
+                id,
+                object: object.into(),
+            },
+            EventV7::ObjectRemoved { id } => EventV8::ObjectRemoved { id },
+        }
+    }
+}
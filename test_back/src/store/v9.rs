@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::v8::{self, BoardV8, EventV8};
+
+pub use crate::store::v8::{
+    Comment, GridKind, Role, Settings, ShareLink, Texture, Thread, Timestamp,
+};
+
+// DO NOT CHANGE AFTER RELEASE
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Object {
+    Image {
+        x: f64,
+        y: f64,
+        texture_id: u64,
+        position_timestamp: Timestamp,
+    },
+    Line,
+    Deck {
+        x: f64,
+        y: f64,
+        back: u64,
+        cards: Vec<u64>,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Card {
+        x: f64,
+        y: f64,
+        face: u64,
+        back: u64,
+        face_up: bool,
+        position_timestamp: Timestamp,
+    },
+    Hand {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        owner: u64,
+        position_timestamp: Timestamp,
+    },
+    Dice {
+        x: f64,
+        y: f64,
+        notation: String,
+        last_roll: Option<Roll>,
+        position_timestamp: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Die {
+    pub value: u32,
+    pub kept: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Roll {
+    pub dice: u64,
+    pub roller: u64,
+    pub roller_name: String,
+    pub notation: String,
+    pub results: Vec<Die>,
+    pub modifier: i64,
+    pub total: i64,
+    pub rolled_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BoardV9 {
+    pub textures: HashMap<u64, Vec<u8>>,
+    pub objects: HashMap<u64, Object>,
+    pub roles: HashMap<u64, Role>,
+    pub share_links: HashMap<u64, ShareLink>,
+    pub settings: Settings,
+    pub threads: HashMap<u64, Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum EventV9 {
+    NewImage {
+        id: u64,
+        x: f64,
+        y: f64,
+        texture: Texture,
+    },
+    Move {
+        id: u64,
+        x: f64,
+        y: f64,
+        timestamp: Timestamp,
+    },
+    SetRole {
+        user: u64,
+        role: Option<Role>,
+    },
+    ShareLinkCreated {
+        id: u64,
+        link: ShareLink,
+    },
+    ShareLinkRevoked {
+        id: u64,
+    },
+    SetSettings(Settings),
+    CommentAdded {
+        object: u64,
+        comment: Comment,
+    },
+    ThreadResolved {
+        object: u64,
+        resolved: bool,
+    },
+    CommentDeleted {
+        object: u64,
+        comment: u64,
+    },
+    ObjectSet {
+        id: u64,
+        object: Object,
+    },
+    ObjectRemoved {
+        id: u64,
+    },
+    Rolled(Roll),
+}
+
+impl BoardV9 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply_event(&mut self, event: EventV9) {
+        match event {
+            EventV9::NewImage { id, x, y, texture } => {
+                let texture_id = match texture {
+                    Texture::New { id: tex_id, data } => {
+                        self.textures.insert(tex_id, data);
+                        tex_id
+                    }
+                    Texture::Existing { id: tex_id } => tex_id,
+                };
+                self.objects.insert(
+                    id,
+                    Object::Image {
+                        x,
+                        y,
+                        texture_id,
+                        position_timestamp: Timestamp::default(),
+                    },
+                );
+            }
+            EventV9::Move {
+                id,
+                x: new_x,
+                y: new_y,
+                timestamp,
+            } => match self.objects.get_mut(&id) {
+                Some(
+                    Object::Image {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Deck {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Card {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Hand {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    }
+                    | Object::Dice {
+                        x,
+                        y,
+                        position_timestamp,
+                        ..
+                    },
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                    *position_timestamp = timestamp;
+                }
+                Some(Object::Line) | None => {}
+            },
+            EventV9::SetRole { user, role } => self.apply_v8_event(EventV8::SetRole { user, role }),
+            EventV9::ShareLinkCreated { id, link } => {
+                self.apply_v8_event(EventV8::ShareLinkCreated { id, link })
+            }
+            EventV9::ShareLinkRevoked { id } => {
+                self.apply_v8_event(EventV8::ShareLinkRevoked { id })
+            }
+            EventV9::SetSettings(settings) => self.apply_v8_event(EventV8::SetSettings(settings)),
+            EventV9::CommentAdded { object, comment } => {
+                self.apply_v8_event(EventV8::CommentAdded { object, comment })
+            }
+            EventV9::ThreadResolved { object, resolved } => {
+                self.apply_v8_event(EventV8::ThreadResolved { object, resolved })
+            }
+            EventV9::CommentDeleted { object, comment } => {
+                self.apply_v8_event(EventV8::CommentDeleted { object, comment })
+            }
+            EventV9::ObjectSet { id, object } => {
+                self.objects.insert(id, object);
+            }
+            EventV9::ObjectRemoved { id } => {
+                self.objects.remove(&id);
+                self.threads.remove(&id);
+            }
+            EventV9::Rolled(roll) => {
+                if let Some(Object::Dice { last_roll, .. }) = self.objects.get_mut(&roll.dice) {
+                    *last_roll = Some(roll);
+                }
+            }
+        }
+    }
+
+    /// Only for events that leave objects and textures alone, as their
+    /// representation changed in this version.
+    fn apply_v8_event(&mut self, event: EventV8) {
+        let mut board = BoardV8 {
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            roles: std::mem::take(&mut self.roles),
+            share_links: std::mem::take(&mut self.share_links),
+            settings: self.settings,
+            threads: std::mem::take(&mut self.threads),
+        };
+        board.apply_event(event);
+        self.roles = board.roles;
+        self.share_links = board.share_links;
+        self.settings = board.settings;
+        self.threads = board.threads;
+    }
+}
+
+impl From<v8::Object> for Object {
+    fn from(object: v8::Object) -> Self {
+        match object {
+            v8::Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            } => Object::Image {
+                x,
+                y,
+                texture_id,
+                position_timestamp,
+            },
+            v8::Object::Line => Object::Line,
+            v8::Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            } => Object::Deck {
+                x,
+                y,
+                back,
+                cards,
+                face_up,
+                position_timestamp,
+            },
+            v8::Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            } => Object::Card {
+                x,
+                y,
+                face,
+                back,
+                face_up,
+                position_timestamp,
+            },
+            v8::Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            } => Object::Hand {
+                x,
+                y,
+                width,
+                height,
+                owner,
+                position_timestamp,
+            },
+        }
+    }
+}
+
+impl From<BoardV8> for BoardV9 {
+    fn from(board: BoardV8) -> Self {
+        Self {
+            textures: board.textures,
+            objects: board
+                .objects
+                .into_iter()
+                .map(|(id, object)| (id, object.into()))
+                .collect(),
+            roles: board.roles,
+            share_links: board.share_links,
+            settings: board.settings,
+            threads: board.threads,
+        }
+    }
+}
+
+impl From<EventV8> for EventV9 {
+    fn from(event: EventV8) -> Self {
+        match event {
+            EventV8::NewImage { id, x, y, texture } => EventV9::NewImage { id, x, y, texture },
+            EventV8::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            } => EventV9::Move {
+                id,
+                x,
+                y,
+                timestamp,
+            },
+            EventV8::SetRole { user, role } => EventV9::SetRole { user, role },
+            EventV8::ShareLinkCreated { id, link } => EventV9::ShareLinkCreated { id, link },
+            EventV8::ShareLinkRevoked { id } => EventV9::ShareLinkRevoked { id },
+            EventV8::SetSettings(settings) => EventV9::SetSettings(settings),
+            EventV8::CommentAdded { object, comment } => EventV9::CommentAdded { object, comment },
+            EventV8::ThreadResolved { object, resolved } => {
+                EventV9::ThreadResolved { object, resolved }
+            }
+            EventV8::CommentDeleted { object, comment } => {
+                EventV9::CommentDeleted { object, comment }
+            }
+            EventV8::ObjectSet { id, object } => EventV9::ObjectSet {
+                id,
+                object: object.into(),
+            },
+            EventV8::ObjectRemoved { id } => EventV9::ObjectRemoved { id },
+        }
+    }
+}
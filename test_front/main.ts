@@ -3,9 +3,12 @@
 import * as _socket from 'commons/socket'
 import * as _list from 'commons/list'
 import * as _client_info from 'commons/client_info'
+import * as _chat from 'commons/chat'
 import * as _canvas from './ts/canvas'
+import * as _comments from './ts/comments'
 import * as _paste from './ts/paste'
 import * as _mouse from './ts/mouse'
+import * as _keyboard from './ts/keyboard'
 
 declare var socket;
 socket = _socket;
@@ -16,11 +19,20 @@ list = _list;
 declare var client_info;
 client_info = _client_info;
 
+declare var chat;
+chat = _chat;
+
 declare var canvas;
 canvas = _canvas;
 
+declare var comments;
+comments = _comments;
+
 declare var paste;
 paste = _paste;
 
 declare var mouse;
-mouse = _mouse;
\ No newline at end of file
+mouse = _mouse;
+
+declare var keyboard;
+keyboard = _keyboard;
\ No newline at end of file
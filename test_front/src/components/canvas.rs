@@ -1,3 +1,5 @@
+use test_back::board::settings::GridKind;
+
 use crate::image_atlas::BoundingBox;
 
 pub struct Canvas {
@@ -30,6 +32,23 @@ impl Canvas {
         self.canvas.draw();
     }
 
+    pub fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    pub fn set_background(&mut self, rgb: u32) {
+        self.canvas.setBackground(rgb);
+    }
+
+    pub fn set_grid(&mut self, grid: GridKind, size: f32) {
+        let grid = match grid {
+            GridKind::None => 0,
+            GridKind::Square => 1,
+            GridKind::Hex => 2,
+        };
+        self.canvas.setGrid(grid, size);
+    }
+
     pub fn set_transform(&mut self, x: f32, y: f32, scale: f32) {
         self.canvas.setTransform(x, y, scale);
     }
@@ -63,6 +82,15 @@ mod ts {
         #[wasm_bindgen(method)]
         pub fn draw(this: &Canvas);
 
+        #[wasm_bindgen(method)]
+        pub fn clear(this: &Canvas);
+
+        #[wasm_bindgen(method)]
+        pub fn setBackground(this: &Canvas, rgb: u32);
+
+        #[wasm_bindgen(method)]
+        pub fn setGrid(this: &Canvas, grid: u32, size: f32);
+
         #[wasm_bindgen(method)]
         pub fn setTransform(this: &Canvas, x: f32, y: f32, scale: f32);
 
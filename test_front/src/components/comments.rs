@@ -0,0 +1,118 @@
+use frontend_commons::command::{Command, CommandConsumer};
+use test_back::board::comments::Thread;
+
+#[derive(Debug)]
+pub enum CommentsCommand {
+    Submit(String),
+    Resolve(bool),
+    /// Position of the comment in the thread shown.
+    Delete(u32),
+}
+
+/// Side panel with the comment thread of the selected object.
+pub struct Comments {
+    comments: ts::Comments,
+}
+
+impl Comments {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, CommentsCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            comments: ts::Comments::new(ts::CommentsBackend::new(Box::new(consumer))),
+        }
+    }
+
+    pub fn show(&mut self, thread: Option<&Thread>) {
+        let count = thread.map_or(0, |thread| thread.comments.len());
+        self.comments.clear(&format!("Comments ({count})"), true);
+        let Some(thread) = thread else {
+            self.comments.setResolved(false);
+            return;
+        };
+        self.comments.setResolved(thread.resolved);
+        for comment in &thread.comments {
+            self.comments.addComment(
+                &comment.author_name,
+                comment.created_at as f64,
+                &comment.text,
+            );
+        }
+    }
+
+    pub fn hide(&mut self) {
+        self.comments.clear("Select an object to discuss it", false);
+    }
+}
+
+impl<State, Cmd> ts::CommentsObserver for CommandConsumer<State, CommentsCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_submit(&mut self, text: String) {
+        self.consume(CommentsCommand::Submit(text));
+    }
+
+    fn on_resolve(&mut self, resolved: bool) {
+        self.consume(CommentsCommand::Resolve(resolved));
+    }
+
+    fn on_delete(&mut self, index: u32) {
+        self.consume(CommentsCommand::Delete(index));
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait CommentsObserver {
+        fn on_submit(&mut self, text: String);
+        fn on_resolve(&mut self, resolved: bool);
+        fn on_delete(&mut self, index: u32);
+    }
+
+    #[wasm_bindgen]
+    pub struct CommentsBackend {
+        observer: Box<dyn CommentsObserver>,
+    }
+
+    impl CommentsBackend {
+        pub fn new(observer: Box<dyn CommentsObserver>) -> CommentsBackend {
+            CommentsBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl CommentsBackend {
+        pub fn on_submit(&mut self, text: String) {
+            self.observer.on_submit(text);
+        }
+
+        pub fn on_resolve(&mut self, resolved: bool) {
+            self.observer.on_resolve(resolved);
+        }
+
+        pub fn on_delete(&mut self, index: u32) {
+            self.observer.on_delete(index);
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = comments)]
+    unsafe extern "C" {
+        pub type Comments;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: CommentsBackend) -> Comments;
+
+        #[wasm_bindgen(method)]
+        pub fn clear(this: &Comments, title: &str, can_comment: bool);
+
+        #[wasm_bindgen(method)]
+        pub fn setResolved(this: &Comments, resolved: bool);
+
+        #[wasm_bindgen(method)]
+        pub fn addComment(this: &Comments, author: &str, created_at: f64, text: &str);
+    }
+}
@@ -0,0 +1,65 @@
+use frontend_commons::command::{Command, CommandConsumer};
+
+#[derive(Debug, Clone)]
+pub enum KeyboardCommand {
+    Down { key: String },
+}
+
+pub struct Keyboard {
+    _keyboard: ts::Keyboard,
+}
+
+impl Keyboard {
+    pub fn new<State, Cmd>(consumer: CommandConsumer<State, KeyboardCommand, Cmd>) -> Self
+    where
+        Cmd: Command<State> + 'static,
+        State: 'static,
+    {
+        Self {
+            _keyboard: ts::Keyboard::new(ts::KeyboardBackend::new(Box::new(consumer))),
+        }
+    }
+}
+
+impl<State, Cmd> ts::KeyboardObserver for CommandConsumer<State, KeyboardCommand, Cmd>
+where
+    Cmd: Command<State>,
+{
+    fn on_down(&mut self, key: String) {
+        self.consume(KeyboardCommand::Down { key });
+    }
+}
+
+mod ts {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    pub trait KeyboardObserver {
+        fn on_down(&mut self, key: String);
+    }
+
+    #[wasm_bindgen]
+    pub struct KeyboardBackend {
+        observer: Box<dyn KeyboardObserver>,
+    }
+
+    impl KeyboardBackend {
+        pub fn new(observer: Box<dyn KeyboardObserver>) -> KeyboardBackend {
+            KeyboardBackend { observer }
+        }
+    }
+
+    #[wasm_bindgen]
+    impl KeyboardBackend {
+        pub fn on_down(&mut self, key: String) {
+            self.observer.on_down(key);
+        }
+    }
+
+    #[wasm_bindgen(js_namespace = "keyboard")]
+    unsafe extern "C" {
+        pub type Keyboard;
+
+        #[wasm_bindgen(constructor)]
+        pub fn new(backend: KeyboardBackend) -> Keyboard;
+    }
+}
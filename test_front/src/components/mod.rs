@@ -1,3 +1,5 @@
 pub mod canvas;
+pub mod comments;
+pub mod keyboard;
 pub mod mouse;
 pub mod paste;
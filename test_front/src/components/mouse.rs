@@ -1,6 +1,6 @@
 use frontend_commons::command::{Command, CommandConsumer};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum MouseCommand {
     Move { x: f32, y: f32 },
     Down { button: u8, x: f32, y: f32 },
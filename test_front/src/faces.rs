@@ -0,0 +1,94 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+
+const SIZE: u32 = 64;
+const BORDER: u32 = 2;
+const FACE: Rgba<u8> = Rgba([250, 248, 240, 255]);
+const INK: Rgba<u8> = Rgba([60, 60, 60, 255]);
+
+/// 3×5 glyphs, one row of three bits per entry, most significant bit left.
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => [0; 5],
+    }
+}
+
+/// PNG of a die face with the given total printed on it, blank before the
+/// first roll.
+pub fn dice_face(total: Option<i64>) -> Vec<u8> {
+    let mut face = RgbaImage::from_fn(SIZE, SIZE, |x, y| {
+        let edge = x < BORDER || y < BORDER || x >= SIZE - BORDER || y >= SIZE - BORDER;
+        if edge { INK } else { FACE }
+    });
+    if let Some(total) = total {
+        print(&mut face, &total.to_string());
+    }
+    encode(face)
+}
+
+/// PNG of a counter token, a disc with its value on it.
+pub fn counter_face(value: i64) -> Vec<u8> {
+    let radius = SIZE as f64 / 2.0;
+    let mut face = RgbaImage::from_fn(SIZE, SIZE, |x, y| {
+        let distance = (x as f64 + 0.5 - radius).hypot(y as f64 + 0.5 - radius);
+        if distance > radius {
+            Rgba([0, 0, 0, 0])
+        } else if distance > radius - BORDER as f64 {
+            INK
+        } else {
+            FACE
+        }
+    });
+    print(&mut face, &value.to_string());
+    encode(face)
+}
+
+/// Writes `text` in the middle of the face, as large as it fits.
+fn print(face: &mut RgbaImage, text: &str) {
+    let glyphs = text.len() as u32;
+    let width_at = |scale: u32| glyphs * 4 * scale - scale;
+    let scale = (1..=4)
+        .rev()
+        .find(|&scale| width_at(scale) <= SIZE - 6 * BORDER)
+        .unwrap_or(1);
+    let left = SIZE.saturating_sub(width_at(scale)) / 2;
+    let top = (SIZE - 5 * scale) / 2;
+    for (i, c) in text.chars().enumerate() {
+        let glyph_left = left + i as u32 * 4 * scale;
+        for (row, bits) in glyph(c).into_iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = glyph_left + column * scale + dx;
+                        let y = top + row as u32 * scale + dy;
+                        if x < SIZE && y < SIZE {
+                            face.put_pixel(x, y, INK);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn encode(face: RgbaImage) -> Vec<u8> {
+    let mut data = Vec::new();
+    face.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .unwrap();
+    data
+}
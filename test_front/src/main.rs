@@ -1,11 +1,14 @@
 mod camera;
 mod components;
+mod faces;
 mod image_atlas;
 mod textures;
 
 use std::{collections::HashMap, vec};
 
+use backend_commons::client_info::ClientListMessage;
 use frontend_commons::{
+    chat::{Chat, ChatCommand},
     client_info::ClientInfo,
     command::{Command, CommandConsumerGenerator},
     socket::{Socket, SocketCommand},
@@ -13,13 +16,22 @@ use frontend_commons::{
 use log::{Level, debug, info};
 use test_back::{
     ToClient, ToServer,
-    board::local_board::{BoardObserver, LocalBoard},
+    board::{
+        comments::CommentAction,
+        common::{BoardAction, BoardEvent, BoardObject, COUNTER_SIZE, DICE_SIZE, ObjectIdentifier},
+        local_board::{BoardObserver, LocalBoard},
+        settings::{BoardSettings, GridKind},
+        sheet::{Rect, Slices},
+    },
 };
+use web_sys::js_sys::{Date, Math};
 
 use crate::{
     camera::Camera,
     components::{
         canvas::Canvas,
+        comments::{Comments, CommentsCommand},
+        keyboard::{Keyboard, KeyboardCommand},
         mouse::{Mouse, MouseCommand},
         paste::{Paste, PasteCommand},
     },
@@ -31,6 +43,8 @@ struct Graphics {
     canvas: Canvas,
     textures: Textures,
     local_id_counter: u64,
+    zone_texture: Option<u64>,
+    fog_texture: Option<u64>,
 }
 
 impl Graphics {
@@ -39,6 +53,53 @@ impl Graphics {
         self.local_id_counter += 1;
         id
     }
+
+    /// Plain texture stretched over hand zones, generated on first use.
+    fn zone_texture(&mut self) -> u64 {
+        if let Some(id) = self.zone_texture {
+            return id;
+        }
+        let id = self.plain_texture(ZONE_COLOR);
+        self.zone_texture = Some(id);
+        id
+    }
+
+    /// Translucent texture stretched over fog, generated on first use.
+    fn fog_texture(&mut self) -> u64 {
+        if let Some(id) = self.fog_texture {
+            return id;
+        }
+        let id = self.plain_texture(FOG_COLOR);
+        self.fog_texture = Some(id);
+        id
+    }
+
+    fn plain_texture(&mut self, color: [u8; 4]) -> u64 {
+        let plain = image::RgbaImage::from_pixel(8, 8, image::Rgba(color));
+        let mut data = Vec::new();
+        plain
+            .write_to(
+                &mut std::io::Cursor::new(&mut data),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        self.create_texture(data).unwrap()
+    }
+
+    /// Stretches a texture over a region of the board.
+    fn push_region(&mut self, texture: u64, x: f64, y: f64, width: f64, height: f64) {
+        let bounding_box = self.textures.get_bounds(texture);
+        self.canvas.push(
+            bounding_box.atlas_id / 8,
+            get_vertices(
+                &bounding_box,
+                x as f32,
+                y as f32,
+                width as u32,
+                height as u32,
+            ),
+        );
+    }
 }
 
 impl BoardObserver for Graphics {
@@ -46,29 +107,110 @@ impl BoardObserver for Graphics {
         self.textures.insert_texture(data, &mut self.canvas)
     }
 
+    fn texture_size(&self, texture_id: u64) -> (f64, f64) {
+        let (width, height) = image_size(&self.textures.get_bounds(texture_id));
+        (width as f64, height as f64)
+    }
+
     fn new_image(&mut self, x: f64, y: f64, texture_id: u64) -> u64 {
         let bounding_box = self.textures.get_bounds(texture_id);
-        let (width, height) = if bounding_box.rotated {
-            (bounding_box.height, bounding_box.width)
-        } else {
-            (bounding_box.width, bounding_box.height)
-        };
+        let (width, height) = image_size(&bounding_box);
         self.canvas.push(
             bounding_box.atlas_id / 8,
             get_vertices(&bounding_box, x as f32, y as f32, width, height),
         );
         self.next_local_id()
     }
+
+    fn new_zone(&mut self, x: f64, y: f64, width: f64, height: f64) -> u64 {
+        let texture = self.zone_texture();
+        self.push_region(texture, x, y, width, height);
+        self.next_local_id()
+    }
+
+    fn new_fog(&mut self, x: f64, y: f64, width: f64, height: f64) -> u64 {
+        let texture = self.fog_texture();
+        self.push_region(texture, x, y, width, height);
+        self.next_local_id()
+    }
+
+    fn new_dice(&mut self, x: f64, y: f64, total: Option<i64>) -> u64 {
+        let texture = self.create_texture(faces::dice_face(total)).unwrap();
+        let bounding_box = self.textures.get_bounds(texture);
+        let size = DICE_SIZE as u32;
+        self.canvas.push(
+            bounding_box.atlas_id / 8,
+            get_vertices(&bounding_box, x as f32, y as f32, size, size),
+        );
+        self.next_local_id()
+    }
+
+    fn new_counter(&mut self, x: f64, y: f64, value: i64) -> u64 {
+        let texture = self.create_texture(faces::counter_face(value)).unwrap();
+        let bounding_box = self.textures.get_bounds(texture);
+        let size = COUNTER_SIZE as u32;
+        self.canvas.push(
+            bounding_box.atlas_id / 8,
+            get_vertices(&bounding_box, x as f32, y as f32, size, size),
+        );
+        self.next_local_id()
+    }
+
+    fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    fn settings_changed(&mut self, settings: &BoardSettings) {
+        self.canvas.set_background(settings.background);
+        self.canvas
+            .set_grid(settings.grid, settings.grid_size as f32);
+        self.canvas.draw();
+    }
+}
+
+const BACKGROUNDS: [u32; 4] = [0xffffff, 0x35654d, 0x8b5a2b, 0x2f3640];
+
+const ZONE_COLOR: [u8; 4] = [220, 230, 245, 255];
+const HAND_SIZE: (f64, f64) = (600.0, 200.0);
+const FOG_COLOR: [u8; 4] = [40, 40, 50, 200];
+const FOG_SIZE: (f64, f64) = (400.0, 300.0);
+
+const DICE_PRESETS: [&str; 5] = ["d6", "2d6", "d20", "4d6kh3", "d%"];
+
+fn image_size(bounding_box: &BoundingBox) -> (u32, u32) {
+    if bounding_box.rotated {
+        (bounding_box.height, bounding_box.width)
+    } else {
+        (bounding_box.width, bounding_box.height)
+    }
+}
+
+/// An object being dragged with the left mouse button. Only the final
+/// position is sent to the server when the button is released.
+struct Drag {
+    id: ObjectIdentifier,
+    offset_x: f64,
+    offset_y: f64,
+    action: Option<BoardAction>,
 }
 
 struct TestState {
+    session: u64,
     socket: Socket<ToClient, ToServer>,
     clients: HashMap<u64, ClientInfo>,
     paste: Paste,
     mouse: Mouse,
+    keyboard: Keyboard,
+    comments: Comments,
+    chat: Chat,
     camera: Camera,
     graphics: Graphics,
     board: LocalBoard,
+    drag: Option<Drag>,
+    /// Confirmed object whose comments are shown.
+    selected: Option<u64>,
+    /// Index in [`DICE_PRESETS`] of the next dice placed.
+    dice_preset: usize,
 }
 
 #[derive(Debug)]
@@ -76,6 +218,9 @@ enum TestCommand {
     Socket(SocketCommand<ToClient>),
     Paste(PasteCommand),
     Mouse(MouseCommand),
+    Keyboard(KeyboardCommand),
+    Comments(CommentsCommand),
+    Chat(ChatCommand),
 }
 
 fn get_vertices(
@@ -112,6 +257,464 @@ fn get_vertices(
     [v1.clone(), v2, v3.clone(), v1, v3, v4].concat()
 }
 
+impl TestState {
+    fn drag(&mut self, mouse_command: MouseCommand) {
+        match mouse_command {
+            MouseCommand::Down { button: 0, x, y } => {
+                let (x, y) = self.camera.get_world_position(x as f64, y as f64);
+                let id = self.board.object_at(x, y, &self.graphics);
+                self.selected = match id {
+                    Some(ObjectIdentifier::Global(id)) => Some(id),
+                    _ => None,
+                };
+                self.show_comments();
+                let Some(id) = id else {
+                    return;
+                };
+                let Some((object_x, object_y)) = self.board.position(id) else {
+                    return;
+                };
+                self.drag = Some(Drag {
+                    id,
+                    offset_x: object_x - x,
+                    offset_y: object_y - y,
+                    action: None,
+                });
+            }
+            MouseCommand::Move { x, y } => {
+                let Some(drag) = &mut self.drag else {
+                    return;
+                };
+                let (x, y) = self.camera.get_world_position(x as f64, y as f64);
+                drag.action = self.board.move_object(
+                    drag.id,
+                    x + drag.offset_x,
+                    y + drag.offset_y,
+                    &mut self.graphics,
+                );
+                self.graphics.canvas.draw();
+            }
+            MouseCommand::Up { button: 0, x, y } => {
+                let Some(Drag {
+                    id,
+                    action: Some(action),
+                    ..
+                }) = self.drag.take()
+                else {
+                    return;
+                };
+                self.socket.send(ToServer::BoardAction(action));
+                let (x, y) = self.camera.get_world_position(x as f64, y as f64);
+                let object = self.board.object(id);
+                let to_deck = matches!(object, Some(BoardObject::Image(_) | BoardObject::Card(_)));
+                let to_stack =
+                    matches!(object, Some(BoardObject::Image(_) | BoardObject::Stack(_)));
+                let action = if let Some(deck) = self.board.deck_at(x, y, &self.graphics)
+                    && to_deck
+                {
+                    BoardAction::AddToDeck { deck, object: id }
+                } else if let Some(target) = self.board.stack_target_at(x, y, id, &self.graphics)
+                    && to_stack
+                {
+                    BoardAction::StackOnto { target, object: id }
+                } else {
+                    return;
+                };
+                self.socket.send(ToServer::BoardAction(action));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl TestState {
+    fn show_comments(&mut self) {
+        match self.selected {
+            Some(id) => self
+                .comments
+                .show(self.board.thread(ObjectIdentifier::Global(id))),
+            None => self.comments.hide(),
+        }
+    }
+
+    fn comment(&mut self, command: CommentsCommand) {
+        let Some(object) = self.selected else {
+            return;
+        };
+        let action = match command {
+            CommentsCommand::Submit(text) => CommentAction::Add { object, text },
+            CommentsCommand::Resolve(resolved) => CommentAction::SetResolved { object, resolved },
+            CommentsCommand::Delete(index) => {
+                let Some(comment) = self
+                    .board
+                    .thread(ObjectIdentifier::Global(object))
+                    .and_then(|thread| thread.comments.get(index as usize))
+                else {
+                    return;
+                };
+                CommentAction::Delete {
+                    object,
+                    comment: comment.id,
+                }
+            }
+        };
+        self.socket.send(ToServer::CommentAction(action));
+    }
+
+    /// Sends a chat message, unless it is a command such as `/slice`.
+    /// Seats are numbered from 1 in commands.
+    fn send_chat(&mut self, text: String) {
+        if let Some(args) = text.strip_prefix("/slice ") {
+            return self.slice_selected(args);
+        }
+        let mut words = text.split_whitespace();
+        let argument = |word: Option<&str>| word.and_then(|word| word.parse::<usize>().ok());
+        let message = match words.next() {
+            Some("/seats") => argument(words.next()).map(ToServer::SetSeats),
+            Some("/sit") => argument(words.next())
+                .and_then(|seat| seat.checked_sub(1))
+                .map(ToServer::Sit),
+            Some("/stand") => Some(ToServer::Stand),
+            Some("/end") => Some(ToServer::EndTurn),
+            _ => Some(ToServer::Chat(text)),
+        };
+        if let Some(message) = message {
+            self.socket.send(message);
+        }
+    }
+
+    fn on_client_list_message(&mut self, message: ClientListMessage) {
+        match message {
+            ClientListMessage::Joined(data) => {
+                if let Some(previous) = self.clients.insert(data.id, ClientInfo::new(data)) {
+                    previous.remove();
+                }
+            }
+            ClientListMessage::Quit(id) => {
+                if let Some(client) = self.clients.remove(&id) {
+                    client.remove();
+                }
+            }
+            ClientListMessage::Update(id, message) => {
+                if let Some(client) = self.clients.get_mut(&id) {
+                    client.consume(message);
+                }
+            }
+        }
+    }
+
+    /// Posts whose turn it is in the chat when it changes.
+    fn announce_turn(&mut self, previous: Option<u64>) {
+        let Some(user) = self.board.seating().to_move() else {
+            return;
+        };
+        if previous == Some(user) {
+            return;
+        }
+        let name = self
+            .clients
+            .get(&user)
+            .map(|client| client.name().to_owned())
+            .unwrap_or_else(|| format!("#{user}"));
+        self.chat.notice(&name, Date::now() as u64, "has the turn");
+    }
+
+    /// Slices the selected image as described by `args`: a grid such as
+    /// `10x7`, or rectangles such as `0,0,64,64 64,0,64,64`, followed by
+    /// `deck` to make a deck of the pieces.
+    fn slice_selected(&mut self, args: &str) {
+        let Some(id) = self.selected else {
+            return;
+        };
+        if !matches!(
+            self.board.object(ObjectIdentifier::Global(id)),
+            Some(BoardObject::Image(_))
+        ) {
+            return;
+        }
+        let mut words: Vec<_> = args.split_whitespace().collect();
+        let into_deck = words.last() == Some(&"deck");
+        if into_deck {
+            words.pop();
+        }
+        let grid = match words[..] {
+            [grid] => grid.split_once('x').and_then(|(columns, rows)| {
+                Some(Slices::Grid {
+                    columns: columns.parse().ok()?,
+                    rows: rows.parse().ok()?,
+                })
+            }),
+            _ => None,
+        };
+        let slices = match grid {
+            Some(grid) => grid,
+            None => {
+                let rects: Option<Vec<_>> = words
+                    .iter()
+                    .map(|word| {
+                        let numbers: Vec<u32> = word
+                            .split(',')
+                            .map(|n| n.parse().ok())
+                            .collect::<Option<_>>()?;
+                        let [x, y, width, height] = numbers[..] else {
+                            return None;
+                        };
+                        Some(Rect {
+                            x,
+                            y,
+                            width,
+                            height,
+                        })
+                    })
+                    .collect();
+                match rects {
+                    Some(rects) => Slices::Rects(rects),
+                    None => return,
+                }
+            }
+        };
+        let action = BoardAction::SliceSheet {
+            image: ObjectIdentifier::Global(id),
+            slices,
+            into_deck,
+        };
+        self.socket.send(ToServer::BoardAction(action));
+    }
+
+    fn on_key(&mut self, key: &str) {
+        if !self.object_key(key)
+            && !self.hand_key(key)
+            && !self.dice_key(key)
+            && !self.counter_key(key)
+            && !self.fog_key(key)
+        {
+            self.change_settings(key);
+        }
+    }
+
+    /// `n` places dice rolling the next of [`DICE_PRESETS`] at the cursor and
+    /// `t` rolls the dice under it.
+    fn dice_key(&mut self, key: &str) -> bool {
+        let (x, y) = self.camera.get_mouse_position();
+        match key {
+            "n" => {
+                let notation = DICE_PRESETS[self.dice_preset].to_owned();
+                self.dice_preset = (self.dice_preset + 1) % DICE_PRESETS.len();
+                let action = BoardAction::NewDice { x, y, notation };
+                self.socket.send(ToServer::BoardAction(action));
+            }
+            "t" => {
+                let Some(ObjectIdentifier::Global(id)) = self.board.object_at(x, y, &self.graphics)
+                else {
+                    return false;
+                };
+                if !matches!(
+                    self.board.object(ObjectIdentifier::Global(id)),
+                    Some(BoardObject::Dice(_))
+                ) {
+                    return false;
+                }
+                self.socket.send(ToServer::Roll(id));
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// `c` places a counter at the cursor, `+` and `-` change the counter
+    /// under it by one and `0` resets it.
+    fn counter_key(&mut self, key: &str) -> bool {
+        let (x, y) = self.camera.get_mouse_position();
+        if key == "c" {
+            let action = BoardAction::NewCounter { x, y, value: 0 };
+            self.socket.send(ToServer::BoardAction(action));
+            return true;
+        }
+        let Some(ObjectIdentifier::Global(id)) = self.board.object_at(x, y, &self.graphics) else {
+            return false;
+        };
+        if !matches!(
+            self.board.object(ObjectIdentifier::Global(id)),
+            Some(BoardObject::Counter(_))
+        ) {
+            return false;
+        }
+        let action = match key {
+            "+" | "=" => BoardAction::Increment { id, delta: 1 },
+            "-" => BoardAction::Increment { id, delta: -1 },
+            "0" => BoardAction::SetCounter { id, value: 0 },
+            _ => return false,
+        };
+        self.socket.send(ToServer::BoardAction(action));
+        true
+    }
+
+    /// Game master keys: `o` lays fog at the cursor, `u` clears the fog under
+    /// it and `i` hides the object under it from players or reveals it.
+    fn fog_key(&mut self, key: &str) -> bool {
+        let (x, y) = self.camera.get_mouse_position();
+        let action = match key {
+            "o" => BoardAction::NewFog {
+                x,
+                y,
+                width: FOG_SIZE.0,
+                height: FOG_SIZE.1,
+            },
+            "u" => {
+                let Some(id) = self.board.fog_at(x, y, &self.graphics) else {
+                    return false;
+                };
+                BoardAction::RemoveFog { id }
+            }
+            "i" => {
+                let Some(ObjectIdentifier::Global(id)) = self.board.object_at(x, y, &self.graphics)
+                else {
+                    return false;
+                };
+                BoardAction::SetHidden {
+                    id,
+                    hidden: !self.board.is_hidden(id),
+                }
+            }
+            _ => return false,
+        };
+        self.socket.send(ToServer::BoardAction(action));
+        true
+    }
+
+    /// `h` opens a hand zone of your own at the cursor and `x` removes the
+    /// hand under it.
+    fn hand_key(&mut self, key: &str) -> bool {
+        let (x, y) = self.camera.get_mouse_position();
+        let action = match key {
+            "h" => BoardAction::NewHand {
+                x,
+                y,
+                width: HAND_SIZE.0,
+                height: HAND_SIZE.1,
+            },
+            "x" => {
+                let Some(ObjectIdentifier::Global(id)) = self.board.object_at(x, y, &self.graphics)
+                else {
+                    return false;
+                };
+                if !matches!(
+                    self.board.object(ObjectIdentifier::Global(id)),
+                    Some(BoardObject::Hand(_))
+                ) {
+                    return false;
+                }
+                BoardAction::RemoveHand { id }
+            }
+            _ => return false,
+        };
+        self.socket.send(ToServer::BoardAction(action));
+        true
+    }
+
+    /// Keys acting on the object under the cursor: `d` turns an image into a
+    /// deck, `f` flips, `r` shuffles, `w` draws the top card of a deck and `e`
+    /// deals a card to each of four places below it. On a stack, `w` takes the
+    /// top image, `v` splits off its top half and `a` spreads it out.
+    fn object_key(&mut self, key: &str) -> bool {
+        let (x, y) = self.camera.get_mouse_position();
+        let Some(id) = self.board.object_at(x, y, &self.graphics) else {
+            return false;
+        };
+        let object = self.board.object(id);
+        let action = match (key, object, id) {
+            ("d", Some(BoardObject::Image(_)), _) => BoardAction::MakeDeck { image: id },
+            (
+                "f",
+                Some(BoardObject::Deck(_) | BoardObject::Card(_)),
+                ObjectIdentifier::Global(id),
+            ) => BoardAction::Flip { id },
+            ("r", Some(BoardObject::Deck(_)), ObjectIdentifier::Global(deck)) => {
+                BoardAction::Shuffle { deck }
+            }
+            ("w" | "e", Some(BoardObject::Deck(_)), ObjectIdentifier::Global(deck)) => {
+                let (Some((deck_x, deck_y)), Some((width, height))) =
+                    (self.board.position(id), self.board.size(id, &self.graphics))
+                else {
+                    return false;
+                };
+                if key == "w" {
+                    BoardAction::Draw {
+                        deck,
+                        x: deck_x + width + 16.0,
+                        y: deck_y,
+                    }
+                } else {
+                    let positions = (0..4)
+                        .map(|i| (deck_x + i as f64 * (width + 16.0), deck_y + height + 16.0))
+                        .collect();
+                    BoardAction::Deal {
+                        deck,
+                        count: 1,
+                        positions,
+                    }
+                }
+            }
+            ("w" | "v" | "a", Some(BoardObject::Stack(stack)), ObjectIdentifier::Global(id)) => {
+                let count = stack.items.len() as u32;
+                let (Some((stack_x, stack_y)), Some((width, _))) = (
+                    self.board.position(ObjectIdentifier::Global(id)),
+                    self.board
+                        .size(ObjectIdentifier::Global(id), &self.graphics),
+                ) else {
+                    return false;
+                };
+                let (x, y) = (stack_x + width + 16.0, stack_y);
+                match key {
+                    "w" => BoardAction::TakeFromStack { stack: id, x, y },
+                    "v" => BoardAction::SplitStack {
+                        stack: id,
+                        count: count / 2,
+                        x,
+                        y,
+                    },
+                    _ => BoardAction::SpreadStack {
+                        stack: id,
+                        dx: width + 16.0,
+                        dy: 0.0,
+                    },
+                }
+            }
+            _ => return false,
+        };
+        self.socket.send(ToServer::BoardAction(action));
+        true
+    }
+
+    /// `g` cycles the grid, `s` toggles snapping, `[` and `]` resize the grid
+    /// and `b` cycles the background.
+    fn change_settings(&mut self, key: &str) {
+        let mut settings = self.board.settings();
+        match key {
+            "g" => {
+                settings.grid = match settings.grid {
+                    GridKind::None => GridKind::Square,
+                    GridKind::Square => GridKind::Hex,
+                    GridKind::Hex => GridKind::None,
+                }
+            }
+            "s" => settings.snap_to_grid = !settings.snap_to_grid,
+            "[" => settings.grid_size = (settings.grid_size / 2.0).max(8.0),
+            "]" => settings.grid_size = (settings.grid_size * 2.0).min(1024.0),
+            "b" => {
+                let next = BACKGROUNDS
+                    .iter()
+                    .position(|&color| color == settings.background)
+                    .map_or(0, |i| (i + 1) % BACKGROUNDS.len());
+                settings.background = BACKGROUNDS[next];
+            }
+            _ => return,
+        }
+        let action = self.board.set_settings(settings, &mut self.graphics);
+        self.socket.send(ToServer::BoardAction(action));
+    }
+}
+
 impl Command<TestState> for TestCommand {
     fn apply(self, state: &mut TestState) {
         match self {
@@ -124,6 +727,7 @@ impl Command<TestState> for TestCommand {
                 state.graphics.canvas.draw();
             }
             TestCommand::Mouse(mouse_command) => {
+                state.drag(mouse_command);
                 if state.camera.update(mouse_command) {
                     state.graphics.canvas.set_transform(
                         state.camera.get_x() as f32,
@@ -133,16 +737,43 @@ impl Command<TestState> for TestCommand {
                     state.graphics.canvas.draw();
                 }
             }
+            TestCommand::Comments(command) => state.comment(command),
+            TestCommand::Chat(ChatCommand::Send(text)) => state.send_chat(text),
+            TestCommand::Keyboard(KeyboardCommand::Down { key }) => state.on_key(&key),
+            TestCommand::Socket(SocketCommand::Open) => {
+                state.socket.send(ToServer::Session(state.session));
+                for action in state.board.pending_actions() {
+                    state.socket.send(ToServer::BoardAction(action));
+                }
+            }
             TestCommand::Socket(SocketCommand::Data(event)) => {
                 debug!("{:?}", event);
                 match event {
                     ToClient::BoardEvent(board_event) => {
+                        if let BoardEvent::Rolled(roll) = &board_event {
+                            let text = format!("rolled {}", roll.describe());
+                            state.chat.notice(&roll.roller_name, roll.rolled_at, &text);
+                        }
+                        let seating = matches!(board_event, BoardEvent::SeatingChanged(_));
+                        let to_move = state.board.seating().to_move();
                         state.board.apply_event(board_event, &mut state.graphics);
                         state.graphics.canvas.draw();
+                        if seating {
+                            state.announce_turn(to_move);
+                        }
                     }
                     ToClient::NewBoard(board) => {
-                        state.board.load(board, &mut state.graphics);
+                        for action in state.board.load(*board, &mut state.graphics) {
+                            state.socket.send(ToServer::BoardAction(action));
+                        }
                         state.graphics.canvas.draw();
+                        state.show_comments();
+                    }
+                    ToClient::ChatListMessage(message) => state.chat.consume(message),
+                    ToClient::ClientListMessage(message) => state.on_client_list_message(message),
+                    ToClient::CommentEvent(comment_event) => {
+                        state.board.apply_comment_event(comment_event);
+                        state.show_comments();
                     }
                     _ => {}
                 }
@@ -164,6 +795,24 @@ impl From<PasteCommand> for TestCommand {
     }
 }
 
+impl From<ChatCommand> for TestCommand {
+    fn from(value: ChatCommand) -> Self {
+        TestCommand::Chat(value)
+    }
+}
+
+impl From<CommentsCommand> for TestCommand {
+    fn from(value: CommentsCommand) -> Self {
+        TestCommand::Comments(value)
+    }
+}
+
+impl From<KeyboardCommand> for TestCommand {
+    fn from(value: KeyboardCommand) -> Self {
+        TestCommand::Keyboard(value)
+    }
+}
+
 impl From<MouseCommand> for TestCommand {
     fn from(value: MouseCommand) -> Self {
         TestCommand::Mouse(value)
@@ -176,18 +825,28 @@ fn main() {
     info!("Hello, world!");
     let mut consumer_generator: CommandConsumerGenerator<_, TestCommand> =
         CommandConsumerGenerator::new();
+    let session = (Math::random() * (1u64 << 53) as f64) as u64;
     let state = TestState {
+        session,
         socket: Socket::new(consumer_generator.make_consumer(), "socket"),
         clients: HashMap::new(),
         graphics: Graphics {
             canvas: Canvas::new(),
             textures: Textures::new(),
             local_id_counter: 0,
+            zone_texture: None,
+            fog_texture: None,
         },
         paste: Paste::new(consumer_generator.make_consumer()),
         mouse: Mouse::new(consumer_generator.make_consumer()),
+        keyboard: Keyboard::new(consumer_generator.make_consumer()),
+        comments: Comments::new(consumer_generator.make_consumer()),
+        chat: Chat::new(consumer_generator.make_consumer()),
         camera: Camera::new(),
-        board: LocalBoard::new(),
+        board: LocalBoard::new(session),
+        drag: None,
+        selected: None,
+        dice_preset: 0,
     };
     consumer_generator.activate(state);
 }
@@ -1,5 +1,5 @@
 import { WebGLFloatVector } from "./glvector";
-import { createProgram, createShader, FRAGMENT_SHADER_SOURCE, init, VERTEX_SHADER_SOURCE } from "./shaders";
+import { createProgram, createShader, FRAGMENT_SHADER_SOURCE, GRID_FRAGMENT_SHADER_SOURCE, GRID_VERTEX_SHADER_SOURCE, init, VERTEX_SHADER_SOURCE } from "./shaders";
 
 export class Canvas {
     private element: HTMLCanvasElement;
@@ -11,6 +11,14 @@ export class Canvas {
     private texCoordAttributeLocation: number
     private atlasCoordAttributeLocation: number;
     private atlases: WebGLTexture[] = [];
+    private program: WebGLProgram;
+    private vao: WebGLVertexArrayObject;
+    private gridProgram: WebGLProgram;
+    private gridVao: WebGLVertexArrayObject;
+    private camera = [0, 0, 1];
+    private background = [1, 1, 1];
+    private grid = 0;
+    private gridSize = 64;
 
     constructor() {
         this.element = document.createElement("canvas");
@@ -23,14 +31,19 @@ export class Canvas {
         };
         const vertexShader = createShader(this.gl, this.gl.VERTEX_SHADER, VERTEX_SHADER_SOURCE);
         const fragmentShader = createShader(this.gl, this.gl.FRAGMENT_SHADER, FRAGMENT_SHADER_SOURCE);
+        const gridVertexShader = createShader(this.gl, this.gl.VERTEX_SHADER, GRID_VERTEX_SHADER_SOURCE);
+        const gridFragmentShader = createShader(this.gl, this.gl.FRAGMENT_SHADER, GRID_FRAGMENT_SHADER_SOURCE);
+        this.gridProgram = createProgram(this.gl, gridVertexShader, gridFragmentShader);
+        this.gridVao = this.gl.createVertexArray();
         const program = createProgram(this.gl, vertexShader, fragmentShader);
+        this.program = program;
         this.gl.useProgram(program);
         this.positionAttributeLocation = this.gl.getAttribLocation(program, "a_position");
         this.texCoordAttributeLocation = this.gl.getAttribLocation(program, "a_texCoord");
         this.atlasCoordAttributeLocation = this.gl.getAttribLocation(program, "a_atlas");
         this.image_groups = [];
-        const vao = this.gl.createVertexArray();
-        this.gl.bindVertexArray(vao);
+        this.vao = this.gl.createVertexArray();
+        this.gl.bindVertexArray(this.vao);
         this.transformLocation = this.gl.getUniformLocation(this.gl.getParameter(this.gl.CURRENT_PROGRAM), "u_transform");
         this.aspectLocation = this.gl.getUniformLocation(this.gl.getParameter(this.gl.CURRENT_PROGRAM), "u_aspect");
         this.setTransform(0.0, 0.0, 1);
@@ -68,6 +81,7 @@ export class Canvas {
     }
 
     setTransform(x: number, y: number, scale: number) {
+        this.camera = [x, y, scale];
         this.gl.uniformMatrix3fv(this.transformLocation, false, new Float32Array([
             scale, 0, 0,
             0, scale, 0,
@@ -94,6 +108,40 @@ export class Canvas {
         vec.push(new Float32Array(vertices));
     }
 
+    clear() {
+        for (const vec of this.image_groups) {
+            vec.clear();
+        }
+    }
+
+    setBackground(rgb: number) {
+        this.background = [(rgb >> 16) & 0xff, (rgb >> 8) & 0xff, rgb & 0xff].map(c => c / 255);
+    }
+
+    // 0 hides the grid, 1 draws squares and 2 hexagons.
+    setGrid(grid: number, size: number) {
+        this.grid = grid;
+        this.gridSize = size;
+    }
+
+    drawGrid() {
+        const [r, g, b] = this.background;
+        const line = 0.299 * r + 0.587 * g + 0.114 * b > 0.5 ? [0, 0, 0, 0.25] : [1, 1, 1, 0.25];
+        this.gl.useProgram(this.gridProgram);
+        this.gl.bindVertexArray(this.gridVao);
+        this.gl.uniform3fv(this.gl.getUniformLocation(this.gridProgram, "u_camera"), new Float32Array(this.camera));
+        this.gl.uniform2fv(this.gl.getUniformLocation(this.gridProgram, "u_aspect"), new Float32Array([this.element.width, this.element.height]));
+        this.gl.uniform1i(this.gl.getUniformLocation(this.gridProgram, "u_grid"), this.grid);
+        this.gl.uniform1f(this.gl.getUniformLocation(this.gridProgram, "u_size"), this.gridSize);
+        this.gl.uniform4fv(this.gl.getUniformLocation(this.gridProgram, "u_color"), new Float32Array(line));
+        this.gl.enable(this.gl.BLEND);
+        this.gl.blendFunc(this.gl.SRC_ALPHA, this.gl.ONE_MINUS_SRC_ALPHA);
+        this.gl.drawArrays(this.gl.TRIANGLES, 0, 3);
+        this.gl.disable(this.gl.BLEND);
+        this.gl.useProgram(this.program);
+        this.gl.bindVertexArray(this.vao);
+    }
+
     bindTextures(group: number) {
         for (let i = 0; i < 8; i++) {
             this.gl.activeTexture(this.gl.TEXTURE0 + i);
@@ -102,8 +150,16 @@ export class Canvas {
     }
 
     draw() {
-        this.gl.clearColor(0, 0, 0, 0);
+        const [r, g, b] = this.background;
+        this.gl.clearColor(r, g, b, 1);
         this.gl.clear(this.gl.COLOR_BUFFER_BIT);
+        if (this.grid !== 0) {
+            this.drawGrid();
+        }
+        // Images may be translucent, such as fog over what the game master
+        // hides from players.
+        this.gl.enable(this.gl.BLEND);
+        this.gl.blendFunc(this.gl.SRC_ALPHA, this.gl.ONE_MINUS_SRC_ALPHA);
         for (let group_id = 0; group_id < this.image_groups.length; group_id++) {
             let vec = this.image_groups[group_id];
             this.gl.bindBuffer(this.gl.ARRAY_BUFFER, vec.getBuffer());
@@ -116,5 +172,6 @@ export class Canvas {
             this.bindTextures(group_id);
             this.gl.drawArrays(this.gl.TRIANGLES, 0, vec.size() / 5);
         }
+        this.gl.disable(this.gl.BLEND);
     }
 }
@@ -0,0 +1,79 @@
+import JSX from "commons/createElement";
+
+export interface CommentsObserver {
+    on_submit(text: string): void;
+    on_resolve(resolved: boolean): void;
+    on_delete(index: number): void;
+}
+
+export class Comments {
+    private element: HTMLElement;
+    private title: HTMLElement;
+    private list: HTMLElement;
+    private resolved: HTMLInputElement;
+    private form: HTMLElement;
+    private input: HTMLTextAreaElement;
+    private observer: CommentsObserver;
+
+    public constructor(observer: CommentsObserver) {
+        this.observer = observer;
+        this.title = <h3></h3>;
+        this.list = <div></div>;
+        this.resolved = <input type="checkbox"></input> as HTMLInputElement;
+        this.resolved.addEventListener("change", _ => observer.on_resolve(this.resolved.checked));
+        this.input = <textarea rows={3} style="width: 100%; box-sizing: border-box"></textarea> as HTMLTextAreaElement;
+        const send = <button>Comment</button>;
+        send.addEventListener("click", _ => this.submit());
+        this.input.addEventListener("keydown", (event: KeyboardEvent) => {
+            if (event.key === "Enter" && !event.shiftKey) {
+                event.preventDefault();
+                this.submit();
+            }
+        });
+        this.form = <div>
+            <label>{this.resolved} Resolved</label>
+            {this.input}
+            {send}
+        </div>;
+        this.element = <div style="position: fixed; top: 0; right: 0; width: 280px; max-height: 100%; overflow-y: auto; padding: 8px; background: rgba(255, 255, 255, 0.9); font-family: sans-serif; font-size: 14px">
+            {this.title}
+            {this.list}
+            {this.form}
+        </div>;
+        // The board listens on the whole document, clicks in the panel are not
+        // meant for it.
+        for (const type of ["mousedown", "mouseup", "wheel"]) {
+            this.element.addEventListener(type, event => event.stopPropagation());
+        }
+        document.body.appendChild(this.element);
+    }
+
+    private submit() {
+        const text = this.input.value.trim();
+        if (text.length > 0) {
+            this.observer.on_submit(text);
+        }
+        this.input.value = "";
+    }
+
+    public clear(title: string, can_comment: boolean) {
+        this.title.innerText = title;
+        this.list.replaceChildren();
+        this.form.style.display = can_comment ? "" : "none";
+    }
+
+    public setResolved(resolved: boolean) {
+        this.resolved.checked = resolved;
+    }
+
+    public addComment(author: string, created_at: number, text: string) {
+        const index = this.list.childElementCount;
+        const remove = <button style="float: right">✕</button>;
+        remove.addEventListener("click", _ => this.observer.on_delete(index));
+        this.list.appendChild(<div style="margin-bottom: 8px">
+            {remove}
+            <b>{author}</b> <small>{new Date(created_at).toLocaleString()}</small>
+            <div style="white-space: pre-wrap">{text}</div>
+        </div>);
+    }
+}
@@ -53,6 +53,13 @@ export class WebGLFloatVector {
         )
     }
 
+    /**
+     * Drops all floats, keeping the allocated capacity.
+     */
+    clear(): void {
+        this.length = 0
+    }
+
     size(): number {
         return this.length
     }
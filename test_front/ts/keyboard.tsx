@@ -0,0 +1,18 @@
+export interface KeyboardObserver {
+    on_down(key: string): void;
+}
+
+export class Keyboard {
+    public constructor(observer: KeyboardObserver) {
+        document.addEventListener("keydown", (event: KeyboardEvent) => {
+            const target = event.target as HTMLElement;
+            if (target.isContentEditable || target instanceof HTMLInputElement || target instanceof HTMLTextAreaElement) {
+                return;
+            }
+            if (event.ctrlKey || event.metaKey || event.altKey) {
+                return;
+            }
+            observer.on_down(event.key);
+        });
+    }
+}
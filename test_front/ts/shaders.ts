@@ -54,6 +54,45 @@ export const FRAGMENT_SHADER_SOURCE = `#version 300 es
     }
 `;
 
+export const GRID_VERTEX_SHADER_SOURCE = `#version 300 es
+    void main() {
+        vec2 pos = vec2(gl_VertexID == 1 ? 3.0 : -1.0, gl_VertexID == 2 ? 3.0 : -1.0);
+        gl_Position = vec4(pos, 0, 1);
+    }
+`;
+
+// u_grid is 1 for squares and 2 for pointy-top hexagons, whose centres lie on
+// two rectangular lattices offset by half a cell.
+export const GRID_FRAGMENT_SHADER_SOURCE = `#version 300 es
+    precision highp float;
+    uniform vec3 u_camera;
+    uniform vec2 u_aspect;
+    uniform int u_grid;
+    uniform float u_size;
+    uniform vec4 u_color;
+    out vec4 outColor;
+    const vec2 HEX = vec2(1.0, 1.7320508);
+    float hexEdge(vec2 p) {
+        vec2 a = mod(p + HEX * 0.5, HEX) - HEX * 0.5;
+        vec2 b = mod(p, HEX) - HEX * 0.5;
+        vec2 h = abs(dot(a, a) < dot(b, b) ? a : b);
+        return 0.5 - max(dot(h, HEX * 0.5), h.x);
+    }
+    void main() {
+        vec2 screen = vec2(gl_FragCoord.x, u_aspect.y - gl_FragCoord.y);
+        vec2 p = (screen / u_camera.z + u_camera.xy) / u_size;
+        float d;
+        if (u_grid == 1) {
+            vec2 f = abs(fract(p + 0.5) - 0.5);
+            d = min(f.x, f.y);
+        } else {
+            d = hexEdge(p);
+        }
+        float pixels = d * u_size * u_camera.z;
+        outColor = vec4(u_color.rgb, u_color.a * (1.0 - smoothstep(0.5, 1.5, pixels)));
+    }
+`;
+
 export function createShader(gl: WebGL2RenderingContext, type: GLenum, source: string) {
     const shader = gl.createShader(type);
     gl.shaderSource(shader, source);